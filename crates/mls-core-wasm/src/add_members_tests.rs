@@ -0,0 +1,342 @@
+use crate::{
+    commit::process_commit,
+    error::ErrorContext,
+    key_package::{generate_key_package, key_package_ref},
+    messaging::{decrypt_message, encrypt_message},
+    model::{CommitData, GeneratedKeyPackageOutput, MLS_CIPHERSUITE_ID},
+    operations::{add_members, join_group},
+    protocol::{generate_credential, group_state_metadata},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+struct Invitee {
+    credential_bundle: Vec<u8>,
+    credential_private_key: Vec<u8>,
+    key_package: GeneratedKeyPackageOutput,
+}
+
+fn invitee(user_id: &str) -> Invitee {
+    let credential = must(generate_credential(user_id), "generate credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    Invitee {
+        credential_bundle: credential.credential_bundle,
+        credential_private_key: credential.private_key,
+        key_package,
+    }
+}
+
+fn join(group_id: &str, welcome: &[u8], invitee: &Invitee) -> Vec<u8> {
+    must(
+        join_group(
+            group_id,
+            welcome,
+            &invitee.key_package.key_package_ref,
+            &invitee.key_package.private_key,
+            &invitee.credential_bundle,
+            &invitee.credential_private_key,
+        ),
+        "join group",
+    )
+    .state
+}
+
+#[test]
+fn add_members_routes_one_combined_welcome_to_each_recipient() {
+    let alice_state = create_single_member_state("group-batch", "alice");
+    let bob = invitee("bob");
+    let carol = invitee("carol");
+
+    let added = must(
+        add_members(
+            &alice_state,
+            &[
+                bob.key_package.key_package.clone(),
+                carol.key_package.key_package.clone(),
+            ],
+            false,
+        ),
+        "add bob and carol",
+    );
+    assert_eq!(added.new_epoch, 1);
+
+    let refs: Vec<&str> = added
+        .welcomes
+        .iter()
+        .map(|entry| entry.key_package_ref.as_str())
+        .collect();
+    assert_eq!(
+        refs,
+        [
+            bob.key_package.key_package_ref.as_str(),
+            carol.key_package.key_package_ref.as_str()
+        ]
+    );
+    assert_eq!(added.welcomes[0].welcome, added.welcomes[1].welcome);
+
+    let bob_state = join("group-batch", &added.welcomes[0].welcome, &bob);
+    let carol_state = join("group-batch", &added.welcomes[1].welcome, &carol);
+
+    let metadata = must(group_state_metadata(&carol_state), "carol metadata");
+    assert_eq!(metadata.epoch, 1);
+    for entry in &added.welcomes {
+        assert_eq!(entry.epoch, metadata.epoch);
+        assert_eq!(entry.ciphersuite, MLS_CIPHERSUITE_ID);
+    }
+    assert_eq!(metadata.members.len(), 3);
+
+    let ciphertext = must(encrypt_message(&bob_state, b"hi all"), "bob encrypts");
+    let decrypted = must(
+        decrypt_message(&carol_state, &ciphertext, false),
+        "carol decrypts",
+    );
+    assert_eq!(decrypted.sender_id, "bob");
+    assert_eq!(decrypted.plaintext, b"hi all");
+
+    let replayed = must(
+        process_commit(&alice_state, &added.commit),
+        "replay batch commit",
+    );
+    assert_eq!(replayed.new_epoch, 1);
+}
+
+#[test]
+fn add_members_keeps_input_order_rather_than_ref_order() {
+    let alice_state = create_single_member_state("group-batch-order", "alice");
+    let mut invitees = vec![invitee("bob"), invitee("carol")];
+    invitees.sort_by(|left, right| {
+        right
+            .key_package
+            .key_package_ref
+            .cmp(&left.key_package.key_package_ref)
+    });
+
+    let key_packages: Vec<Vec<u8>> = invitees
+        .iter()
+        .map(|entry| entry.key_package.key_package.clone())
+        .collect();
+    let added = must(
+        add_members(&alice_state, &key_packages, false),
+        "add in descending ref order",
+    );
+
+    let refs: Vec<&str> = added
+        .welcomes
+        .iter()
+        .map(|entry| entry.key_package_ref.as_str())
+        .collect();
+    let expected: Vec<&str> = invitees
+        .iter()
+        .map(|entry| entry.key_package.key_package_ref.as_str())
+        .collect();
+    assert_eq!(refs, expected);
+
+    let metadata = must(group_state_metadata(&added.state), "metadata");
+    for (leaf_index, entry) in (1u32..).zip(&invitees) {
+        let joined = join("group-batch-order", &added.welcomes[0].welcome, entry);
+        let member = must(group_state_metadata(&joined), "joined metadata").self_user_id;
+        assert!(
+            metadata
+                .members
+                .iter()
+                .any(|listed| listed.leaf_index == leaf_index && listed.user_id == member)
+        );
+    }
+}
+
+#[test]
+fn add_members_rejects_empty_and_duplicate_batches() {
+    let alice_state = create_single_member_state("group-batch-2", "alice");
+    let bob = invitee("bob");
+    let other_bob = invitee("bob");
+
+    assert!(add_members(&alice_state, &[], false).is_err());
+    assert!(
+        add_members(
+            &alice_state,
+            &[
+                bob.key_package.key_package,
+                other_bob.key_package.key_package
+            ],
+            false,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn recipient_cannot_join_with_unlisted_key_package() {
+    let alice_state = create_single_member_state("group-batch-3", "alice");
+    let bob = invitee("bob");
+    let eve = invitee("eve");
+
+    let added = must(
+        add_members(
+            &alice_state,
+            std::slice::from_ref(&bob.key_package.key_package),
+            false,
+        ),
+        "add bob",
+    );
+    assert!(
+        join_group(
+            "group-batch-3",
+            &added.welcomes[0].welcome,
+            &eve.key_package.key_package_ref,
+            &eve.key_package.private_key,
+            &eve.credential_bundle,
+            &eve.credential_private_key,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn add_members_reports_failing_key_package_and_leaves_state_untouched() {
+    let alice_state = create_single_member_state("group-batch-4", "alice");
+    let bob = invitee("bob");
+    let garbage_ref = key_package_ref(b"garbage");
+
+    let Err(error) = add_members(
+        &alice_state,
+        &[bob.key_package.key_package, b"garbage".to_vec()],
+        false,
+    ) else {
+        panic!("expected invalid key package to fail");
+    };
+    assert!(error.to_string().starts_with(&format!(
+        "add_members validate key package 1: key package {garbage_ref}:"
+    )));
+    assert_eq!(
+        error.item_context(),
+        Some(&ErrorContext::KeyPackageRef(garbage_ref))
+    );
+
+    let metadata = must(group_state_metadata(&alice_state), "alice metadata");
+    assert_eq!(metadata.epoch, 0);
+    assert_eq!(metadata.members.len(), 1);
+}
+
+#[test]
+fn add_members_merge_failure_returns_stage_error() {
+    let alice_state = create_single_member_state("group-batch-5", "alice");
+    let mut state: serde_json::Value = must(serde_json::from_slice(&alice_state), "parse state");
+    state["epoch"] = serde_json::json!(u64::MAX);
+    state["epoch_secrets"][0]["epoch"] = serde_json::json!(u64::MAX);
+    let exhausted = must(serde_json::to_vec(&state), "encode state");
+    let bob = invitee("bob");
+
+    let Err(error) = add_members(&exhausted, &[bob.key_package.key_package], false) else {
+        panic!("expected merge to fail at epoch exhaustion");
+    };
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "add_members merge: cannot advance from epoch {0} to {0}",
+            u64::MAX
+        )
+    );
+    // The group cannot advance, so no key package is to blame.
+    assert_eq!(error.item_context(), None);
+}
+
+#[test]
+fn join_rejects_welcome_for_unsupported_ciphersuite() {
+    let alice_state = create_single_member_state("group-batch-6", "alice");
+    let bob = invitee("bob");
+    let added = must(
+        add_members(
+            &alice_state,
+            std::slice::from_ref(&bob.key_package.key_package),
+            false,
+        ),
+        "add bob",
+    );
+
+    let mut welcome: serde_json::Value = must(
+        serde_json::from_slice(&added.welcomes[0].welcome),
+        "parse welcome",
+    );
+    welcome["ciphersuite"] = serde_json::json!(0x0001);
+    let foreign = must(serde_json::to_vec(&welcome), "encode welcome");
+
+    let Err(error) = join_group(
+        "group-batch-6",
+        &foreign,
+        &bob.key_package.key_package_ref,
+        &bob.key_package.private_key,
+        &bob.credential_bundle,
+        &bob.credential_private_key,
+    ) else {
+        panic!("expected foreign ciphersuite to be rejected");
+    };
+    assert!(
+        error
+            .to_string()
+            .contains("unsupported welcome ciphersuite")
+    );
+}
+
+#[test]
+fn add_members_round_trips_with_and_without_path_update() {
+    for force_path_update in [false, true] {
+        let alice_state = create_single_member_state("group-batch-path", "alice");
+        let (added_dave, dave_state) = add_and_join(&alice_state, "group-batch-path", "dave");
+        let bob = invitee("bob");
+
+        let added = must(
+            add_members(
+                &added_dave.state,
+                std::slice::from_ref(&bob.key_package.key_package),
+                force_path_update,
+            ),
+            "add bob",
+        );
+        let commit: CommitData = must(serde_json::from_slice(&added.commit), "parse commit");
+        assert_eq!(commit.path.is_some(), force_path_update);
+
+        let dave_state = must(
+            process_commit(&dave_state, &added.commit),
+            "dave processes add",
+        )
+        .state;
+        let bob_state = join("group-batch-path", &added.welcomes[0].welcome, &bob);
+
+        let ciphertext = must(encrypt_message(&dave_state, b"hi bob"), "dave encrypts");
+        let decrypted = must(
+            decrypt_message(&bob_state, &ciphertext, false),
+            "bob decrypts",
+        );
+        assert_eq!(decrypted.plaintext, b"hi bob");
+        let reply = must(encrypt_message(&bob_state, b"hi all"), "bob encrypts");
+        let decrypted = must(
+            decrypt_message(&added.state, &reply, false),
+            "alice decrypts",
+        );
+        assert_eq!(decrypted.plaintext, b"hi all");
+    }
+}
+
+#[test]
+fn add_members_reports_validate_stage_for_empty_and_malformed_groups() {
+    let alice_state = create_single_member_state("group-batch-validate", "alice");
+    let bob = invitee("bob");
+
+    let Err(empty) = add_members(&alice_state, &[], false) else {
+        panic!("expected an empty add to fail");
+    };
+    assert_eq!(
+        empty.to_string(),
+        "add_members validate: at least one key package is required"
+    );
+
+    let mut state: serde_json::Value = must(serde_json::from_slice(&alice_state), "parse state");
+    state["self_user_id"] = serde_json::json!("mallory");
+    let foreign = must(serde_json::to_vec(&state), "encode state");
+    let Err(error) = add_members(&foreign, &[bob.key_package.key_package], false) else {
+        panic!("expected a state without the local member to fail");
+    };
+    assert!(error.to_string().starts_with("add_members validate:"));
+}
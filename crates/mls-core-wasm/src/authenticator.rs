@@ -0,0 +1,50 @@
+use crate::{
+    crypto::{constant_time_eq, derive_epoch_authenticator},
+    error::MlsError,
+    model::SafetyNumberOutput,
+    protocol::{current_epoch_secret, decode_group_state},
+};
+
+const SAFETY_NUMBER_GROUPS: usize = 6;
+const SAFETY_NUMBER_CHUNK_LEN: usize = 5;
+
+pub(crate) fn epoch_authenticator(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let epoch_secret = current_epoch_secret(&state)?;
+    Ok(derive_epoch_authenticator(&epoch_secret, &state.group_id, state.epoch)?.to_vec())
+}
+
+/// Formats an authenticator like Signal safety numbers: each 5-byte chunk becomes
+/// a zero-padded 5-digit group.
+pub(crate) fn format_safety_number(authenticator: &[u8]) -> Result<String, MlsError> {
+    let required = SAFETY_NUMBER_GROUPS * SAFETY_NUMBER_CHUNK_LEN;
+    if authenticator.len() < required {
+        return Err(MlsError::InvalidInput(format!(
+            "epoch authenticator must be at least {required} bytes"
+        )));
+    }
+
+    let groups: Vec<String> = authenticator[..required]
+        .chunks(SAFETY_NUMBER_CHUNK_LEN)
+        .map(|chunk| {
+            let value = chunk
+                .iter()
+                .fold(0_u64, |acc, byte| (acc << 8) | u64::from(*byte));
+            format!("{:05}", value % 100_000)
+        })
+        .collect();
+
+    Ok(groups.join(" "))
+}
+
+pub(crate) fn compare_safety_number(
+    group_state_bytes: &[u8],
+    other_authenticator: &[u8],
+) -> Result<SafetyNumberOutput, MlsError> {
+    let local = epoch_authenticator(group_state_bytes)?;
+
+    Ok(SafetyNumberOutput {
+        matches: constant_time_eq(&local, other_authenticator),
+        safety_number: format_safety_number(&local)?,
+    })
+}
@@ -0,0 +1,57 @@
+use crate::{
+    authenticator::{compare_safety_number, epoch_authenticator, format_safety_number},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn members_at_same_epoch_share_safety_number() {
+    let alice_state = create_single_member_state("group-safety", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-safety", "bob");
+
+    let bob_authenticator = must(epoch_authenticator(&bob_state), "bob authenticator");
+    let comparison = must(
+        compare_safety_number(&added.state, &bob_authenticator),
+        "alice compares",
+    );
+    let bob_view = must(
+        compare_safety_number(&bob_state, &bob_authenticator),
+        "bob compares",
+    );
+
+    assert!(comparison.matches);
+    assert_eq!(comparison.safety_number, bob_view.safety_number);
+
+    let groups: Vec<&str> = comparison.safety_number.split(' ').collect();
+    assert_eq!(groups.len(), 6);
+    assert!(
+        groups
+            .iter()
+            .all(|group| group.len() == 5 && group.chars().all(|c| c.is_ascii_digit()))
+    );
+}
+
+#[test]
+fn diverged_epochs_do_not_match() {
+    let alice_state = create_single_member_state("group-safety-2", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-safety-2", "bob");
+
+    let stale_authenticator = must(epoch_authenticator(&alice_state), "stale authenticator");
+    let comparison = must(
+        compare_safety_number(&added.state, &stale_authenticator),
+        "compare against stale epoch",
+    );
+    assert!(!comparison.matches);
+
+    let truncated = must(
+        compare_safety_number(&added.state, &stale_authenticator[..8]),
+        "compare truncated",
+    );
+    assert!(!truncated.matches);
+}
+
+#[test]
+fn safety_number_formatting_zero_pads_groups() {
+    let formatted = must(format_safety_number(&[0_u8; 32]), "format zeros");
+    assert_eq!(formatted, "00000 00000 00000 00000 00000 00000");
+    assert!(format_safety_number(&[1_u8; 8]).is_err());
+}
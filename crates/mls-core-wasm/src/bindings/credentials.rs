@@ -0,0 +1,104 @@
+use wasm_bindgen::JsValue;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{from_js_value, to_js_error, to_js_value};
+use crate::{
+    identity::{generate_credential_from_identity, sign_blob, verify_blob},
+    key_package::{
+        generate_key_package, generate_last_resort_key_package, inspect_key_package,
+        key_package_expired, validate_key_package,
+    },
+    model::{
+        GeneratedCredentialOutput, GeneratedKeyPackageOutput, KeyPackageInfoOutput,
+        KeyPackageValidationOutput,
+    },
+    protocol::generate_credential,
+    welcome::match_welcome_key_package,
+};
+
+/// Generates an MLS credential bundle and signing key.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_generate_credential(user_id: &str) -> Result<JsValue, JsValue> {
+    let credential: GeneratedCredentialOutput =
+        generate_credential(user_id).map_err(to_js_error)?;
+    to_js_value(&credential)
+}
+
+/// Generates an MLS credential bundle whose identity is arbitrary bytes.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_generate_credential_from_identity(identity: &[u8]) -> Result<JsValue, JsValue> {
+    let credential: GeneratedCredentialOutput =
+        generate_credential_from_identity(identity).map_err(to_js_error)?;
+    to_js_value(&credential)
+}
+
+/// Generates a signed key package.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_generate_key_package(
+    credential_bundle: &[u8],
+    credential_private_key: &[u8],
+) -> Result<JsValue, JsValue> {
+    let key_package: GeneratedKeyPackageOutput =
+        generate_key_package(credential_bundle, credential_private_key).map_err(to_js_error)?;
+    to_js_value(&key_package)
+}
+
+/// Generates a last-resort key package that may be used for more than one add.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_generate_last_resort_key_package(
+    credential_bundle: &[u8],
+    credential_private_key: &[u8],
+) -> Result<JsValue, JsValue> {
+    let key_package: GeneratedKeyPackageOutput =
+        generate_last_resort_key_package(credential_bundle, credential_private_key)
+            .map_err(to_js_error)?;
+    to_js_value(&key_package)
+}
+
+/// Parses a key package and returns its public contents after verifying its signature.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_inspect_key_package(key_package: &[u8]) -> Result<JsValue, JsValue> {
+    let info: KeyPackageInfoOutput = inspect_key_package(key_package).map_err(to_js_error)?;
+    to_js_value(&info)
+}
+
+/// Returns whether a key package's lifetime ended before `now_seconds`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_key_package_expired(key_package: &[u8], now_seconds: u64) -> Result<bool, JsValue> {
+    key_package_expired(key_package, now_seconds).map_err(to_js_error)
+}
+
+/// Validates an uploaded key package without a group and reports why it was rejected.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_validate_key_package(key_package: &[u8], now_seconds: u64) -> Result<JsValue, JsValue> {
+    let validation: KeyPackageValidationOutput =
+        validate_key_package(key_package, now_seconds).map_err(to_js_error)?;
+    to_js_value(&validation)
+}
+
+/// Returns which stored key package reference a welcome targets, if any.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_match_welcome_key_package(
+    welcome: &[u8],
+    stored_refs: JsValue,
+) -> Result<Option<String>, JsValue> {
+    let stored_refs: Vec<String> = from_js_value(stored_refs, "stored_refs")?;
+    match_welcome_key_package(welcome, &stored_refs).map_err(to_js_error)
+}
+
+/// Signs application data outside MLS with the credential's signing key.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_sign_blob(credential_private_key: &[u8], data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    sign_blob(credential_private_key, data).map_err(to_js_error)
+}
+
+/// Returns whether `signature` over `data` was made by the holder of `signing_public_key`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_verify_blob(
+    signing_public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, JsValue> {
+    verify_blob(signing_public_key, data, signature).map_err(to_js_error)
+}
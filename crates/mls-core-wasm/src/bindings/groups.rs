@@ -0,0 +1,247 @@
+use wasm_bindgen::JsValue;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{from_js_value, to_js_error, to_js_value};
+use crate::{
+    bundle::{add_members_bundle, unpack_commit_bundle},
+    commit::process_commit,
+    group_context::update_group_context,
+    model::{
+        AddMemberOutput, AddMembersBundleOutput, AddMembersOutput, CommitBundleOutput,
+        CommitOutput, CommitPreviewOutput, ForceLeaveOutput, GroupContextData, JoinGroupOutput,
+        ProcessCommitOutput, RemoveMemberOutput, StoreProposalOutput, WelcomeInfoOutput,
+        WelcomeJoinStatus,
+    },
+    operations::{add_member, add_members, join_group, remove_member},
+    path_update::{self_update, should_rekey},
+    preview::preview_commit,
+    proposals::{commit_received_proposals, force_leave, pending_proposal_count, store_proposal},
+    protocol::create_group,
+    replace::replace_member,
+    solo::create_solo_group,
+    welcome::{
+        attach_ratchet_tree, export_ratchet_tree, inspect_welcome, resend_welcome,
+        welcome_join_status,
+    },
+};
+
+/// Creates a new MLS group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_create_group(
+    group_id: &str,
+    credential_bundle: &[u8],
+    credential_private_key: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    create_group(group_id, credential_bundle, credential_private_key).map_err(to_js_error)
+}
+
+/// Creates a single-member notes-to-self group whose own messages decrypt locally.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_create_solo_group(
+    group_id: &str,
+    credential_bundle: &[u8],
+    credential_private_key: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    create_solo_group(group_id, credential_bundle, credential_private_key).map_err(to_js_error)
+}
+
+/// Joins a group from a welcome payload and key package private key.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_join_group(
+    group_id: &str,
+    welcome_bytes: &[u8],
+    key_package_ref: &str,
+    key_package_private_key: &[u8],
+    credential_bundle: &[u8],
+    credential_private_key: &[u8],
+) -> Result<JsValue, JsValue> {
+    let output: JoinGroupOutput = join_group(
+        group_id,
+        welcome_bytes,
+        key_package_ref,
+        key_package_private_key,
+        credential_bundle,
+        credential_private_key,
+    )
+    .map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Returns a welcome's public header, including whether it embeds the member tree.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_inspect_welcome(welcome: &[u8]) -> Result<JsValue, JsValue> {
+    let info: WelcomeInfoOutput = inspect_welcome(welcome).map_err(to_js_error)?;
+    to_js_value(&info)
+}
+
+/// Rebuilds a current-epoch welcome for a member who never processed theirs.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_resend_welcome(group_state: &[u8], leaf_index: u32) -> Result<Vec<u8>, JsValue> {
+    resend_welcome(group_state, leaf_index).map_err(to_js_error)
+}
+
+/// Exports the member tree for joiners of groups whose welcomes omit it.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_export_ratchet_tree(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    export_ratchet_tree(group_state).map_err(to_js_error)
+}
+
+/// Attaches an out-of-band member tree to a welcome so it can be joined.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_attach_ratchet_tree(welcome: &[u8], ratchet_tree: &[u8]) -> Result<Vec<u8>, JsValue> {
+    attach_ratchet_tree(welcome, ratchet_tree).map_err(to_js_error)
+}
+
+/// Reports whether a welcome for an already-joined group should be processed.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_welcome_join_status(group_state: &[u8], welcome: &[u8]) -> Result<JsValue, JsValue> {
+    let status: WelcomeJoinStatus =
+        welcome_join_status(group_state, welcome).map_err(to_js_error)?;
+    to_js_value(&status)
+}
+
+/// Adds a member and returns commit/welcome plus updated state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_add_member(group_state: &[u8], member_key_package: &[u8]) -> Result<JsValue, JsValue> {
+    let output: AddMemberOutput =
+        add_member(group_state, member_key_package).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Adds several members in one commit and returns per-recipient welcome routing.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_add_members(
+    group_state: &[u8],
+    member_key_packages: JsValue,
+    force_path_update: bool,
+) -> Result<JsValue, JsValue> {
+    let member_key_packages: Vec<Vec<u8>> =
+        from_js_value(member_key_packages, "member_key_packages")?;
+    let output: AddMembersOutput =
+        add_members(group_state, &member_key_packages, force_path_update).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Adds several members and returns the commit and welcomes as one length-prefixed bundle.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_add_members_bundle(
+    group_state: &[u8],
+    member_key_packages: JsValue,
+    force_path_update: bool,
+) -> Result<JsValue, JsValue> {
+    let member_key_packages: Vec<Vec<u8>> =
+        from_js_value(member_key_packages, "member_key_packages")?;
+    let output: AddMembersBundleOutput =
+        add_members_bundle(group_state, &member_key_packages, force_path_update)
+            .map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Splits a commit bundle into its commit and per-member welcomes.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_unpack_commit_bundle(bundle: &[u8]) -> Result<JsValue, JsValue> {
+    let output: CommitBundleOutput = unpack_commit_bundle(bundle).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Removes a member and returns commit plus updated state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_remove_member(group_state: &[u8], leaf_index: u32) -> Result<JsValue, JsValue> {
+    let output: RemoveMemberOutput = remove_member(group_state, leaf_index).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Removes a leaf and adds a fresh key package in one commit, returning its welcome.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_replace_member(
+    group_state: &[u8],
+    leaf_index: u32,
+    member_key_package: &[u8],
+    allow_identity_change: bool,
+) -> Result<JsValue, JsValue> {
+    let output: AddMemberOutput = replace_member(
+        group_state,
+        leaf_index,
+        member_key_package,
+        allow_identity_change,
+    )
+    .map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Processes a commit and returns updated state plus any group context change.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_process_commit(group_state: &[u8], commit_bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let output: ProcessCommitOutput =
+        process_commit(group_state, commit_bytes).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Verifies a commit and returns its membership effect without merging it.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_preview_commit(group_state: &[u8], commit_bytes: &[u8]) -> Result<JsValue, JsValue> {
+    let output: CommitPreviewOutput =
+        preview_commit(group_state, commit_bytes).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Commits new group context extensions (name, external senders).
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_update_group_context(
+    group_state: &[u8],
+    group_context: JsValue,
+) -> Result<JsValue, JsValue> {
+    let group_context: GroupContextData = from_js_value(group_context, "group_context")?;
+    let output: CommitOutput =
+        update_group_context(group_state, group_context).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Rotates the local leaf key in a new epoch for post-compromise security.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_self_update(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let output: CommitOutput = self_update(group_state).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Returns whether more than `max_epochs` epochs passed since the last local self-update.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_should_rekey(group_state: &[u8], max_epochs: u64) -> Result<bool, JsValue> {
+    should_rekey(group_state, max_epochs).map_err(to_js_error)
+}
+
+/// Emits a self-remove proposal and zeroes the provided group state buffer.
+///
+/// Remaining members will not see the departure until one of them commits it.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_force_leave(group_state: &mut [u8]) -> Result<JsValue, JsValue> {
+    let output: ForceLeaveOutput = force_leave(group_state).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Verifies a received proposal and stores it as pending for the current epoch.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_store_proposal(group_state: &[u8], proposal: &[u8]) -> Result<JsValue, JsValue> {
+    let output: StoreProposalOutput = store_proposal(group_state, proposal).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Returns how many stored proposals await a commit; non-zero means a commit is needed.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_pending_proposal_count(group_state: &[u8]) -> Result<u32, JsValue> {
+    pending_proposal_count(group_state).map_err(to_js_error)
+}
+
+/// Commits only the pending proposals named by `proposal_refs`; the rest expire with the epoch.
+/// Members added by the proposals receive the returned welcomes.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_commit_received_proposals(
+    group_state: &[u8],
+    proposal_refs: JsValue,
+) -> Result<JsValue, JsValue> {
+    let proposal_refs: Vec<String> = from_js_value(proposal_refs, "proposal_refs")?;
+    let output: AddMembersOutput =
+        commit_received_proposals(group_state, &proposal_refs).map_err(to_js_error)?;
+    to_js_value(&output)
+}
@@ -0,0 +1,100 @@
+use wasm_bindgen::JsValue;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{from_js_value, to_js_error, to_js_value};
+use crate::{
+    catch_up::catch_up,
+    messaging::{decrypt_message, decrypt_message_into, encrypt_message, reencrypt_history},
+    model::{CatchUpOutput, DecryptIntoOutput, DecryptOutput},
+};
+
+/// Encrypts an application message with authenticated metadata.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_encrypt_message(group_state: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    encrypt_message(group_state, plaintext).map_err(to_js_error)
+}
+
+/// Re-encrypts chosen past plaintexts as fresh current-epoch messages for late joiners.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_reencrypt_history(group_state: &[u8], plaintexts: JsValue) -> Result<JsValue, JsValue> {
+    let plaintexts: Vec<Vec<u8>> = from_js_value(plaintexts, "plaintexts")?;
+    let messages: Vec<Vec<u8>> =
+        reencrypt_history(group_state, &plaintexts).map_err(to_js_error)?;
+    to_js_value(&messages)
+}
+
+/// Decrypts an application message and returns authenticated sender identity.
+///
+/// With `include_framing`, also returns the canonical message bytes for forwarding.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_decrypt_message(
+    group_state: &[u8],
+    ciphertext: &[u8],
+    include_framing: bool,
+) -> Result<JsValue, JsValue> {
+    let output: DecryptOutput =
+        decrypt_message(group_state, ciphertext, include_framing).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Plaintext scratch reused across `mls_decrypt_message_into` calls.
+    static DECRYPT_SCRATCH: std::cell::RefCell<Vec<u8>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Decrypts an application message into a caller-owned JS buffer.
+///
+/// Fails with the required size when `out` is smaller than the plaintext.
+/// `out` is never copied into WASM memory: the plaintext is decrypted into a
+/// scratch buffer reused across calls, copied once into `out`, then zeroed.
+/// The group state and ciphertext are still copied in as for every binding.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn mls_decrypt_message_into(
+    group_state: &[u8],
+    ciphertext: &[u8],
+    out: &js_sys::Uint8Array,
+) -> Result<JsValue, JsValue> {
+    DECRYPT_SCRATCH.with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        let capacity = out.length() as usize;
+        if scratch.len() < capacity {
+            scratch.resize(capacity, 0);
+        }
+        let buffer = &mut scratch[..capacity];
+        let output: DecryptIntoOutput =
+            decrypt_message_into(group_state, ciphertext, buffer).map_err(to_js_error)?;
+        if let Some(plaintext) = buffer.get_mut(..output.plaintext_len) {
+            out.subarray(0, output.plaintext_len as u32)
+                .copy_from(plaintext);
+            plaintext.fill(0);
+        }
+        to_js_value(&output)
+    })
+}
+
+/// Decrypts an application message into a preallocated buffer.
+///
+/// Fails with the required size when `out` is smaller than the plaintext.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn mls_decrypt_message_into(
+    group_state: &[u8],
+    ciphertext: &[u8],
+    out: &mut [u8],
+) -> Result<JsValue, JsValue> {
+    let output: DecryptIntoOutput =
+        decrypt_message_into(group_state, ciphertext, out).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Applies an ordered mix of missed commits and application messages.
+///
+/// Messages ahead of the current epoch are retried after each commit.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_catch_up(group_state: &[u8], items: JsValue) -> Result<JsValue, JsValue> {
+    let items: Vec<Vec<u8>> = from_js_value(items, "items")?;
+    let output: CatchUpOutput = catch_up(group_state, &items).map_err(to_js_error)?;
+    to_js_value(&output)
+}
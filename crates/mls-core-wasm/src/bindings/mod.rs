@@ -0,0 +1,48 @@
+//! `wasm_bindgen` exports grouped by feature area.
+
+mod credentials;
+mod groups;
+mod messages;
+mod secrets;
+mod state;
+
+pub use credentials::*;
+pub use groups::*;
+pub use messages::*;
+pub use secrets::*;
+pub use state::*;
+
+use wasm_bindgen::JsValue;
+
+use crate::error::MlsError;
+
+/// Throws an `Error`, carrying the failing item as `error_context` when known.
+#[cfg(target_arch = "wasm32")]
+fn to_js_error(error: MlsError) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    if let Some(context) = error.item_context()
+        && let Ok(value) = serde_wasm_bindgen::to_value(context)
+    {
+        // Setting a property on a freshly created Error cannot fail.
+        let _ = js_sys::Reflect::set(&js_error, &JsValue::from_str("error_context"), &value);
+    }
+    js_error.into()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn to_js_error(error: MlsError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+fn to_js_value<T: serde::Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value)
+        .map_err(|error| JsValue::from_str(&format!("failed to convert result to JS: {error}")))
+}
+
+fn from_js_value<T: serde::de::DeserializeOwned>(
+    value: JsValue,
+    context: &str,
+) -> Result<T, JsValue> {
+    serde_wasm_bindgen::from_value(value)
+        .map_err(|error| JsValue::from_str(&format!("invalid {context} argument: {error}")))
+}
@@ -0,0 +1,71 @@
+use wasm_bindgen::JsValue;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{from_js_value, to_js_error, to_js_value};
+use crate::{
+    authenticator::{compare_safety_number, epoch_authenticator},
+    exporter::{derive_attachment_key, derive_backup_key},
+    model::{CommitOutput, PskReferenceData, SafetyNumberOutput},
+    psk::{add_external_psk, commit_psk, list_external_psks, remove_external_psk},
+};
+
+/// Stores an externally agreed 32-byte PSK on the group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_add_external_psk(
+    group_state: &[u8],
+    psk_id: &str,
+    secret: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    add_external_psk(group_state, psk_id, secret).map_err(to_js_error)
+}
+
+/// Removes a stored external PSK.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_remove_external_psk(group_state: &[u8], psk_id: &str) -> Result<Vec<u8>, JsValue> {
+    remove_external_psk(group_state, psk_id).map_err(to_js_error)
+}
+
+/// Lists stored external PSK identifiers.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_list_external_psks(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let psk_ids = list_external_psks(group_state).map_err(to_js_error)?;
+    to_js_value(&psk_ids)
+}
+
+/// Commits an external or resumption PSK into the next epoch.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_commit_psk(group_state: &[u8], psk: JsValue) -> Result<JsValue, JsValue> {
+    let psk: PskReferenceData = from_js_value(psk, "psk")?;
+    let output: CommitOutput = commit_psk(group_state, psk).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Returns the epoch authenticator for out-of-band comparison between members.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_epoch_authenticator(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    epoch_authenticator(group_state).map_err(to_js_error)
+}
+
+/// Derives the shared encrypted-backup key for the current epoch.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_derive_backup_key(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    derive_backup_key(group_state).map_err(to_js_error)
+}
+
+/// Derives the key for an out-of-band attachment from the nonce shipped with it.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_derive_attachment_key(group_state: &[u8], nonce: &[u8]) -> Result<Vec<u8>, JsValue> {
+    derive_attachment_key(group_state, nonce).map_err(to_js_error)
+}
+
+/// Compares another member's epoch authenticator and returns a display safety number.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_compare_safety_number(
+    group_state: &[u8],
+    other_authenticator: &[u8],
+) -> Result<JsValue, JsValue> {
+    let output: SafetyNumberOutput =
+        compare_safety_number(group_state, other_authenticator).map_err(to_js_error)?;
+    to_js_value(&output)
+}
@@ -0,0 +1,123 @@
+use wasm_bindgen::JsValue;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use super::{from_js_value, to_js_error, to_js_value};
+use crate::{
+    identity::member_credentials,
+    local_metadata::{group_metadata, set_group_metadata},
+    model::{
+        GroupMetadataEntryOutput, GroupPolicyData, GroupStateMetadataOutput,
+        GroupStateReportOutput, ImportStateOutput, MemberCredentialOutput, PublicGroupStateOutput,
+    },
+    policy::{delete_signing_key, group_policy, set_group_policy},
+    protocol::{export_group_state, group_state_metadata, import_group_state},
+    sync::{epochs_behind, members_digest, public_group_state, sync_digest, validate_group_state},
+};
+
+/// Returns metadata for a serialized group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_group_state_metadata(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let metadata: GroupStateMetadataOutput =
+        group_state_metadata(group_state).map_err(to_js_error)?;
+    to_js_value(&metadata)
+}
+
+/// Exports normalized serialized group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_export_group_state(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    export_group_state(group_state).map_err(to_js_error)
+}
+
+/// Imports and validates serialized group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_import_group_state(group_id: &str, group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let output: ImportStateOutput =
+        import_group_state(group_id, group_state).map_err(to_js_error)?;
+    to_js_value(&output)
+}
+
+/// Reports whether a persisted group state is intact; never throws for corrupted bytes.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_validate_group_state(group_id: &str, group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let report: GroupStateReportOutput = validate_group_state(group_id, group_state);
+    to_js_value(&report)
+}
+
+/// Returns the current epoch encoded in a serialized group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_group_epoch(group_state: &[u8]) -> Result<u64, JsValue> {
+    let metadata = group_state_metadata(group_state).map_err(to_js_error)?;
+    Ok(metadata.epoch)
+}
+
+/// Returns how many epochs the local state trails `remote_epoch` (negative when ahead).
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_epochs_behind(group_state: &[u8], remote_epoch: u64) -> Result<i64, JsValue> {
+    epochs_behind(group_state, remote_epoch).map_err(to_js_error)
+}
+
+/// Returns a digest that two members share only when their group views are in sync.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_sync_digest(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    sync_digest(group_state).map_err(to_js_error)
+}
+
+/// Returns a digest of the sorted member identities, independent of leaf order and keys.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_members_digest(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    members_digest(group_state).map_err(to_js_error)
+}
+
+/// Returns a secret-free snapshot (ids, ciphersuite, members) for directory services.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_export_public_group_state(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let snapshot: PublicGroupStateOutput = public_group_state(group_state).map_err(to_js_error)?;
+    to_js_value(&snapshot)
+}
+
+/// Returns each member's identity and signature key for application trust checks.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_member_credentials(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let credentials: Vec<MemberCredentialOutput> =
+        member_credentials(group_state).map_err(to_js_error)?;
+    to_js_value(&credentials)
+}
+
+/// Replaces the local commit policy (e.g. `rekey_on_remove`) stored with the group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_set_group_policy(group_state: &[u8], policy: JsValue) -> Result<Vec<u8>, JsValue> {
+    let policy: GroupPolicyData = from_js_value(policy, "policy")?;
+    set_group_policy(group_state, policy).map_err(to_js_error)
+}
+
+/// Erases the local signing key from a group state; signing with the result then fails.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_delete_signing_key(group_state: &[u8]) -> Result<Vec<u8>, JsValue> {
+    delete_signing_key(group_state).map_err(to_js_error)
+}
+
+/// Returns the local commit policy stored with the group state.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_group_policy(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let policy: GroupPolicyData = group_policy(group_state).map_err(to_js_error)?;
+    to_js_value(&policy)
+}
+
+/// Sets or clears (when `value` is omitted) a local-only group metadata entry.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_set_group_metadata(
+    group_state: &[u8],
+    key: &str,
+    value: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    set_group_metadata(group_state, key, value.as_deref()).map_err(to_js_error)
+}
+
+/// Returns local-only group metadata entries sorted by key.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn mls_group_metadata(group_state: &[u8]) -> Result<JsValue, JsValue> {
+    let entries: Vec<GroupMetadataEntryOutput> =
+        group_metadata(group_state).map_err(to_js_error)?;
+    to_js_value(&entries)
+}
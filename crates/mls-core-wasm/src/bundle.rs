@@ -0,0 +1,139 @@
+//! Single-blob framing of a membership commit and its welcomes for delivery services.
+//!
+//! Layout, with every integer a big-endian `u32` unless noted:
+//!
+//! ```text
+//! version: u8 (currently 1)
+//! commit_len, commit bytes
+//! welcome_count
+//! welcome_count times:
+//!     ref_len, key package ref (UTF-8 hex)
+//!     welcome_len, welcome bytes
+//! ```
+//!
+//! Nothing may follow the last welcome.
+
+use crate::{
+    error::MlsError,
+    model::{AddMembersBundleOutput, BundledWelcomeOutput, CommitBundleOutput},
+    operations::add_members,
+};
+
+const COMMIT_BUNDLE_VERSION: u8 = 1;
+
+fn push_chunk(out: &mut Vec<u8>, bytes: &[u8], field: &str) -> Result<(), MlsError> {
+    let len = u32::try_from(bytes.len())
+        .map_err(|_| MlsError::InvalidInput(format!("commit bundle {field} is too large")))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+struct BundleReader<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> BundleReader<'a> {
+    fn take(&mut self, len: usize, field: &str) -> Result<&'a [u8], MlsError> {
+        if self.remaining.len() < len {
+            return Err(MlsError::InvalidInput(format!(
+                "commit bundle is truncated in {field}"
+            )));
+        }
+        let (head, rest) = self.remaining.split_at(len);
+        self.remaining = rest;
+        Ok(head)
+    }
+
+    fn read_u32(&mut self, field: &str) -> Result<u32, MlsError> {
+        let bytes: [u8; 4] = self
+            .take(4, field)?
+            .try_into()
+            .map_err(|_| MlsError::InvalidInput(format!("commit bundle {field} is malformed")))?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn read_chunk(&mut self, field: &str) -> Result<&'a [u8], MlsError> {
+        let len = self.read_u32(field)? as usize;
+        self.take(len, field)
+    }
+}
+
+/// Packs a commit and per-member welcomes, keeping the welcome order.
+pub(crate) fn pack_commit_bundle(
+    commit: &[u8],
+    welcomes: &[BundledWelcomeOutput],
+) -> Result<Vec<u8>, MlsError> {
+    let mut bundle = vec![COMMIT_BUNDLE_VERSION];
+    push_chunk(&mut bundle, commit, "commit")?;
+    let count = u32::try_from(welcomes.len())
+        .map_err(|_| MlsError::InvalidInput("commit bundle has too many welcomes".to_owned()))?;
+    bundle.extend_from_slice(&count.to_be_bytes());
+    for welcome in welcomes {
+        push_chunk(
+            &mut bundle,
+            welcome.key_package_ref.as_bytes(),
+            "key package ref",
+        )?;
+        push_chunk(&mut bundle, &welcome.welcome, "welcome")?;
+    }
+    Ok(bundle)
+}
+
+/// Splits a bundle from [`pack_commit_bundle`] back into its commit and welcomes.
+pub(crate) fn unpack_commit_bundle(bundle: &[u8]) -> Result<CommitBundleOutput, MlsError> {
+    let mut reader = BundleReader { remaining: bundle };
+    if let &[version] = reader.take(1, "version")?
+        && version != COMMIT_BUNDLE_VERSION
+    {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported commit bundle version {version}"
+        )));
+    }
+
+    let commit = reader.read_chunk("commit")?.to_vec();
+    let count = reader.read_u32("welcome count")?;
+    // The count is untrusted, so grow the list as welcomes actually parse.
+    let mut welcomes = Vec::new();
+    for _ in 0..count {
+        let key_package_ref = String::from_utf8(reader.read_chunk("key package ref")?.to_vec())
+            .map_err(|_| {
+                MlsError::InvalidInput("commit bundle key package ref is not UTF-8".to_owned())
+            })?;
+        welcomes.push(BundledWelcomeOutput {
+            key_package_ref,
+            welcome: reader.read_chunk("welcome")?.to_vec(),
+        });
+    }
+
+    if !reader.remaining.is_empty() {
+        return Err(MlsError::InvalidInput(
+            "commit bundle has trailing bytes".to_owned(),
+        ));
+    }
+
+    Ok(CommitBundleOutput { commit, welcomes })
+}
+
+/// Adds members like [`add_members`] but returns the commit and welcomes as one bundle.
+pub(crate) fn add_members_bundle(
+    group_state_bytes: &[u8],
+    member_key_packages: &[Vec<u8>],
+    force_path_update: bool,
+) -> Result<AddMembersBundleOutput, MlsError> {
+    let added = add_members(group_state_bytes, member_key_packages, force_path_update)?;
+    let welcomes: Vec<BundledWelcomeOutput> = added
+        .welcomes
+        .into_iter()
+        .map(|welcome| BundledWelcomeOutput {
+            key_package_ref: welcome.key_package_ref,
+            welcome: welcome.welcome,
+        })
+        .collect();
+
+    Ok(AddMembersBundleOutput {
+        bundle: pack_commit_bundle(&added.commit, &welcomes)?,
+        state: added.state,
+        new_epoch: added.new_epoch,
+    })
+}
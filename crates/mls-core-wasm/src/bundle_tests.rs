@@ -0,0 +1,98 @@
+use crate::{
+    bundle::{add_members_bundle, pack_commit_bundle, unpack_commit_bundle},
+    commit::process_commit,
+    key_package::generate_key_package,
+    model::BundledWelcomeOutput,
+    operations::join_group,
+    protocol::generate_credential,
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn add_members_bundle_round_trips_commit_and_welcomes() {
+    let alice_state = create_single_member_state("group-bundle", "alice");
+    let (added_bob, bob_state) = add_and_join(&alice_state, "group-bundle", "bob");
+    let carol = must(generate_credential("carol"), "carol credential");
+    let carol_package = must(
+        generate_key_package(&carol.credential_bundle, &carol.private_key),
+        "carol key package",
+    );
+    let dave = must(generate_credential("dave"), "dave credential");
+    let dave_package = must(
+        generate_key_package(&dave.credential_bundle, &dave.private_key),
+        "dave key package",
+    );
+
+    let bundled = must(
+        add_members_bundle(
+            &added_bob.state,
+            &[
+                carol_package.key_package.clone(),
+                dave_package.key_package.clone(),
+            ],
+            false,
+        ),
+        "bundle add",
+    );
+    assert_eq!(bundled.new_epoch, 2);
+
+    let unpacked = must(unpack_commit_bundle(&bundled.bundle), "unpack");
+    let refs: Vec<&str> = unpacked
+        .welcomes
+        .iter()
+        .map(|welcome| welcome.key_package_ref.as_str())
+        .collect();
+    assert_eq!(
+        refs,
+        [
+            carol_package.key_package_ref.as_str(),
+            dave_package.key_package_ref.as_str()
+        ]
+    );
+
+    let processed = must(
+        process_commit(&bob_state, &unpacked.commit),
+        "bob processes bundled commit",
+    );
+    assert_eq!(processed.new_epoch, 2);
+    let joined = must(
+        join_group(
+            "group-bundle",
+            &unpacked.welcomes[1].welcome,
+            &dave_package.key_package_ref,
+            &dave_package.private_key,
+            &dave.credential_bundle,
+            &dave.private_key,
+        ),
+        "dave joins from bundle",
+    );
+    assert_eq!(joined.epoch, 2);
+}
+
+#[test]
+fn unpack_commit_bundle_rejects_malformed_framing() {
+    let welcomes = [BundledWelcomeOutput {
+        key_package_ref: "ref".to_owned(),
+        welcome: vec![1, 2, 3],
+    }];
+    let bundle = must(pack_commit_bundle(b"commit", &welcomes), "pack");
+    assert_eq!(
+        must(unpack_commit_bundle(&bundle), "unpack").welcomes,
+        welcomes
+    );
+
+    let truncated = &bundle[..bundle.len() - 1];
+    assert!(unpack_commit_bundle(truncated).is_err());
+
+    let mut trailing = bundle.clone();
+    trailing.push(0);
+    assert!(unpack_commit_bundle(&trailing).is_err());
+
+    let mut future = bundle;
+    future[0] = 2;
+    assert!(unpack_commit_bundle(&future).is_err());
+
+    let mut huge_count = vec![1, 0, 0, 0, 0];
+    huge_count.extend_from_slice(&u32::MAX.to_be_bytes());
+    assert!(unpack_commit_bundle(&huge_count).is_err());
+}
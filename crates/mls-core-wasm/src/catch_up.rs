@@ -0,0 +1,76 @@
+use serde::Deserialize;
+
+use crate::{
+    commit::process_commit,
+    error::MlsError,
+    messaging::decrypt_message,
+    model::{CatchUpOutput, DecryptOutput, DecryptStatus},
+    protocol::{decode_group_state, deserialize_json},
+};
+
+/// Reads the one field that only commits carry, to route an item without parsing it twice.
+#[derive(Deserialize)]
+struct WireProbe {
+    previous_epoch: Option<u64>,
+}
+
+/// Applies an ordered mix of commits and application messages in one pass.
+///
+/// Messages from epochs not yet reached are held back and retried after each commit,
+/// so a message that arrived ahead of its commit is still returned once the epoch
+/// catches up. Messages still ahead at the end are handed back in `pending`.
+pub(crate) fn catch_up(
+    group_state_bytes: &[u8],
+    items: &[Vec<u8>],
+) -> Result<CatchUpOutput, MlsError> {
+    let mut epoch = decode_group_state(group_state_bytes)?.epoch;
+    let mut state = group_state_bytes.to_vec();
+    let mut messages = Vec::new();
+    let mut held: Vec<(usize, &[u8])> = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let context = format!("catch_up item {index}");
+        let probe: WireProbe = deserialize_json(item, "catch-up item")
+            .map_err(|error| error.with_context(&context))?;
+
+        if probe.previous_epoch.is_none() {
+            if let Some(output) = open_or_hold(&state, index, item, &mut held)? {
+                messages.push(output);
+            }
+            continue;
+        }
+
+        let processed =
+            process_commit(&state, item).map_err(|error| error.with_context(&context))?;
+        state = processed.state;
+        epoch = processed.new_epoch;
+
+        for (held_index, held_item) in std::mem::take(&mut held) {
+            if let Some(output) = open_or_hold(&state, held_index, held_item, &mut held)? {
+                messages.push(output);
+            }
+        }
+    }
+
+    Ok(CatchUpOutput {
+        state,
+        epoch,
+        messages,
+        pending: held.into_iter().map(|(_, item)| item.to_vec()).collect(),
+    })
+}
+
+fn open_or_hold<'a>(
+    state: &[u8],
+    index: usize,
+    item: &'a [u8],
+    held: &mut Vec<(usize, &'a [u8])>,
+) -> Result<Option<DecryptOutput>, MlsError> {
+    let output = decrypt_message(state, item, false)
+        .map_err(|error| error.with_context(&format!("catch_up item {index}")))?;
+    if output.status == DecryptStatus::FutureEpoch {
+        held.push((index, item));
+        return Ok(None);
+    }
+    Ok(Some(output))
+}
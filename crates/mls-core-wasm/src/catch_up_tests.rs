@@ -0,0 +1,75 @@
+use crate::{
+    catch_up::catch_up,
+    messaging::encrypt_message,
+    model::DecryptStatus,
+    path_update::self_update,
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn catch_up_applies_interleaved_commits_and_flushes_held_messages() {
+    let alice_state = create_single_member_state("group-catch-up", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-catch-up", "bob");
+
+    let first = must(encrypt_message(&added.state, b"one"), "encrypt at epoch 1");
+    let to_two = must(self_update(&added.state), "alice moves to epoch 2");
+    let second = must(encrypt_message(&to_two.state, b"two"), "encrypt at epoch 2");
+    let to_three = must(self_update(&to_two.state), "alice moves to epoch 3");
+    let third = must(
+        encrypt_message(&to_three.state, b"three"),
+        "encrypt at epoch 3",
+    );
+    let to_four = must(self_update(&to_three.state), "alice moves to epoch 4");
+    let fourth = must(
+        encrypt_message(&to_four.state, b"four"),
+        "encrypt at epoch 4",
+    );
+
+    // The epoch-2 message arrives before its commit; the epoch-4 commit never arrives.
+    let items = vec![
+        first,
+        second,
+        to_two.commit,
+        to_three.commit,
+        third,
+        fourth.clone(),
+    ];
+    let caught_up = must(catch_up(&bob_state, &items), "bob catches up");
+
+    assert_eq!(caught_up.epoch, 3);
+    let read: Vec<&[u8]> = caught_up
+        .messages
+        .iter()
+        .map(|message| {
+            assert_eq!(message.status, DecryptStatus::Decrypted);
+            assert_eq!(message.sender_id, "alice");
+            message.plaintext.as_slice()
+        })
+        .collect();
+    assert_eq!(read, vec![&b"one"[..], b"two", b"three"]);
+    assert_eq!(caught_up.pending, vec![fourth]);
+
+    let again = must(
+        catch_up(&caught_up.state, &[to_four.commit]),
+        "bob applies the late commit",
+    );
+    assert_eq!(again.epoch, 4);
+    let flushed = must(
+        catch_up(&again.state, &caught_up.pending),
+        "bob flushes pending",
+    );
+    assert_eq!(flushed.messages.len(), 1);
+    assert_eq!(flushed.messages[0].plaintext, b"four");
+}
+
+#[test]
+fn catch_up_errors_name_the_failing_item() {
+    let alice_state = create_single_member_state("group-catch-up-2", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-catch-up-2", "bob");
+    let message = must(encrypt_message(&added.state, b"ok"), "encrypt");
+
+    let Err(error) = catch_up(&bob_state, &[message, b"not json".to_vec()]) else {
+        panic!("garbage item should fail");
+    };
+    assert!(error.to_string().starts_with("catch_up item 1:"), "{error}");
+}
@@ -0,0 +1,399 @@
+use crate::{
+    crypto::{derive_epoch_secret, sha256, sign_bytes, verify_signature},
+    diagnostics::debug_log,
+    error::MlsError,
+    key_package::{decode_key_package, key_package_ref},
+    model::{
+        CommitData, CommitOperationData, GroupMemberData, GroupStateData, MLS_COMMIT_VERSION,
+        ProcessCommitOutput, ProposalOperationData, UnsignedCommitData, UpdatePathData,
+    },
+    operations::key_package_reused,
+    path_update::apply_update_path,
+    policy::{ensure_can_author, self_signing_key},
+    proposals::verify_proposal,
+    protocol::{
+        add_epoch_secret, current_epoch_secret, decode_group_state, deserialize_json,
+        encode_group_state, ensure_schema_version, self_leaf_index, serialize_json,
+        validate_group_context,
+    },
+    psk::resolve_psk,
+};
+
+/// Commit authored by the local member together with its signed payload bytes.
+pub(crate) struct SignedCommit {
+    pub(crate) commit: CommitData,
+    pub(crate) unsigned_commit_bytes: Vec<u8>,
+}
+
+pub(crate) fn unsigned_commit(commit: &CommitData) -> UnsignedCommitData {
+    UnsignedCommitData {
+        version: commit.version,
+        group_id: commit.group_id.clone(),
+        previous_epoch: commit.previous_epoch,
+        new_epoch: commit.new_epoch,
+        proposer_leaf_index: commit.proposer_leaf_index,
+        operation: commit.operation.clone(),
+        path: commit.path.clone(),
+    }
+}
+
+pub(crate) fn sign_commit(
+    state: &GroupStateData,
+    operation: CommitOperationData,
+) -> Result<SignedCommit, MlsError> {
+    sign_commit_with_path(state, operation, None)
+}
+
+pub(crate) fn sign_commit_with_path(
+    state: &GroupStateData,
+    operation: CommitOperationData,
+    path: Option<UpdatePathData>,
+) -> Result<SignedCommit, MlsError> {
+    ensure_can_author(state, "commits")?;
+    let unsigned = UnsignedCommitData {
+        version: MLS_COMMIT_VERSION,
+        group_id: state.group_id.clone(),
+        previous_epoch: state.epoch,
+        new_epoch: state.epoch.saturating_add(1),
+        proposer_leaf_index: self_leaf_index(state)?,
+        operation,
+        path,
+    };
+    let unsigned_commit_bytes = serialize_json(&unsigned)?;
+    let signature = sign_bytes(self_signing_key(state)?, &unsigned_commit_bytes)?;
+
+    Ok(SignedCommit {
+        commit: CommitData {
+            version: unsigned.version,
+            group_id: unsigned.group_id,
+            previous_epoch: unsigned.previous_epoch,
+            new_epoch: unsigned.new_epoch,
+            proposer_leaf_index: unsigned.proposer_leaf_index,
+            operation: unsigned.operation,
+            path: unsigned.path,
+            signature,
+        },
+        unsigned_commit_bytes,
+    })
+}
+
+/// Moves the state to the commit's epoch using the secret derived from its payload.
+pub(crate) fn advance_epoch(
+    state: &mut GroupStateData,
+    new_epoch: u64,
+    unsigned_commit_bytes: &[u8],
+) -> Result<(), MlsError> {
+    advance_epoch_with_secret(state, new_epoch, unsigned_commit_bytes, &[])
+}
+
+/// Like [`advance_epoch`], additionally mixing in a path secret or PSK.
+pub(crate) fn advance_epoch_with_secret(
+    state: &mut GroupStateData,
+    new_epoch: u64,
+    unsigned_commit_bytes: &[u8],
+    injected_secret: &[u8],
+) -> Result<(), MlsError> {
+    if new_epoch <= state.epoch {
+        return Err(MlsError::InvalidState(format!(
+            "cannot advance from epoch {} to {new_epoch}",
+            state.epoch
+        )));
+    }
+
+    let current_secret = current_epoch_secret(state)?;
+    let mut input = unsigned_commit_bytes.to_vec();
+    input.extend_from_slice(injected_secret);
+    let next_epoch_secret = derive_epoch_secret(&current_secret, &input)?.to_vec();
+
+    let mut transcript_input = state.confirmed_transcript_hash.clone();
+    transcript_input.extend_from_slice(unsigned_commit_bytes);
+    state.confirmed_transcript_hash = sha256(&transcript_input).to_vec();
+
+    state.epoch = new_epoch;
+    // Proposals are bound to the epoch they were sent in.
+    state.pending_proposals.clear();
+    add_epoch_secret(state, new_epoch, next_epoch_secret);
+    Ok(())
+}
+
+/// Decodes a commit after checking its schema version.
+pub(crate) fn decode_commit(commit_bytes: &[u8]) -> Result<CommitData, MlsError> {
+    ensure_schema_version(commit_bytes, "commit", MLS_COMMIT_VERSION)?;
+    deserialize_json(commit_bytes, "commit")
+}
+
+pub(crate) fn verify_commit(
+    state: &GroupStateData,
+    commit: &CommitData,
+) -> Result<Vec<u8>, MlsError> {
+    if commit.version != MLS_COMMIT_VERSION {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported commit version {}",
+            commit.version
+        )));
+    }
+
+    if commit.group_id != state.group_id {
+        return Err(MlsError::WrongGroup(format!(
+            "commit for group {} routed to group {}",
+            commit.group_id, state.group_id
+        )));
+    }
+
+    if commit.previous_epoch != state.epoch {
+        return Err(MlsError::InvalidInput(format!(
+            "commit previous_epoch mismatch: expected {}, got {}",
+            state.epoch, commit.previous_epoch
+        )));
+    }
+
+    if commit.new_epoch != state.epoch.saturating_add(1) {
+        return Err(MlsError::InvalidInput(format!(
+            "commit new_epoch mismatch: expected {}, got {}",
+            state.epoch.saturating_add(1),
+            commit.new_epoch
+        )));
+    }
+
+    let proposer = state
+        .members
+        .iter()
+        .find(|member| member.leaf_index == commit.proposer_leaf_index)
+        .ok_or_else(|| {
+            MlsError::NotFound(format!(
+                "commit proposer leaf {} not found",
+                commit.proposer_leaf_index
+            ))
+        })?;
+
+    let unsigned_commit_bytes = serialize_json(&unsigned_commit(commit))?;
+    verify_signature(
+        &proposer.signing_public_key,
+        &unsigned_commit_bytes,
+        &commit.signature,
+    )?;
+
+    Ok(unsigned_commit_bytes)
+}
+
+pub(crate) fn apply_operation(
+    state: &mut GroupStateData,
+    operation: CommitOperationData,
+) -> Result<(), MlsError> {
+    match operation {
+        CommitOperationData::Add {
+            members,
+            key_package_refs,
+        } => add_leaves(state, members, key_package_refs)?,
+        CommitOperationData::Replace {
+            leaf_index,
+            member,
+            key_package_refs,
+        } => {
+            remove_leaf(state, leaf_index)?;
+            add_leaves(state, vec![member], key_package_refs)?;
+        }
+        CommitOperationData::Update | CommitOperationData::Psk { .. } => {}
+        CommitOperationData::Remove { leaf_index } => remove_leaf(state, leaf_index)?,
+        CommitOperationData::GroupContextExtensions { group_context } => {
+            validate_group_context(&group_context)?;
+            state.group_context = group_context;
+        }
+        CommitOperationData::Proposals { proposals } => {
+            for proposal in &proposals {
+                verify_proposal(state, proposal)?;
+            }
+
+            for proposal in proposals {
+                match proposal.operation {
+                    ProposalOperationData::Add { key_package } => {
+                        add_proposed_member(state, &key_package)?;
+                    }
+                    ProposalOperationData::Remove { leaf_index } => {
+                        remove_leaf(state, leaf_index)?;
+                    }
+                    _ => {
+                        return Err(MlsError::InvalidInput(
+                            "only add and remove proposals can be committed".to_owned(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn add_leaves(
+    state: &mut GroupStateData,
+    members: Vec<GroupMemberData>,
+    key_package_refs: Vec<String>,
+) -> Result<(), MlsError> {
+    for key_package_ref in key_package_refs {
+        if !state
+            .consumed_key_package_refs
+            .insert(key_package_ref.clone())
+        {
+            return Err(MlsError::KeyPackageReused(format!(
+                "cannot apply add commit reusing key package {key_package_ref}"
+            )));
+        }
+    }
+
+    for mut member in members {
+        // Derived locally rather than trusted from the committer.
+        member.leaf_epoch = state.epoch.saturating_add(1);
+        if state
+            .members
+            .iter()
+            .any(|existing| existing.leaf_index == member.leaf_index)
+        {
+            return Err(MlsError::InvalidInput(format!(
+                "cannot apply add commit with existing leaf index {}",
+                member.leaf_index
+            )));
+        }
+
+        if state
+            .members
+            .iter()
+            .any(|existing| existing.user_id == member.user_id)
+        {
+            return Err(MlsError::InvalidInput(format!(
+                "cannot apply add commit with existing user {}",
+                member.user_id
+            )));
+        }
+
+        state.members.push(member);
+    }
+
+    Ok(())
+}
+
+/// Adds a proposed member at the next free leaf so every member derives the same index.
+fn add_proposed_member(
+    state: &mut GroupStateData,
+    key_package_bytes: &[u8],
+) -> Result<(), MlsError> {
+    if state.solo {
+        return Err(MlsError::InvalidInput(
+            "solo groups cannot add members".to_owned(),
+        ));
+    }
+
+    let key_package = decode_key_package(key_package_bytes)?;
+    let key_package_ref = key_package_ref(key_package_bytes);
+    if key_package_reused(state, &key_package, &key_package_ref, &[], &[]) {
+        return Err(MlsError::KeyPackageReused(format!(
+            "add proposal reuses key package {key_package_ref}"
+        )));
+    }
+
+    let leaf_index = state
+        .members
+        .iter()
+        .map(|member| member.leaf_index)
+        .max()
+        .map_or(0, |leaf| leaf.saturating_add(1));
+    let consumed_refs = if key_package.last_resort {
+        Vec::new()
+    } else {
+        vec![key_package_ref.clone()]
+    };
+
+    add_leaves(
+        state,
+        vec![GroupMemberData {
+            user_id: key_package.user_id,
+            leaf_index,
+            signing_public_key: key_package.signing_public_key,
+            hpke_public_key: key_package.hpke_public_key,
+            key_package_ref,
+            leaf_epoch: state.epoch.saturating_add(1),
+        }],
+        consumed_refs,
+    )
+}
+
+fn remove_leaf(state: &mut GroupStateData, leaf_index: u32) -> Result<(), MlsError> {
+    let removed_member = state
+        .members
+        .iter()
+        .find(|member| member.leaf_index == leaf_index)
+        .ok_or_else(|| {
+            MlsError::NotFound(format!(
+                "cannot apply remove commit for unknown leaf index {leaf_index}"
+            ))
+        })?;
+
+    if removed_member.user_id == state.self_user_id {
+        return Err(MlsError::InvalidState(
+            "local member has been removed from group".to_owned(),
+        ));
+    }
+
+    state
+        .members
+        .retain(|member| member.leaf_index != leaf_index);
+    Ok(())
+}
+
+pub(crate) fn process_commit(
+    group_state_bytes: &[u8],
+    commit_bytes: &[u8],
+) -> Result<ProcessCommitOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    let commit = decode_commit(commit_bytes)?;
+    let unsigned_commit_bytes = verify_commit(&state, &commit)?;
+
+    let psk_secret = match &commit.operation {
+        CommitOperationData::Psk { psk } => resolve_psk(&state, psk)?,
+        CommitOperationData::Update if commit.path.is_none() => {
+            return Err(MlsError::InvalidInput(
+                "update commit is missing its update path".to_owned(),
+            ));
+        }
+        _ => Vec::new(),
+    };
+
+    let previous_group_context = state.group_context.clone();
+    apply_operation(&mut state, commit.operation)?;
+
+    let mut injected_secret = match &commit.path {
+        Some(path) => apply_update_path(
+            &mut state,
+            commit.proposer_leaf_index,
+            commit.new_epoch,
+            path,
+        )?,
+        None => Vec::new(),
+    };
+    injected_secret.extend_from_slice(&psk_secret);
+    advance_epoch_with_secret(
+        &mut state,
+        commit.new_epoch,
+        &unsigned_commit_bytes,
+        &injected_secret,
+    )?;
+
+    let group_context_changed =
+        (state.group_context != previous_group_context).then(|| state.group_context.clone());
+    debug_log(|| {
+        format!(
+            "process_commit {}: epoch {} -> {} by leaf {}, update path {}",
+            state.group_id,
+            commit.previous_epoch,
+            state.epoch,
+            commit.proposer_leaf_index,
+            commit.path.is_some()
+        )
+    });
+
+    Ok(ProcessCommitOutput {
+        state: encode_group_state(&state)?,
+        new_epoch: state.epoch,
+        group_context_changed,
+    })
+}
@@ -1,6 +1,6 @@
 use chacha20poly1305::{
     ChaCha20Poly1305,
-    aead::{Aead, KeyInit, Payload},
+    aead::{Aead, AeadInPlace, KeyInit, Payload},
 };
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use hkdf::Hkdf;
@@ -14,6 +14,7 @@ const SIGNATURE_LEN: usize = 64;
 const X25519_KEY_LEN: usize = 32;
 const CHACHA20_KEY_LEN: usize = 32;
 const CHACHA20_NONCE_LEN: usize = 12;
+const CHACHA20_TAG_LEN: usize = 16;
 
 /// Validates and clones a fixed-size key byte slice.
 pub fn require_key_bytes<const LEN: usize>(
@@ -98,6 +99,13 @@ pub fn generate_x25519_key_pair() -> Result<(Vec<u8>, Vec<u8>), MlsError> {
     ))
 }
 
+/// Returns the X25519 public key for a private key.
+pub fn x25519_public_key(private_key_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let private = require_key_bytes::<X25519_KEY_LEN>(private_key_bytes, "X25519 private key")?;
+    let private_secret = X25519Secret::from(private);
+    Ok(X25519PublicKey::from(&private_secret).as_bytes().to_vec())
+}
+
 /// Computes an X25519 shared secret.
 pub fn x25519_shared_secret(
     private_key_bytes: &[u8],
@@ -156,6 +164,80 @@ pub fn derive_app_message_key(
     hkdf_derive::<CHACHA20_KEY_LEN>(None, &secret, &info)
 }
 
+/// Derives the public epoch authenticator used to confirm members share an epoch.
+pub fn derive_epoch_authenticator(
+    epoch_secret: &[u8],
+    group_id: &str,
+    epoch: u64,
+) -> Result<[u8; 32], MlsError> {
+    let secret = require_key_bytes::<CHACHA20_KEY_LEN>(epoch_secret, "epoch secret")?;
+    let mut info = Vec::with_capacity(64);
+    info.extend_from_slice(b"tearleads-mls/epoch-authenticator/v1:");
+    info.extend_from_slice(group_id.as_bytes());
+    info.extend_from_slice(&epoch.to_be_bytes());
+
+    hkdf_derive::<32>(None, &secret, &info)
+}
+
+/// Derives an application secret from the epoch secret, bound to `label` and `context`.
+pub fn derive_exported_secret(
+    epoch_secret: &[u8],
+    label: &str,
+    context: &[u8],
+) -> Result<[u8; 32], MlsError> {
+    let secret = require_key_bytes::<CHACHA20_KEY_LEN>(epoch_secret, "epoch secret")?;
+    let mut info = Vec::with_capacity(64 + label.len() + context.len());
+    info.extend_from_slice(b"tearleads-mls/exporter/v1:");
+    info.extend_from_slice(&(label.len() as u64).to_be_bytes());
+    info.extend_from_slice(label.as_bytes());
+    info.extend_from_slice(context);
+
+    hkdf_derive::<32>(None, &secret, &info)
+}
+
+/// Derives the key that seals the shared welcome group payload.
+pub fn derive_welcome_group_key(
+    joiner_secret: &[u8],
+    group_id: &str,
+    epoch: u64,
+) -> Result<[u8; CHACHA20_KEY_LEN], MlsError> {
+    let secret = require_key_bytes::<CHACHA20_KEY_LEN>(joiner_secret, "joiner secret")?;
+    let mut info = Vec::with_capacity(64);
+    info.extend_from_slice(b"tearleads-mls/welcome-group-key/v1:");
+    info.extend_from_slice(group_id.as_bytes());
+    info.extend_from_slice(&epoch.to_be_bytes());
+
+    hkdf_derive::<CHACHA20_KEY_LEN>(None, &secret, &info)
+}
+
+/// Derives the key that seals a path secret to one leaf.
+pub fn derive_path_secret_key(
+    shared_secret: &[u8],
+    group_id: &str,
+    epoch: u64,
+    leaf_index: u32,
+) -> Result<[u8; CHACHA20_KEY_LEN], MlsError> {
+    let mut info = Vec::with_capacity(64);
+    info.extend_from_slice(b"tearleads-mls/path-secret-key/v1:");
+    info.extend_from_slice(group_id.as_bytes());
+    info.extend_from_slice(&epoch.to_be_bytes());
+    info.extend_from_slice(&leaf_index.to_be_bytes());
+
+    hkdf_derive::<CHACHA20_KEY_LEN>(None, shared_secret, &info)
+}
+
+/// Compares byte slices without short-circuiting on the first mismatch.
+pub fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    left.iter()
+        .zip(right)
+        .fold(0_u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
 /// Derives a welcome message key from shared secret and metadata.
 pub fn derive_welcome_key(
     shared_secret: &[u8],
@@ -218,6 +300,43 @@ pub fn decrypt_chacha20(
         .map_err(|error| MlsError::Crypto(format!("message decryption failed: {error}")))
 }
 
+/// Decrypts ChaCha20-Poly1305 ciphertext into `out` and returns the plaintext length.
+pub fn decrypt_chacha20_into(
+    key_bytes: &[u8],
+    nonce_bytes: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+    out: &mut [u8],
+) -> Result<usize, MlsError> {
+    let key = require_key_bytes::<CHACHA20_KEY_LEN>(key_bytes, "ChaCha20 key")?;
+    let nonce = require_key_bytes::<CHACHA20_NONCE_LEN>(nonce_bytes, "ChaCha20 nonce")?;
+
+    let plaintext_len = ciphertext
+        .len()
+        .checked_sub(CHACHA20_TAG_LEN)
+        .ok_or_else(|| MlsError::Crypto("ciphertext shorter than tag".to_owned()))?;
+    if out.len() < plaintext_len {
+        return Err(MlsError::InvalidInput(format!(
+            "output buffer too small: need {plaintext_len} bytes, got {}",
+            out.len()
+        )));
+    }
+
+    let (message, tag) = ciphertext.split_at(plaintext_len);
+    let buffer = &mut out[..plaintext_len];
+    buffer.copy_from_slice(message);
+
+    let cipher = ChaCha20Poly1305::new((&key).into());
+    cipher
+        .decrypt_in_place_detached((&nonce).into(), aad, buffer, tag.into())
+        .map_err(|error| {
+            buffer.fill(0);
+            MlsError::Crypto(format!("message decryption failed: {error}"))
+        })?;
+
+    Ok(plaintext_len)
+}
+
 /// Returns a random ChaCha20 nonce.
 pub fn random_nonce() -> Result<[u8; CHACHA20_NONCE_LEN], MlsError> {
     random_bytes::<CHACHA20_NONCE_LEN>()
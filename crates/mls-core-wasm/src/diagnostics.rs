@@ -0,0 +1,45 @@
+//! Opt-in debug logging for field diagnostics.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub(crate) fn verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Emits a debug line when verbose logging is on; `message` only runs then.
+///
+/// Messages must carry public metadata only (group ids, epochs, leaf indexes, statuses),
+/// never key material, secrets, or plaintext.
+pub(crate) fn debug_log(message: impl FnOnce() -> String) {
+    if verbose() {
+        emit(&format!("[mls-core-wasm] {}", message()));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn emit(line: &str) {
+    use js_sys::{Function, Reflect, global};
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let Ok(console) = Reflect::get(&global(), &JsValue::from_str("console")) else {
+        return;
+    };
+    let Ok(debug) = Reflect::get(&console, &JsValue::from_str("debug")) else {
+        return;
+    };
+    if let Ok(debug) = debug.dyn_into::<Function>() {
+        // A throwing console must not fail the protocol operation being logged.
+        let _ = debug.call1(&console, &JsValue::from_str(line));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn emit(line: &str) {
+    eprintln!("{line}");
+}
@@ -0,0 +1,22 @@
+use crate::{
+    diagnostics::{debug_log, set_verbose, verbose},
+    mls_init_with_options,
+};
+
+#[test]
+fn verbose_logging_is_opt_in_and_lazy() {
+    assert!(!verbose());
+    debug_log(|| panic!("silent logging must not build messages"));
+
+    mls_init_with_options(true);
+    assert!(verbose());
+    let mut built = false;
+    debug_log(|| {
+        built = true;
+        String::new()
+    });
+    assert!(built);
+
+    set_verbose(false);
+    assert!(!verbose());
+}
@@ -1,5 +1,30 @@
 use core::fmt::{Display, Formatter};
 
+use serde::Serialize;
+
+/// Item of a multi-item operation an error is attributed to; exported to
+/// JavaScript as the thrown error's `error_context` field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum ErrorContext {
+    /// Key package being added, by reference.
+    KeyPackageRef(String),
+    /// Leaf being removed.
+    LeafIndex(u32),
+    /// Sender leaf of a message being decrypted.
+    SenderLeafIndex(u32),
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::KeyPackageRef(key_package_ref) => write!(f, "key package {key_package_ref}"),
+            Self::LeafIndex(leaf_index) => write!(f, "leaf {leaf_index}"),
+            Self::SenderLeafIndex(leaf_index) => write!(f, "sender leaf {leaf_index}"),
+        }
+    }
+}
+
 /// MLS primitive operation error.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MlsError {
@@ -13,6 +38,81 @@ pub enum MlsError {
     Crypto(String),
     /// Serialization or deserialization failed.
     Serialization(String),
+    /// A one-time key package was offered for a second add.
+    KeyPackageReused(String),
+    /// A removal would shrink the group below its configured minimum size.
+    MinimumGroupSize(String),
+    /// A read-only observer tried to author a message or commit.
+    ReadOnlyClient(String),
+    /// A message or commit was routed to a group other than the one it belongs to.
+    WrongGroup(String),
+    /// The local signing key was deleted, so this member can no longer sign.
+    SigningKeyDeleted(String),
+    /// The state predates leaf keys and must send a self update before it can open update paths.
+    LegacyLeafKey(String),
+    /// `source` failed on one item of a multi-item operation.
+    Item {
+        /// Failing item.
+        context: ErrorContext,
+        /// Underlying error, whose message already names the item.
+        source: Box<MlsError>,
+    },
+}
+
+impl MlsError {
+    /// Prefixes the message with `context`, keeping the error kind.
+    pub fn with_context(self, context: &str) -> Self {
+        match self {
+            Self::InvalidInput(message) => Self::InvalidInput(format!("{context}: {message}")),
+            Self::InvalidState(message) => Self::InvalidState(format!("{context}: {message}")),
+            Self::NotFound(message) => Self::NotFound(format!("{context}: {message}")),
+            Self::Crypto(message) => Self::Crypto(format!("{context}: {message}")),
+            Self::Serialization(message) => Self::Serialization(format!("{context}: {message}")),
+            Self::KeyPackageReused(message) => {
+                Self::KeyPackageReused(format!("{context}: {message}"))
+            }
+            Self::MinimumGroupSize(message) => {
+                Self::MinimumGroupSize(format!("{context}: {message}"))
+            }
+            Self::ReadOnlyClient(message) => Self::ReadOnlyClient(format!("{context}: {message}")),
+            Self::WrongGroup(message) => Self::WrongGroup(format!("{context}: {message}")),
+            Self::SigningKeyDeleted(message) => {
+                Self::SigningKeyDeleted(format!("{context}: {message}"))
+            }
+            Self::LegacyLeafKey(message) => Self::LegacyLeafKey(format!("{context}: {message}")),
+            Self::Item {
+                context: item,
+                source,
+            } => Self::Item {
+                context: item,
+                source: Box::new(source.with_context(context)),
+            },
+        }
+    }
+
+    /// Attributes the error to `context`, naming the item in the message too.
+    pub fn for_item(self, context: ErrorContext) -> Self {
+        let source = Box::new(self.with_context(&context.to_string()));
+        Self::Item { context, source }
+    }
+
+    /// Returns the underlying error kind, looking through item attribution.
+    #[cfg(test)]
+    pub fn kind(&self) -> &Self {
+        match self {
+            Self::Item { source, .. } => source.kind(),
+            other => other,
+        }
+    }
+
+    /// Returns the item the error is attributed to, if any.
+    #[cfg(any(test, target_arch = "wasm32"))]
+    pub fn item_context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Item { context, .. } => Some(context),
+            _ => None,
+        }
+    }
 }
 
 impl Display for MlsError {
@@ -22,7 +122,14 @@ impl Display for MlsError {
             | Self::InvalidState(message)
             | Self::NotFound(message)
             | Self::Crypto(message)
-            | Self::Serialization(message) => f.write_str(message),
+            | Self::Serialization(message)
+            | Self::KeyPackageReused(message)
+            | Self::MinimumGroupSize(message)
+            | Self::ReadOnlyClient(message)
+            | Self::WrongGroup(message)
+            | Self::SigningKeyDeleted(message)
+            | Self::LegacyLeafKey(message) => f.write_str(message),
+            Self::Item { source, .. } => source.fmt(f),
         }
     }
 }
@@ -0,0 +1,48 @@
+use crate::{
+    crypto::derive_exported_secret,
+    error::MlsError,
+    protocol::{current_epoch_secret, decode_group_state, ensure_non_empty},
+};
+
+const ATTACHMENT_LABEL: &str = "attachment";
+const BACKUP_LABEL: &str = "backup";
+
+/// Exports a 32-byte secret for the current epoch that every member derives identically.
+///
+/// Not bound to JavaScript: caller-chosen labels could collide with the labels below, so
+/// applications only reach the exporter through the purpose-specific derivations.
+pub(crate) fn export_secret(
+    group_state_bytes: &[u8],
+    label: &str,
+    context: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    ensure_non_empty(label, "label")?;
+    let state = decode_group_state(group_state_bytes)?;
+    let epoch_secret = current_epoch_secret(&state)?;
+    Ok(derive_exported_secret(&epoch_secret, label, context)?.to_vec())
+}
+
+/// Derives the shared key for encrypted history backups of the current epoch.
+///
+/// The key changes with every epoch, so backups must record the epoch they were sealed
+/// under and members must derive the key before their state moves past it.
+pub(crate) fn derive_backup_key(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    export_secret(group_state_bytes, BACKUP_LABEL, state.group_id.as_bytes())
+}
+
+/// Derives the symmetric key for one out-of-band attachment from a sender-chosen nonce.
+///
+/// The sender ships `nonce` in the message referencing the attachment so any member at the
+/// same epoch can re-derive the key; a fresh nonce per attachment keeps the keys distinct.
+pub(crate) fn derive_attachment_key(
+    group_state_bytes: &[u8],
+    nonce: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    if nonce.is_empty() {
+        return Err(MlsError::InvalidInput(
+            "attachment nonce must not be empty".to_owned(),
+        ));
+    }
+    export_secret(group_state_bytes, ATTACHMENT_LABEL, nonce)
+}
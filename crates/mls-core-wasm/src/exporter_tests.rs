@@ -0,0 +1,67 @@
+use crate::{
+    commit::process_commit,
+    exporter::{derive_attachment_key, derive_backup_key, export_secret},
+    path_update::self_update,
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn members_at_same_epoch_derive_same_backup_key() {
+    let alice_state = create_single_member_state("group-backup", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-backup", "bob");
+
+    let alice_key = must(derive_backup_key(&added.state), "alice backup key");
+    let bob_key = must(derive_backup_key(&bob_state), "bob backup key");
+    assert_eq!(alice_key.len(), 32);
+    assert_eq!(alice_key, bob_key);
+
+    let other_label = must(
+        export_secret(&added.state, "other", b"group-backup"),
+        "other label",
+    );
+    assert_ne!(other_label, alice_key);
+    assert!(export_secret(&added.state, " ", b"").is_err());
+
+    let update = must(self_update(&bob_state), "bob self-updates");
+    let processed = must(
+        process_commit(&added.state, &update.commit),
+        "alice processes",
+    );
+    let rotated = must(derive_backup_key(&processed.state), "rotated key");
+    assert_ne!(rotated, alice_key);
+    assert_eq!(
+        rotated,
+        must(derive_backup_key(&update.state), "bob rotated key")
+    );
+}
+
+#[test]
+fn members_derive_same_attachment_key_from_same_nonce() {
+    let alice_state = create_single_member_state("group-attachment", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-attachment", "bob");
+
+    let alice_key = must(
+        derive_attachment_key(&added.state, b"nonce-1"),
+        "alice attachment key",
+    );
+    let bob_key = must(
+        derive_attachment_key(&bob_state, b"nonce-1"),
+        "bob attachment key",
+    );
+    assert_eq!(alice_key.len(), 32);
+    assert_eq!(alice_key, bob_key);
+
+    let other_nonce = must(
+        derive_attachment_key(&added.state, b"nonce-2"),
+        "other nonce",
+    );
+    assert_ne!(other_nonce, alice_key);
+    assert_ne!(
+        alice_key,
+        must(
+            export_secret(&added.state, "backup", b"nonce-1"),
+            "backup label"
+        )
+    );
+    assert!(derive_attachment_key(&added.state, b"").is_err());
+}
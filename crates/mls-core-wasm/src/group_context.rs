@@ -0,0 +1,37 @@
+use crate::{
+    commit::{advance_epoch, sign_commit},
+    error::MlsError,
+    model::{CommitOperationData, CommitOutput, GroupContextData},
+    protocol::{decode_group_state, encode_group_state, serialize_json, validate_group_context},
+};
+
+pub(crate) fn update_group_context(
+    group_state_bytes: &[u8],
+    group_context: GroupContextData,
+) -> Result<CommitOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    validate_group_context(&group_context)?;
+
+    if group_context == state.group_context {
+        return Err(MlsError::InvalidInput(
+            "group context is unchanged".to_owned(),
+        ));
+    }
+
+    let signed = sign_commit(
+        &state,
+        CommitOperationData::GroupContextExtensions {
+            group_context: group_context.clone(),
+        },
+    )?;
+    let new_epoch = signed.commit.new_epoch;
+
+    state.group_context = group_context;
+    advance_epoch(&mut state, new_epoch, &signed.unsigned_commit_bytes)?;
+
+    Ok(CommitOutput {
+        state: encode_group_state(&state)?,
+        commit: serialize_json(&signed.commit)?,
+        new_epoch,
+    })
+}
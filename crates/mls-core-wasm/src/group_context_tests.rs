@@ -0,0 +1,109 @@
+use crate::{
+    commit::process_commit,
+    group_context::update_group_context,
+    model::{ExternalSenderData, GroupContextData},
+    operations::remove_member,
+    protocol::group_state_metadata,
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn processing_rename_commit_surfaces_new_group_context() {
+    let alice_state = create_single_member_state("group-ctx", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-ctx", "bob");
+
+    let renamed = GroupContextData {
+        name: Some("Design Team".to_owned()),
+        external_senders: vec![ExternalSenderData {
+            user_id: "moderation-service".to_owned(),
+            signing_public_key: vec![7; 32],
+        }],
+        ratchet_tree_out_of_band: false,
+    };
+    let update = must(
+        update_group_context(&added.state, renamed.clone()),
+        "alice renames group",
+    );
+    assert_eq!(update.new_epoch, 2);
+
+    let processed = must(
+        process_commit(&bob_state, &update.commit),
+        "bob processes rename",
+    );
+    assert_eq!(processed.new_epoch, 2);
+    assert_eq!(processed.group_context_changed, Some(renamed.clone()));
+
+    let metadata = must(group_state_metadata(&processed.state), "bob metadata");
+    assert_eq!(metadata.group_context, renamed);
+}
+
+#[test]
+fn membership_commits_do_not_report_group_context_change() {
+    let alice_state = create_single_member_state("group-ctx-2", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-ctx-2", "bob");
+
+    let processed = must(
+        process_commit(&alice_state, &added.commit),
+        "alice replays add commit",
+    );
+    assert_eq!(processed.group_context_changed, None);
+
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    let processed_remove = must(
+        process_commit(&added.state, &removed.commit),
+        "replay remove commit",
+    );
+    assert_eq!(processed_remove.group_context_changed, None);
+}
+
+#[test]
+fn joiner_inherits_group_context_from_welcome() {
+    let alice_state = create_single_member_state("group-ctx-3", "alice");
+    let update = must(
+        update_group_context(
+            &alice_state,
+            GroupContextData {
+                name: Some("Book Club".to_owned()),
+                external_senders: Vec::new(),
+                ratchet_tree_out_of_band: false,
+            },
+        ),
+        "rename before invite",
+    );
+    let (_added, bob_state) = add_and_join(&update.state, "group-ctx-3", "bob");
+
+    let metadata = must(group_state_metadata(&bob_state), "bob metadata");
+    assert_eq!(metadata.group_context.name.as_deref(), Some("Book Club"));
+}
+
+#[test]
+fn update_group_context_rejects_invalid_or_unchanged_context() {
+    let alice_state = create_single_member_state("group-ctx-4", "alice");
+
+    assert!(update_group_context(&alice_state, GroupContextData::default()).is_err());
+    assert!(
+        update_group_context(
+            &alice_state,
+            GroupContextData {
+                name: Some("  ".to_owned()),
+                external_senders: Vec::new(),
+                ratchet_tree_out_of_band: false,
+            },
+        )
+        .is_err()
+    );
+    assert!(
+        update_group_context(
+            &alice_state,
+            GroupContextData {
+                name: None,
+                external_senders: vec![ExternalSenderData {
+                    user_id: "svc".to_owned(),
+                    signing_public_key: vec![1; 16],
+                }],
+                ratchet_tree_out_of_band: false,
+            },
+        )
+        .is_err()
+    );
+}
@@ -0,0 +1,113 @@
+use crate::{
+    crypto::{
+        random_bytes, sign_bytes, signing_key_from_private, verify_signature,
+        verifying_key_from_public,
+    },
+    error::MlsError,
+    model::{
+        CredentialBundleData, GeneratedCredentialOutput, MLS_CREDENTIAL_VERSION,
+        MemberCredentialOutput,
+    },
+    protocol::{decode_group_state, now_ms, serialize_json},
+};
+
+/// Marks user identifiers that carry hex-encoded identity bytes.
+const HEX_IDENTITY_PREFIX: &str = "0x";
+
+/// Maps credential identity bytes to the user identifier stored in groups.
+///
+/// Trimmed UTF-8 identities are used verbatim; anything else, including text that
+/// starts with the hex prefix, is hex-encoded so the mapping stays reversible.
+pub(crate) fn identity_user_id(identity: &[u8]) -> Result<String, MlsError> {
+    if identity.is_empty() {
+        return Err(MlsError::InvalidInput(
+            "identity must not be empty".to_owned(),
+        ));
+    }
+
+    match std::str::from_utf8(identity) {
+        Ok(text) if text.trim() == text && !text.starts_with(HEX_IDENTITY_PREFIX) => {
+            Ok(text.to_owned())
+        }
+        _ => Ok(format!("{HEX_IDENTITY_PREFIX}{}", hex::encode(identity))),
+    }
+}
+
+/// Recovers the credential identity bytes behind a user identifier.
+pub(crate) fn user_id_identity(user_id: &str) -> Result<Vec<u8>, MlsError> {
+    match user_id.strip_prefix(HEX_IDENTITY_PREFIX) {
+        Some(encoded) => hex::decode(encoded).map_err(|error| {
+            MlsError::InvalidInput(format!("invalid hex identity {user_id}: {error}"))
+        }),
+        None => Ok(user_id.as_bytes().to_vec()),
+    }
+}
+
+/// Generates a credential whose identity is arbitrary bytes, such as a serialized
+/// user record or a DID.
+pub(crate) fn generate_credential_from_identity(
+    identity: &[u8],
+) -> Result<GeneratedCredentialOutput, MlsError> {
+    let user_id = identity_user_id(identity)?;
+
+    let private_key = random_bytes::<32>()?.to_vec();
+    let signing_key = signing_key_from_private(&private_key)?;
+    let created_at_ms = now_ms()?;
+
+    let credential_bundle = CredentialBundleData {
+        version: MLS_CREDENTIAL_VERSION,
+        user_id: user_id.clone(),
+        signing_public_key: signing_key.verifying_key().to_bytes().to_vec(),
+        created_at_ms,
+    };
+
+    Ok(GeneratedCredentialOutput {
+        user_id,
+        credential_bundle: serialize_json(&credential_bundle)?,
+        private_key,
+        created_at_ms,
+    })
+}
+
+/// Prefixes blob signatures so they can never be replayed as commit, welcome, or
+/// message signatures made with the same key.
+fn blob_signature_input(data: &[u8]) -> Vec<u8> {
+    let mut input = b"tearleads-mls/blob-signature/v1:".to_vec();
+    input.extend_from_slice(data);
+    input
+}
+
+/// Signs application data outside MLS with the credential's signing key.
+pub(crate) fn sign_blob(credential_private_key: &[u8], data: &[u8]) -> Result<Vec<u8>, MlsError> {
+    sign_bytes(credential_private_key, &blob_signature_input(data))
+}
+
+/// Checks a blob signature against a member's leaf signature key.
+///
+/// A malformed key is an error; a signature that does not match is `false`.
+pub(crate) fn verify_blob(
+    signing_public_key: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> Result<bool, MlsError> {
+    verifying_key_from_public(signing_public_key)?;
+    Ok(verify_signature(signing_public_key, &blob_signature_input(data), signature).is_ok())
+}
+
+/// Lists every leaf's identity and signature key so callers can vet them, e.g. after a join.
+pub(crate) fn member_credentials(
+    group_state_bytes: &[u8],
+) -> Result<Vec<MemberCredentialOutput>, MlsError> {
+    decode_group_state(group_state_bytes)?
+        .members
+        .into_iter()
+        .map(|member| {
+            Ok(MemberCredentialOutput {
+                leaf_index: member.leaf_index,
+                identity: user_id_identity(&member.user_id)?,
+                user_id: member.user_id,
+                signing_public_key: member.signing_public_key,
+            })
+        })
+        .collect()
+}
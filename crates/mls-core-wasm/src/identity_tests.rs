@@ -0,0 +1,136 @@
+use crate::{
+    identity::{
+        generate_credential_from_identity, identity_user_id, member_credentials, sign_blob,
+        user_id_identity, verify_blob,
+    },
+    key_package::{generate_key_package, inspect_key_package},
+    messaging::{decrypt_message, encrypt_message},
+    operations::{add_member, join_group},
+    protocol::{decode_group_state, group_state_metadata},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn identity_user_ids_round_trip() {
+    for identity in [
+        b"alice".as_slice(),
+        b"0xalice".as_slice(),
+        b" padded".as_slice(),
+        &[0xff, 0x00, 0x7f],
+    ] {
+        let user_id = must(identity_user_id(identity), "map identity");
+        assert_eq!(
+            must(user_id_identity(&user_id), "recover identity"),
+            identity
+        );
+    }
+
+    assert_eq!(must(identity_user_id(b"alice"), "text identity"), "alice");
+    assert_eq!(must(identity_user_id(&[0xab]), "byte identity"), "0xab");
+    assert_ne!(
+        must(identity_user_id(b"ab"), "text"),
+        must(identity_user_id(&[0xab]), "bytes")
+    );
+    assert!(identity_user_id(&[]).is_err());
+}
+
+#[test]
+fn byte_identities_surface_through_group_apis() {
+    let identity = [0xde, 0xad, 0xbe, 0xef, 0x00];
+    let alice_state = create_single_member_state("group-identity", "alice");
+    let credential = must(
+        generate_credential_from_identity(&identity),
+        "generate byte credential",
+    );
+    assert_eq!(
+        credential.user_id,
+        must(identity_user_id(&identity), "derived user id")
+    );
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let info = must(inspect_key_package(&key_package.key_package), "inspect");
+    assert_eq!(info.identity, identity);
+
+    let added = must(add_member(&alice_state, &key_package.key_package), "add");
+    let joined = must(
+        join_group(
+            "group-identity",
+            &added.welcome,
+            &key_package.key_package_ref,
+            &key_package.private_key,
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "join",
+    );
+
+    let metadata = must(group_state_metadata(&added.state), "metadata");
+    assert!(
+        metadata
+            .members
+            .iter()
+            .any(|member| member.identity == identity)
+    );
+
+    let ciphertext = must(encrypt_message(&joined.state, b"hi"), "encrypt");
+    let decrypted = must(decrypt_message(&added.state, &ciphertext, false), "decrypt");
+    assert_eq!(decrypted.sender_identity, identity);
+}
+
+#[test]
+fn blob_signatures_verify_against_leaf_signature_key() {
+    let credential = must(generate_credential_from_identity(b"alice"), "credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let leaf_key =
+        must(inspect_key_package(&key_package.key_package), "inspect").signing_public_key;
+
+    let signature = must(sign_blob(&credential.private_key, b"profile v2"), "sign");
+    assert!(must(
+        verify_blob(&leaf_key, b"profile v2", &signature),
+        "verify"
+    ));
+    assert!(!must(
+        verify_blob(&leaf_key, b"profile v3", &signature),
+        "verify tampered"
+    ));
+
+    let mut tampered = signature;
+    tampered[0] ^= 1;
+    assert!(!must(
+        verify_blob(&leaf_key, b"profile v2", &tampered),
+        "verify bad signature"
+    ));
+    assert!(verify_blob(b"short", b"profile v2", &tampered).is_err());
+}
+
+#[test]
+fn member_credentials_list_identity_and_signature_key_per_leaf() {
+    let alice_state = create_single_member_state("group-member-credentials", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-member-credentials", "bob");
+
+    let credentials = must(member_credentials(&bob_state), "member credentials");
+    let alice = must(decode_group_state(&added.state), "decode alice");
+    let bob = must(decode_group_state(&bob_state), "decode bob");
+    let listed: Vec<_> = credentials
+        .iter()
+        .map(|entry| {
+            (
+                entry.leaf_index,
+                entry.identity.as_slice(),
+                &entry.signing_public_key,
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            (0, &b"alice"[..], &alice.self_signing_public_key),
+            (1, &b"bob"[..], &bob.self_signing_public_key),
+        ]
+    );
+}
@@ -0,0 +1,231 @@
+use crate::{
+    crypto::{generate_x25519_key_pair, require_key_bytes, sha256, sign_bytes, verify_signature},
+    error::MlsError,
+    identity::user_id_identity,
+    model::{
+        GeneratedKeyPackageOutput, GroupPolicyData, KeyPackageData, KeyPackageInfoOutput,
+        KeyPackageLifetimeData, KeyPackageValidationOutput, MLS_CIPHERSUITE_ID,
+        MLS_KEY_PACKAGE_VERSION, UnsignedKeyPackageData,
+    },
+    protocol::{
+        deserialize_json, ensure_non_empty, ensure_schema_version, now_ms, serialize_json,
+        verify_credential,
+    },
+};
+
+/// Validity window applied to freshly generated key packages.
+const KEY_PACKAGE_LIFETIME_SECS: u64 = 90 * 24 * 60 * 60;
+
+/// Key package extension types this build understands; it defines none yet.
+const KNOWN_KEY_PACKAGE_EXTENSIONS: [u16; 0] = [];
+
+pub(crate) fn unsigned_key_package(key_package: &KeyPackageData) -> UnsignedKeyPackageData {
+    UnsignedKeyPackageData {
+        version: key_package.version,
+        ciphersuite: key_package.ciphersuite,
+        user_id: key_package.user_id.clone(),
+        signing_public_key: key_package.signing_public_key.clone(),
+        hpke_public_key: key_package.hpke_public_key.clone(),
+        created_at_ms: key_package.created_at_ms,
+        lifetime: key_package.lifetime.clone(),
+        last_resort: key_package.last_resort,
+        extensions: key_package.extensions.clone(),
+    }
+}
+
+pub(crate) fn key_package_ref(key_package_bytes: &[u8]) -> String {
+    hex::encode(sha256(key_package_bytes))
+}
+
+pub(crate) fn decode_key_package(key_package_bytes: &[u8]) -> Result<KeyPackageData, MlsError> {
+    ensure_schema_version(key_package_bytes, "key package", MLS_KEY_PACKAGE_VERSION)?;
+    let key_package: KeyPackageData = deserialize_json(key_package_bytes, "key package")?;
+
+    if key_package.ciphersuite != MLS_CIPHERSUITE_ID {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported key package ciphersuite {}",
+            key_package.ciphersuite
+        )));
+    }
+
+    if key_package.lifetime.not_before > key_package.lifetime.not_after {
+        return Err(MlsError::InvalidInput(
+            "key package lifetime ends before it starts".to_owned(),
+        ));
+    }
+
+    ensure_non_empty(&key_package.user_id, "key_package.user_id")?;
+    require_key_bytes::<32>(
+        &key_package.signing_public_key,
+        "key_package.signing_public_key",
+    )?;
+    require_key_bytes::<32>(&key_package.hpke_public_key, "key_package.hpke_public_key")?;
+
+    let unsigned_bytes = serialize_json(&unsigned_key_package(&key_package))?;
+    verify_signature(
+        &key_package.signing_public_key,
+        &unsigned_bytes,
+        &key_package.signature,
+    )?;
+
+    Ok(key_package)
+}
+
+pub(crate) fn generate_key_package(
+    credential_bundle_bytes: &[u8],
+    credential_private_key_bytes: &[u8],
+) -> Result<GeneratedKeyPackageOutput, MlsError> {
+    build_key_package(credential_bundle_bytes, credential_private_key_bytes, false)
+}
+
+/// Generates a key package that servers may hand out again once one-time packages run out.
+pub(crate) fn generate_last_resort_key_package(
+    credential_bundle_bytes: &[u8],
+    credential_private_key_bytes: &[u8],
+) -> Result<GeneratedKeyPackageOutput, MlsError> {
+    build_key_package(credential_bundle_bytes, credential_private_key_bytes, true)
+}
+
+fn build_key_package(
+    credential_bundle_bytes: &[u8],
+    credential_private_key_bytes: &[u8],
+    last_resort: bool,
+) -> Result<GeneratedKeyPackageOutput, MlsError> {
+    let credential = verify_credential(credential_bundle_bytes, credential_private_key_bytes)?;
+    let created_at_ms = now_ms()?;
+    let not_before = created_at_ms / 1000;
+    let (hpke_private_key, hpke_public_key) = generate_x25519_key_pair()?;
+
+    let unsigned = UnsignedKeyPackageData {
+        version: MLS_KEY_PACKAGE_VERSION,
+        ciphersuite: MLS_CIPHERSUITE_ID,
+        user_id: credential.user_id,
+        signing_public_key: credential.signing_public_key,
+        hpke_public_key,
+        created_at_ms,
+        lifetime: KeyPackageLifetimeData {
+            not_before,
+            not_after: not_before.saturating_add(KEY_PACKAGE_LIFETIME_SECS),
+        },
+        last_resort,
+        extensions: Vec::new(),
+    };
+
+    let unsigned_bytes = serialize_json(&unsigned)?;
+    let signature = sign_bytes(credential_private_key_bytes, &unsigned_bytes)?;
+
+    let key_package = KeyPackageData {
+        version: unsigned.version,
+        ciphersuite: unsigned.ciphersuite,
+        user_id: unsigned.user_id,
+        signing_public_key: unsigned.signing_public_key,
+        hpke_public_key: unsigned.hpke_public_key,
+        created_at_ms: unsigned.created_at_ms,
+        lifetime: unsigned.lifetime,
+        last_resort: unsigned.last_resort,
+        extensions: unsigned.extensions,
+        signature,
+    };
+
+    let key_package_bytes = serialize_json(&key_package)?;
+    let key_package_ref = key_package_ref(&key_package_bytes);
+
+    Ok(GeneratedKeyPackageOutput {
+        key_package: key_package_bytes,
+        key_package_ref,
+        private_key: hpke_private_key,
+        created_at_ms,
+    })
+}
+
+/// Rejects key packages advertising extensions this build does not understand, as
+/// RFC 9420 requires, unless `policy` opts into ignoring them for forward compatibility.
+pub(crate) fn ensure_known_extensions(
+    policy: &GroupPolicyData,
+    key_package: &KeyPackageData,
+) -> Result<(), MlsError> {
+    if policy.allow_unknown_extensions {
+        return Ok(());
+    }
+
+    match key_package
+        .extensions
+        .iter()
+        .find(|extension| !KNOWN_KEY_PACKAGE_EXTENSIONS.contains(&extension.extension_type))
+    {
+        Some(extension) => Err(MlsError::InvalidInput(format!(
+            "key package advertises unknown extension {}",
+            extension.extension_type
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Parses and signature-checks a key package without requiring group membership.
+pub(crate) fn inspect_key_package(
+    key_package_bytes: &[u8],
+) -> Result<KeyPackageInfoOutput, MlsError> {
+    let key_package = decode_key_package(key_package_bytes)?;
+
+    // This backend uses one X25519 key both to open welcomes and as the leaf encryption key.
+    Ok(KeyPackageInfoOutput {
+        key_package_ref: key_package_ref(key_package_bytes),
+        ciphersuite: key_package.ciphersuite,
+        identity: user_id_identity(&key_package.user_id)?,
+        user_id: key_package.user_id,
+        signing_public_key: key_package.signing_public_key,
+        init_key: key_package.hpke_public_key.clone(),
+        leaf_node_encryption_key: key_package.hpke_public_key,
+        lifetime: key_package.lifetime,
+        last_resort: key_package.last_resort,
+    })
+}
+
+/// Returns whether a signature-checked key package's lifetime ended before `now_seconds`.
+pub(crate) fn key_package_expired(
+    key_package_bytes: &[u8],
+    now_seconds: u64,
+) -> Result<bool, MlsError> {
+    let key_package = decode_key_package(key_package_bytes)?;
+    Ok(key_package.lifetime.not_after < now_seconds)
+}
+
+/// Validates an uploaded key package without a group, reporting why it is unusable.
+pub(crate) fn validate_key_package(
+    key_package_bytes: &[u8],
+    now_seconds: u64,
+) -> Result<KeyPackageValidationOutput, MlsError> {
+    let key_package_ref = key_package_ref(key_package_bytes);
+    let key_package = match decode_key_package(key_package_bytes) {
+        Ok(key_package) => key_package,
+        Err(error) => {
+            return Ok(KeyPackageValidationOutput {
+                valid: false,
+                key_package_ref,
+                identity: Vec::new(),
+                ciphersuite: 0,
+                reason: Some(error.to_string()),
+            });
+        }
+    };
+
+    let lifetime = &key_package.lifetime;
+    let reason = if now_seconds < lifetime.not_before {
+        Some(format!(
+            "key package is not valid until {}",
+            lifetime.not_before
+        ))
+    } else if lifetime.not_after < now_seconds {
+        Some(format!("key package expired at {}", lifetime.not_after))
+    } else {
+        None
+    };
+
+    Ok(KeyPackageValidationOutput {
+        valid: reason.is_none(),
+        key_package_ref,
+        identity: user_id_identity(&key_package.user_id)?,
+        ciphersuite: key_package.ciphersuite,
+        reason,
+    })
+}
@@ -0,0 +1,318 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+use crate::{
+    crypto::sign_bytes,
+    error::{ErrorContext, MlsError},
+    key_package::{
+        generate_key_package, generate_last_resort_key_package, inspect_key_package,
+        key_package_expired, key_package_ref, unsigned_key_package, validate_key_package,
+    },
+    model::{
+        GroupPolicyData, KeyPackageData, KeyPackageExtensionData, KeyPackageInfoOutput,
+        MLS_CIPHERSUITE_ID,
+    },
+    operations::{add_member, add_members, remove_member},
+    policy::set_group_policy,
+    protocol::generate_credential,
+    test_support::{create_single_member_state, legacy_payload, must},
+};
+
+/// Signs an edited key package again so only the edit, not the signature, is under test.
+fn resigned(mut key_package: KeyPackageData, credential_private_key: &[u8]) -> Vec<u8> {
+    let unsigned_bytes = must(
+        serde_json::to_vec(&unsigned_key_package(&key_package)),
+        "encode unsigned",
+    );
+    key_package.signature = must(
+        sign_bytes(credential_private_key, &unsigned_bytes),
+        "re-sign",
+    );
+    must(serde_json::to_vec(&key_package), "encode key package")
+}
+
+#[test]
+fn inspect_key_package_exposes_public_contents() {
+    let credential = must(generate_credential("alice"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+
+    let info = must(
+        inspect_key_package(&generated.key_package),
+        "inspect key package",
+    );
+    assert_eq!(info.key_package_ref, generated.key_package_ref);
+    assert_eq!(info.ciphersuite, MLS_CIPHERSUITE_ID);
+    assert_eq!(info.user_id, "alice");
+    assert_eq!(info.signing_public_key.len(), 32);
+    assert_eq!(info.init_key.len(), 32);
+    assert_eq!(info.init_key, info.leaf_node_encryption_key);
+    assert_eq!(info.lifetime.not_before, generated.created_at_ms / 1000);
+    assert_eq!(
+        info.lifetime.not_after - info.lifetime.not_before,
+        90 * 24 * 60 * 60
+    );
+}
+
+#[test]
+fn inspected_key_package_serializes_keys_as_base64() {
+    let credential = must(generate_credential("alice"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let info = must(inspect_key_package(&generated.key_package), "inspect");
+
+    let json: serde_json::Value = must(serde_json::to_value(&info), "serialize info");
+    for (field, bytes) in [
+        ("identity", &info.identity),
+        ("signing_public_key", &info.signing_public_key),
+        ("init_key", &info.init_key),
+        ("leaf_node_encryption_key", &info.leaf_node_encryption_key),
+    ] {
+        assert_eq!(
+            json[field],
+            serde_json::json!(STANDARD.encode(bytes)),
+            "{field}"
+        );
+    }
+    assert_eq!(json["lifetime"]["not_after"], info.lifetime.not_after);
+    let decoded: KeyPackageInfoOutput = must(serde_json::from_value(json), "deserialize info");
+    assert_eq!(decoded, info);
+}
+
+#[test]
+fn version_one_key_packages_are_rejected_with_a_version_error() {
+    let credential = must(generate_credential("alice"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let legacy = legacy_payload(&generated.key_package, 1, &["ciphersuite", "lifetime"]);
+
+    let Err(error) = inspect_key_package(&legacy) else {
+        panic!("version 1 key package was accepted");
+    };
+    assert_eq!(
+        error.to_string(),
+        "unsupported key package version 1; expected 2"
+    );
+}
+
+#[test]
+fn inspect_key_package_rejects_tampered_packages() {
+    let credential = must(generate_credential("alice"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let key_package: KeyPackageData = must(
+        serde_json::from_slice(&generated.key_package),
+        "parse key package",
+    );
+
+    let mut renamed = key_package.clone();
+    renamed.user_id = "mallory".to_owned();
+    let renamed_bytes = must(serde_json::to_vec(&renamed), "encode renamed");
+    assert!(inspect_key_package(&renamed_bytes).is_err());
+
+    let mut extended = key_package;
+    extended.lifetime.not_after += 1;
+    let extended_bytes = must(serde_json::to_vec(&extended), "encode extended");
+    assert!(inspect_key_package(&extended_bytes).is_err());
+
+    assert!(inspect_key_package(b"not a key package").is_err());
+}
+
+#[test]
+fn one_time_key_package_cannot_be_added_twice() {
+    let alice_state = create_single_member_state("group-reuse", "alice");
+    let credential = must(generate_credential("bob"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+
+    let added = must(
+        add_member(&alice_state, &generated.key_package),
+        "first add",
+    );
+    assert!(matches!(
+        add_member(&added.state, &generated.key_package)
+            .as_ref()
+            .map_err(MlsError::kind),
+        Err(MlsError::KeyPackageReused(_))
+    ));
+
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    assert!(matches!(
+        add_member(&removed.state, &generated.key_package)
+            .as_ref()
+            .map_err(MlsError::kind),
+        Err(MlsError::KeyPackageReused(_))
+    ));
+}
+
+#[test]
+fn last_resort_key_package_may_be_added_again() {
+    let alice_state = create_single_member_state("group-last-resort", "alice");
+    let credential = must(generate_credential("bob"), "generate credential");
+    let generated = must(
+        generate_last_resort_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate last resort key package",
+    );
+    let info = must(inspect_key_package(&generated.key_package), "inspect");
+    assert!(info.last_resort);
+
+    let added = must(
+        add_member(&alice_state, &generated.key_package),
+        "first add",
+    );
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    must(
+        add_member(&removed.state, &generated.key_package),
+        "re-add from last resort package",
+    );
+}
+
+#[test]
+fn key_package_expiry_follows_signed_lifetime() {
+    let credential = must(generate_credential("bob"), "generate credential");
+    let valid = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate valid key package",
+    );
+    let mut expired: KeyPackageData = must(
+        serde_json::from_slice(&valid.key_package),
+        "parse key package",
+    );
+    expired.lifetime.not_before = 1_000;
+    expired.lifetime.not_after = 2_000;
+    let expired_bytes = resigned(expired, &credential.private_key);
+
+    let now_seconds = valid.created_at_ms / 1000;
+    assert!(must(
+        key_package_expired(&expired_bytes, now_seconds),
+        "expired"
+    ));
+    assert!(!must(
+        key_package_expired(&valid.key_package, now_seconds),
+        "valid"
+    ));
+    assert!(!must(
+        key_package_expired(&expired_bytes, 2_000),
+        "last second"
+    ));
+    assert!(key_package_expired(b"not a key package", now_seconds).is_err());
+}
+
+#[test]
+fn validate_key_package_reports_rejection_reasons() {
+    let credential = must(generate_credential("bob"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let info = must(inspect_key_package(&generated.key_package), "inspect");
+    let now_seconds = info.lifetime.not_before;
+
+    let valid = must(
+        validate_key_package(&generated.key_package, now_seconds),
+        "validate fresh package",
+    );
+    assert!(valid.valid);
+    assert_eq!(valid.key_package_ref, generated.key_package_ref);
+    assert_eq!(valid.identity, b"bob");
+    assert_eq!(valid.ciphersuite, MLS_CIPHERSUITE_ID);
+    assert_eq!(valid.reason, None);
+
+    let expired = must(
+        validate_key_package(&generated.key_package, info.lifetime.not_after + 1),
+        "validate expired package",
+    );
+    assert!(!expired.valid);
+    assert_eq!(expired.identity, b"bob");
+    assert!(
+        expired
+            .reason
+            .is_some_and(|reason| reason.contains("expired"))
+    );
+
+    let early = must(
+        validate_key_package(&generated.key_package, now_seconds - 1),
+        "validate early package",
+    );
+    assert!(!early.valid);
+    assert!(
+        early
+            .reason
+            .is_some_and(|reason| reason.contains("not valid until"))
+    );
+
+    let mut tampered: KeyPackageData = must(
+        serde_json::from_slice(&generated.key_package),
+        "parse key package",
+    );
+    tampered.user_id = "mallory".to_owned();
+    let tampered_bytes = must(serde_json::to_vec(&tampered), "encode tampered");
+    let rejected = must(
+        validate_key_package(&tampered_bytes, now_seconds),
+        "validate tampered package",
+    );
+    assert!(!rejected.valid);
+    assert!(rejected.identity.is_empty());
+    assert!(
+        rejected
+            .reason
+            .is_some_and(|reason| reason.contains("signature"))
+    );
+}
+
+#[test]
+fn unknown_key_package_extensions_follow_group_policy() {
+    let credential = must(generate_credential("bob"), "generate credential");
+    let generated = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let mut extended: KeyPackageData = must(
+        serde_json::from_slice(&generated.key_package),
+        "parse key package",
+    );
+    extended.extensions.push(KeyPackageExtensionData {
+        extension_type: 0xff01,
+        data: vec![1, 2, 3],
+    });
+    let extended_bytes = resigned(extended, &credential.private_key);
+
+    let strict_state = create_single_member_state("group-extensions", "alice");
+    let Err(error) = add_members(&strict_state, std::slice::from_ref(&extended_bytes), false)
+    else {
+        panic!("expected unknown extension to be rejected");
+    };
+    assert!(matches!(error.kind(), MlsError::InvalidInput(_)));
+    assert!(error.to_string().contains("unknown extension 65281"));
+    assert_eq!(
+        error.item_context(),
+        Some(&ErrorContext::KeyPackageRef(key_package_ref(
+            &extended_bytes
+        )))
+    );
+
+    let tolerant_state = must(
+        set_group_policy(
+            &strict_state,
+            GroupPolicyData {
+                allow_unknown_extensions: true,
+                ..GroupPolicyData::default()
+            },
+        ),
+        "allow unknown extensions",
+    );
+    let added = must(
+        add_members(&tolerant_state, &[extended_bytes], false),
+        "add forward-compatible key package",
+    );
+    assert_eq!(added.welcomes.len(), 1);
+}
@@ -0,0 +1,59 @@
+use crate::{
+    error::MlsError,
+    model::GroupMetadataEntryOutput,
+    protocol::{decode_group_state, encode_group_state, ensure_non_empty},
+};
+
+const MAX_METADATA_ENTRIES: usize = 64;
+const MAX_METADATA_KEY_BYTES: usize = 128;
+const MAX_METADATA_VALUE_BYTES: usize = 4096;
+
+/// Sets (or clears, when `value` is `None`) a local metadata entry on the group state.
+pub(crate) fn set_group_metadata(
+    group_state_bytes: &[u8],
+    key: &str,
+    value: Option<&str>,
+) -> Result<Vec<u8>, MlsError> {
+    ensure_non_empty(key, "metadata key")?;
+    let mut state = decode_group_state(group_state_bytes)?;
+    let key = key.trim().to_owned();
+    if key.len() > MAX_METADATA_KEY_BYTES {
+        return Err(MlsError::InvalidInput(format!(
+            "group metadata keys are limited to {MAX_METADATA_KEY_BYTES} bytes"
+        )));
+    }
+
+    match value {
+        Some(value) => {
+            if value.len() > MAX_METADATA_VALUE_BYTES {
+                return Err(MlsError::InvalidInput(format!(
+                    "group metadata values are limited to {MAX_METADATA_VALUE_BYTES} bytes"
+                )));
+            }
+            if !state.local_metadata.contains_key(&key)
+                && state.local_metadata.len() >= MAX_METADATA_ENTRIES
+            {
+                return Err(MlsError::InvalidInput(format!(
+                    "group metadata is limited to {MAX_METADATA_ENTRIES} entries"
+                )));
+            }
+            state.local_metadata.insert(key, value.to_owned());
+        }
+        None => {
+            state.local_metadata.remove(&key);
+        }
+    }
+
+    encode_group_state(&state)
+}
+
+pub(crate) fn group_metadata(
+    group_state_bytes: &[u8],
+) -> Result<Vec<GroupMetadataEntryOutput>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    Ok(state
+        .local_metadata
+        .into_iter()
+        .map(|(key, value)| GroupMetadataEntryOutput { key, value })
+        .collect())
+}
@@ -0,0 +1,80 @@
+use crate::{
+    commit::process_commit,
+    local_metadata::{group_metadata, set_group_metadata},
+    protocol::{export_group_state, import_group_state},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn metadata_round_trips_through_export_and_import() {
+    let state = create_single_member_state("group-meta", "alice");
+    let state = must(
+        set_group_metadata(&state, "topic", Some("weekly sync")),
+        "set topic",
+    );
+    let state = must(
+        set_group_metadata(&state, "avatar_url", Some("https://example.test/a.png")),
+        "set avatar",
+    );
+
+    let exported = must(export_group_state(&state), "export state");
+    let imported = must(import_group_state("group-meta", &exported), "import state");
+    let entries = must(group_metadata(&imported.state), "read metadata");
+
+    let pairs: Vec<(&str, &str)> = entries
+        .iter()
+        .map(|entry| (entry.key.as_str(), entry.value.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            ("avatar_url", "https://example.test/a.png"),
+            ("topic", "weekly sync")
+        ]
+    );
+}
+
+#[test]
+fn clearing_metadata_removes_entry() {
+    let state = create_single_member_state("group-meta-2", "alice");
+    let state = must(
+        set_group_metadata(&state, "unread_marker", Some("42")),
+        "set marker",
+    );
+    let state = must(set_group_metadata(&state, "unread_marker", None), "clear");
+
+    assert!(must(group_metadata(&state), "read metadata").is_empty());
+    assert!(set_group_metadata(&state, " ", Some("value")).is_err());
+}
+
+#[test]
+fn metadata_is_local_and_survives_commits() {
+    let alice_state = create_single_member_state("group-meta-3", "alice");
+    let alice_state = must(
+        set_group_metadata(&alice_state, "topic", Some("private note")),
+        "alice sets topic",
+    );
+    let (added, bob_state) = add_and_join(&alice_state, "group-meta-3", "bob");
+
+    assert_eq!(
+        must(group_metadata(&added.state), "alice metadata").len(),
+        1
+    );
+    assert!(must(group_metadata(&bob_state), "bob metadata").is_empty());
+
+    let replayed = must(
+        process_commit(&alice_state, &added.commit),
+        "alice replays add commit",
+    );
+    assert_eq!(must(group_metadata(&replayed.state), "replayed").len(), 1);
+}
+
+#[test]
+fn oversized_metadata_keys_and_values_are_rejected() {
+    let state = create_single_member_state("group-meta-4", "alice");
+
+    assert!(set_group_metadata(&state, &"k".repeat(128), Some("v")).is_ok());
+    assert!(set_group_metadata(&state, &"k".repeat(129), Some("v")).is_err());
+    assert!(set_group_metadata(&state, "topic", Some(&"v".repeat(4096))).is_ok());
+    assert!(set_group_metadata(&state, "topic", Some(&"v".repeat(4097))).is_err());
+}
@@ -1,10 +1,16 @@
 use crate::{
     crypto::{
-        decrypt_chacha20, derive_app_message_key, encrypt_chacha20, random_nonce, sign_bytes,
-        verify_signature,
+        decrypt_chacha20, decrypt_chacha20_into, derive_app_message_key, encrypt_chacha20,
+        random_nonce, sign_bytes, verify_signature,
     },
-    error::MlsError,
-    model::{AppMessageData, DecryptOutput, MLS_APP_MESSAGE_VERSION, UnsignedAppMessageData},
+    diagnostics::debug_log,
+    error::{ErrorContext, MlsError},
+    identity::user_id_identity,
+    model::{
+        AppMessageData, DecryptIntoOutput, DecryptOutput, DecryptStatus, GroupStateData,
+        MLS_APP_MESSAGE_VERSION, UnsignedAppMessageData,
+    },
+    policy::{ensure_can_author, self_signing_key},
     protocol::{
         current_epoch_secret, decode_group_state, epoch_secret_for, metadata_bytes,
         self_leaf_index, serialize_json,
@@ -15,10 +21,32 @@ pub(crate) fn encrypt_message(
     group_state_bytes: &[u8],
     plaintext: &[u8],
 ) -> Result<Vec<u8>, MlsError> {
+    seal_app_message(&decode_group_state(group_state_bytes)?, plaintext)
+}
+
+/// Re-encrypts chosen history under the current epoch so late joiners can read it.
+///
+/// Nothing is shared automatically; callers decide which plaintexts to expose.
+pub(crate) fn reencrypt_history(
+    group_state_bytes: &[u8],
+    plaintexts: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, MlsError> {
     let state = decode_group_state(group_state_bytes)?;
-    let sender_leaf_index = self_leaf_index(&state)?;
+    plaintexts
+        .iter()
+        .enumerate()
+        .map(|(index, plaintext)| {
+            seal_app_message(&state, plaintext)
+                .map_err(|error| error.with_context(&format!("reencrypt_history {index}")))
+        })
+        .collect()
+}
+
+fn seal_app_message(state: &GroupStateData, plaintext: &[u8]) -> Result<Vec<u8>, MlsError> {
+    ensure_can_author(state, "application messages")?;
+    let sender_leaf_index = self_leaf_index(state)?;
 
-    let epoch_secret = current_epoch_secret(&state)?;
+    let epoch_secret = current_epoch_secret(state)?;
     let message_key = derive_app_message_key(&epoch_secret, &state.group_id, state.epoch)?;
 
     let nonce = random_nonce()?.to_vec();
@@ -27,7 +55,7 @@ pub(crate) fn encrypt_message(
 
     let unsigned_message = UnsignedAppMessageData {
         version: MLS_APP_MESSAGE_VERSION,
-        group_id: state.group_id,
+        group_id: state.group_id.clone(),
         epoch: state.epoch,
         sender_leaf_index,
         nonce,
@@ -35,7 +63,7 @@ pub(crate) fn encrypt_message(
     };
 
     let unsigned_message_bytes = serialize_json(&unsigned_message)?;
-    let signature = sign_bytes(&state.self_signing_private_key, &unsigned_message_bytes)?;
+    let signature = sign_bytes(self_signing_key(state)?, &unsigned_message_bytes)?;
 
     let message = AppMessageData {
         version: unsigned_message.version,
@@ -50,11 +78,19 @@ pub(crate) fn encrypt_message(
     serialize_json(&message)
 }
 
-pub(crate) fn decrypt_message(
-    group_state_bytes: &[u8],
+/// Verified application message whose sender has been resolved against the group.
+struct OpenedAppMessage {
+    message: AppMessageData,
+    status: DecryptStatus,
+    sender_id: String,
+    sender_signature_key: Vec<u8>,
+    authenticated_data: Vec<u8>,
+}
+
+fn open_app_message(
+    state: &GroupStateData,
     ciphertext: &[u8],
-) -> Result<DecryptOutput, MlsError> {
-    let state = decode_group_state(group_state_bytes)?;
+) -> Result<OpenedAppMessage, MlsError> {
     let message: AppMessageData =
         crate::protocol::deserialize_json(ciphertext, "application message")?;
 
@@ -66,22 +102,52 @@ pub(crate) fn decrypt_message(
     }
 
     if message.group_id != state.group_id {
-        return Err(MlsError::InvalidInput(format!(
-            "application message group mismatch: expected {}, got {}",
-            state.group_id, message.group_id
+        return Err(MlsError::WrongGroup(format!(
+            "application message for group {} routed to group {}",
+            message.group_id, state.group_id
         )));
     }
 
-    let sender = state
+    let authenticated_data =
+        metadata_bytes(&message.group_id, message.epoch, message.sender_leaf_index)?;
+
+    // The sender may hold keys from a commit we have not seen, so neither its
+    // leaf nor the epoch secret can be trusted yet.
+    if message.epoch > state.epoch {
+        return Ok(OpenedAppMessage {
+            message,
+            status: DecryptStatus::FutureEpoch,
+            sender_id: String::new(),
+            sender_signature_key: Vec::new(),
+            authenticated_data,
+        });
+    }
+
+    let sender = match state
         .members
         .iter()
         .find(|member| member.leaf_index == message.sender_leaf_index)
-        .ok_or_else(|| {
-            MlsError::NotFound(format!(
-                "application message sender leaf {} not found",
-                message.sender_leaf_index
-            ))
-        })?;
+    {
+        Some(sender) if message.epoch >= sender.leaf_epoch => sender,
+        // A message from an earlier epoch may predate its sender's removal, or
+        // its leaf may since have gone to a new member. The signature key is
+        // gone, so report it without opening the unverifiable plaintext.
+        _ if message.epoch < state.epoch => {
+            return Ok(OpenedAppMessage {
+                message,
+                status: DecryptStatus::SenderNotInGroup,
+                sender_id: String::new(),
+                sender_signature_key: Vec::new(),
+                authenticated_data,
+            });
+        }
+        _ => {
+            return Err(
+                MlsError::NotFound("application message sender not found".to_owned())
+                    .for_item(ErrorContext::SenderLeafIndex(message.sender_leaf_index)),
+            );
+        }
+    };
 
     let unsigned_message = UnsignedAppMessageData {
         version: message.version,
@@ -97,22 +163,124 @@ pub(crate) fn decrypt_message(
         &sender.signing_public_key,
         &unsigned_message_bytes,
         &message.signature,
-    )?;
+    )
+    .map_err(|error| error.for_item(ErrorContext::SenderLeafIndex(message.sender_leaf_index)))?;
+
+    // Relays may echo our own messages back; report them instead of decrypting.
+    // Solo groups exist to read back their own messages, so they skip this.
+    let status = if !state.solo && sender.user_id == state.self_user_id {
+        DecryptStatus::OwnMessage
+    } else {
+        DecryptStatus::Decrypted
+    };
+
+    Ok(OpenedAppMessage {
+        status,
+        sender_id: sender.user_id.clone(),
+        sender_signature_key: sender.signing_public_key.clone(),
+        authenticated_data,
+        message,
+    })
+}
 
+fn message_key(state: &GroupStateData, message: &AppMessageData) -> Result<[u8; 32], MlsError> {
     let epoch_secret = epoch_secret_for(&state.epoch_secrets, message.epoch)?;
-    let message_key = derive_app_message_key(&epoch_secret, &message.group_id, message.epoch)?;
-    let authenticated_data =
-        metadata_bytes(&message.group_id, message.epoch, message.sender_leaf_index)?;
-    let plaintext = decrypt_chacha20(
-        &message_key,
-        &message.nonce,
-        &message.ciphertext,
-        &authenticated_data,
-    )?;
+    derive_app_message_key(&epoch_secret, &message.group_id, message.epoch)
+}
+
+fn decrypt_error(message: &AppMessageData) -> impl Fn(MlsError) -> MlsError {
+    let sender_leaf_index = message.sender_leaf_index;
+    move |error| error.for_item(ErrorContext::SenderLeafIndex(sender_leaf_index))
+}
+
+/// Decrypts an application message.
+///
+/// With `include_framing`, signature-verified messages also return their canonical
+/// re-serialization so validating relays can forward a normalized blob.
+pub(crate) fn decrypt_message(
+    group_state_bytes: &[u8],
+    ciphertext: &[u8],
+    include_framing: bool,
+) -> Result<DecryptOutput, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let opened = open_app_message(&state, ciphertext)?;
+    debug_log(|| {
+        format!(
+            "decrypt_message {}: epoch {} from leaf {}, status {:?}",
+            state.group_id, opened.message.epoch, opened.message.sender_leaf_index, opened.status
+        )
+    });
+
+    let verified = matches!(
+        opened.status,
+        DecryptStatus::Decrypted | DecryptStatus::OwnMessage
+    );
+    let framing = if include_framing && verified {
+        serialize_json(&opened.message)?
+    } else {
+        Vec::new()
+    };
+
+    let plaintext = match opened.status {
+        DecryptStatus::OwnMessage
+        | DecryptStatus::FutureEpoch
+        | DecryptStatus::SenderNotInGroup => Vec::new(),
+        DecryptStatus::Decrypted => decrypt_chacha20(
+            &message_key(&state, &opened.message)?,
+            &opened.message.nonce,
+            &opened.message.ciphertext,
+            &opened.authenticated_data,
+        )
+        .map_err(decrypt_error(&opened.message))?,
+    };
 
     Ok(DecryptOutput {
-        sender_id: sender.user_id.clone(),
+        status: opened.status,
+        epoch: opened.message.epoch,
+        sender_identity: user_id_identity(&opened.sender_id)?,
+        sender_id: opened.sender_id,
+        sender_signature_key: opened.sender_signature_key,
+        authenticated: verified,
         plaintext,
-        authenticated_data,
+        authenticated_data: opened.authenticated_data,
+        framing,
+    })
+}
+
+/// Decrypts an application message into `out` without allocating the plaintext.
+pub(crate) fn decrypt_message_into(
+    group_state_bytes: &[u8],
+    ciphertext: &[u8],
+    out: &mut [u8],
+) -> Result<DecryptIntoOutput, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let opened = open_app_message(&state, ciphertext)?;
+
+    let plaintext_len = match opened.status {
+        DecryptStatus::OwnMessage
+        | DecryptStatus::FutureEpoch
+        | DecryptStatus::SenderNotInGroup => 0,
+        DecryptStatus::Decrypted => decrypt_chacha20_into(
+            &message_key(&state, &opened.message)?,
+            &opened.message.nonce,
+            &opened.message.ciphertext,
+            &opened.authenticated_data,
+            out,
+        )
+        .map_err(decrypt_error(&opened.message))?,
+    };
+
+    Ok(DecryptIntoOutput {
+        status: opened.status,
+        epoch: opened.message.epoch,
+        sender_identity: user_id_identity(&opened.sender_id)?,
+        sender_id: opened.sender_id,
+        sender_signature_key: opened.sender_signature_key,
+        authenticated: matches!(
+            opened.status,
+            DecryptStatus::Decrypted | DecryptStatus::OwnMessage
+        ),
+        plaintext_len,
+        authenticated_data: opened.authenticated_data,
     })
 }
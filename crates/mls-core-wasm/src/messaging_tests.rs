@@ -0,0 +1,318 @@
+use crate::{
+    commit::process_commit,
+    crypto::{derive_app_message_key, encrypt_chacha20, random_nonce, sign_bytes},
+    error::{ErrorContext, MlsError},
+    messaging::{decrypt_message, decrypt_message_into, encrypt_message, reencrypt_history},
+    model::{AppMessageData, DecryptStatus, MLS_APP_MESSAGE_VERSION, UnsignedAppMessageData},
+    operations::remove_member,
+    path_update::self_update,
+    protocol::{current_epoch_secret, decode_group_state, metadata_bytes, serialize_json},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn decrypting_own_message_reports_echo() {
+    let alice_state = create_single_member_state("group-echo", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-echo", "bob");
+
+    let ciphertext = must(encrypt_message(&added.state, b"hello"), "alice encrypts");
+
+    let echoed = must(
+        decrypt_message(&added.state, &ciphertext, false),
+        "alice decrypts echo",
+    );
+    assert_eq!(echoed.status, DecryptStatus::OwnMessage);
+    assert_eq!(echoed.sender_id, "alice");
+    assert!(echoed.plaintext.is_empty());
+
+    let received = must(
+        decrypt_message(&bob_state, &ciphertext, false),
+        "bob decrypts",
+    );
+    assert_eq!(received.status, DecryptStatus::Decrypted);
+    assert_eq!(received.plaintext, b"hello");
+    assert_eq!(received.sender_signature_key, echoed.sender_signature_key);
+    let alice = must(decode_group_state(&added.state), "decode alice state");
+    assert_eq!(received.sender_signature_key, alice.self_signing_public_key);
+}
+
+#[test]
+fn decrypt_into_writes_plaintext_to_caller_buffer() {
+    let alice_state = create_single_member_state("group-into", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-into", "bob");
+    let ciphertext = must(encrypt_message(&added.state, b"hello"), "alice encrypts");
+
+    let mut small = [0_u8; 4];
+    let error = match decrypt_message_into(&bob_state, &ciphertext, &mut small) {
+        Ok(_) => panic!("short buffer should fail"),
+        Err(error) => error.to_string(),
+    };
+    assert!(error.contains("need 5 bytes"), "{error}");
+
+    let mut buffer = [0_u8; 16];
+    let output = must(
+        decrypt_message_into(&bob_state, &ciphertext, &mut buffer),
+        "bob decrypts into buffer",
+    );
+    assert_eq!(output.status, DecryptStatus::Decrypted);
+    assert_eq!(output.sender_id, "alice");
+    assert_eq!(&buffer[..output.plaintext_len], b"hello");
+}
+
+#[test]
+fn message_from_removed_sender_reports_sender_not_in_group() {
+    let alice_state = create_single_member_state("group-orphan", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-orphan", "bob");
+    let ciphertext = must(encrypt_message(&bob_state, b"late"), "bob encrypts");
+
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    let orphaned = must(
+        decrypt_message(&removed.state, &ciphertext, false),
+        "alice processes orphaned message",
+    );
+    assert_eq!(orphaned.status, DecryptStatus::SenderNotInGroup);
+    assert!(!orphaned.authenticated);
+    assert!(orphaned.sender_id.is_empty());
+    assert!(orphaned.plaintext.is_empty());
+
+    let current = must(encrypt_message(&removed.state, b"now"), "alice encrypts");
+    let mut unknown: serde_json::Value = must(serde_json::from_slice(&current), "parse message");
+    unknown["sender_leaf_index"] = serde_json::json!(9);
+    let unknown = must(serde_json::to_vec(&unknown), "encode message");
+    let Err(error) = decrypt_message(&removed.state, &unknown, false) else {
+        panic!("expected unknown sender to be rejected");
+    };
+    assert!(matches!(error.kind(), MlsError::NotFound(_)));
+    assert_eq!(
+        error.item_context(),
+        Some(&ErrorContext::SenderLeafIndex(9))
+    );
+    assert!(error.to_string().contains("sender leaf 9"));
+}
+
+#[test]
+fn message_from_a_reused_leaf_reports_sender_not_in_group() {
+    let alice_state = create_single_member_state("group-reuse", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-reuse", "bob");
+    let ciphertext = must(encrypt_message(&bob_state, b"before"), "bob encrypts");
+
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    let (readded, carol_state) = add_and_join(&removed.state, "group-reuse", "carol");
+    let state = must(decode_group_state(&readded.state), "decode alice state");
+    assert!(
+        state
+            .members
+            .iter()
+            .any(|member| member.user_id == "carol" && member.leaf_index == 1)
+    );
+
+    let stale = must(
+        decrypt_message(&readded.state, &ciphertext, false),
+        "alice processes bob's message",
+    );
+    assert_eq!(stale.status, DecryptStatus::SenderNotInGroup);
+    assert!(!stale.authenticated);
+    assert!(stale.sender_id.is_empty());
+    assert!(stale.plaintext.is_empty());
+
+    let fresh = must(encrypt_message(&carol_state, b"after"), "carol encrypts");
+    let current = must(
+        decrypt_message(&readded.state, &fresh, false),
+        "alice processes carol's message",
+    );
+    assert_eq!(current.status, DecryptStatus::Decrypted);
+    assert!(current.authenticated);
+    assert_eq!(current.sender_id, "carol");
+}
+
+/// Seals `plaintext` under `state`'s epoch secret as if sent from `sender_leaf_index`, signed
+/// with `state`'s own key: what a member holding the epoch secret could forge.
+fn forge_message(state_bytes: &[u8], sender_leaf_index: u32, plaintext: &[u8]) -> Vec<u8> {
+    let state = must(decode_group_state(state_bytes), "decode forger state");
+    let epoch_secret = must(current_epoch_secret(&state), "epoch secret");
+    let key = must(
+        derive_app_message_key(&epoch_secret, &state.group_id, state.epoch),
+        "message key",
+    );
+    let nonce = must(random_nonce(), "nonce").to_vec();
+    let aad = must(
+        metadata_bytes(&state.group_id, state.epoch, sender_leaf_index),
+        "metadata",
+    );
+    let unsigned = UnsignedAppMessageData {
+        version: MLS_APP_MESSAGE_VERSION,
+        group_id: state.group_id.clone(),
+        epoch: state.epoch,
+        sender_leaf_index,
+        ciphertext: must(encrypt_chacha20(&key, &nonce, plaintext, &aad), "encrypt"),
+        nonce,
+    };
+    let signature = must(
+        sign_bytes(
+            &state.self_signing_private_key,
+            &must(serialize_json(&unsigned), "encode unsigned"),
+        ),
+        "sign",
+    );
+    must(
+        serialize_json(&AppMessageData {
+            version: unsigned.version,
+            group_id: unsigned.group_id,
+            epoch: unsigned.epoch,
+            sender_leaf_index: unsigned.sender_leaf_index,
+            nonce: unsigned.nonce,
+            ciphertext: unsigned.ciphertext,
+            signature,
+        }),
+        "encode message",
+    )
+}
+
+#[test]
+fn forged_message_under_a_removed_leaf_discloses_nothing() {
+    let alice_state = create_single_member_state("group-forged", "alice");
+    let (added_bob, bob_state) = add_and_join(&alice_state, "group-forged", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added_bob.state, "group-forged", "carol");
+    let bob_state = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob processes carol add",
+    )
+    .state;
+    let forged = forge_message(&bob_state, 2, b"carol says hi");
+
+    let removed = must(remove_member(&added_carol.state, 2), "remove carol");
+    let opened = must(
+        decrypt_message(&removed.state, &forged, false),
+        "alice opens forged message",
+    );
+    assert_eq!(opened.status, DecryptStatus::SenderNotInGroup);
+    assert!(!opened.authenticated);
+    assert!(opened.plaintext.is_empty());
+
+    let mut out = [0_u8; 32];
+    let opened = must(
+        decrypt_message_into(&removed.state, &forged, &mut out),
+        "alice opens forged message into buffer",
+    );
+    assert_eq!(opened.status, DecryptStatus::SenderNotInGroup);
+    assert_eq!(opened.plaintext_len, 0);
+    assert_eq!(out, [0_u8; 32]);
+}
+
+#[test]
+fn requested_framing_is_canonical_and_accepted_by_peers() {
+    let alice_state = create_single_member_state("group-framing", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-framing", "bob");
+    let ciphertext = must(encrypt_message(&added.state, b"relay me"), "alice encrypts");
+
+    let plain = must(decrypt_message(&bob_state, &ciphertext, false), "decrypt");
+    assert!(plain.framing.is_empty());
+
+    let message: AppMessageData = must(serde_json::from_slice(&ciphertext), "parse message");
+    let reformatted = must(serde_json::to_vec_pretty(&message), "reformat message");
+    let validated = must(
+        decrypt_message(&bob_state, &reformatted, true),
+        "relay validates",
+    );
+    assert_eq!(validated.framing, ciphertext);
+
+    let forwarded = must(
+        decrypt_message(&bob_state, &validated.framing, false),
+        "peer reads forwarded message",
+    );
+    assert_eq!(forwarded.plaintext, b"relay me");
+}
+
+#[test]
+fn late_joiner_reads_reencrypted_history() {
+    let alice_state = create_single_member_state("group-history", "alice");
+    let (added_bob, _bob_state) = add_and_join(&alice_state, "group-history", "bob");
+    let old = must(
+        encrypt_message(&added_bob.state, b"before carol"),
+        "encrypt",
+    );
+
+    let (added_carol, carol_state) = add_and_join(&added_bob.state, "group-history", "carol");
+    assert!(decrypt_message(&carol_state, &old, false).is_err());
+
+    let shared = must(
+        reencrypt_history(
+            &added_carol.state,
+            &[b"before carol".to_vec(), b"second".to_vec()],
+        ),
+        "reencrypt history",
+    );
+    let read: Vec<Vec<u8>> = shared
+        .iter()
+        .map(|message| {
+            must(
+                decrypt_message(&carol_state, message, false),
+                "carol decrypts",
+            )
+            .plaintext
+        })
+        .collect();
+    assert_eq!(read, vec![b"before carol".to_vec(), b"second".to_vec()]);
+}
+
+#[test]
+fn message_one_epoch_ahead_reports_future_epoch_until_commit_is_processed() {
+    let alice_state = create_single_member_state("group-future", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-future", "bob");
+    let current = must(encrypt_message(&added.state, b"now"), "encrypt at epoch 1");
+    let at_epoch = must(
+        decrypt_message(&bob_state, &current, false),
+        "bob reads current epoch",
+    );
+    assert_eq!(at_epoch.status, DecryptStatus::Decrypted);
+    assert_eq!(at_epoch.epoch, 1);
+
+    let update = must(self_update(&added.state), "alice moves to epoch 2");
+    let ahead = must(
+        encrypt_message(&update.state, b"next"),
+        "encrypt at epoch 2",
+    );
+    let buffered = must(
+        decrypt_message(&bob_state, &ahead, true),
+        "bob sees future message",
+    );
+    assert_eq!(buffered.status, DecryptStatus::FutureEpoch);
+    assert_eq!(buffered.epoch, 2);
+    assert!(buffered.sender_id.is_empty());
+    assert!(buffered.plaintext.is_empty());
+    assert!(buffered.framing.is_empty());
+
+    let caught_up = must(
+        process_commit(&bob_state, &update.commit),
+        "bob processes update",
+    );
+    let retried = must(
+        decrypt_message(&caught_up.state, &ahead, false),
+        "bob retries buffered message",
+    );
+    assert_eq!(retried.status, DecryptStatus::Decrypted);
+    assert_eq!(retried.plaintext, b"next");
+}
+
+#[test]
+fn messages_routed_to_another_group_report_wrong_group() {
+    let alice_state = create_single_member_state("group-route-a", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-route-a", "bob");
+    let other_state = create_single_member_state("group-route-b", "alice");
+    let ciphertext = must(encrypt_message(&added.state, b"misrouted"), "encrypt");
+
+    let Err(error) = decrypt_message(&other_state, &ciphertext, false) else {
+        panic!("misrouted message should be rejected");
+    };
+    assert!(matches!(error, MlsError::WrongGroup(_)));
+    assert_eq!(
+        error.to_string(),
+        "application message for group group-route-a routed to group group-route-b"
+    );
+
+    let Err(error) = process_commit(&other_state, &added.commit) else {
+        panic!("misrouted commit should be rejected");
+    };
+    assert!(matches!(error, MlsError::WrongGroup(_)));
+    assert!(error.to_string().contains("group-route-a"));
+}
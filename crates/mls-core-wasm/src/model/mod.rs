@@ -0,0 +1,34 @@
+mod base64_bytes;
+mod output;
+mod state;
+mod wire;
+
+pub use output::*;
+pub use state::*;
+pub use wire::*;
+
+/// MLS state schema version.
+pub const MLS_STATE_VERSION: u8 = 1;
+
+/// Key package schema version; version 2 signs the ciphersuite and lifetime.
+pub const MLS_KEY_PACKAGE_VERSION: u8 = 2;
+
+/// Credential bundle schema version.
+pub const MLS_CREDENTIAL_VERSION: u8 = 1;
+
+/// Commit message schema version; version 2 adds multi-member adds, consumed
+/// key package refs and update paths.
+pub const MLS_COMMIT_VERSION: u8 = 2;
+
+/// Welcome message schema version; version 2 seals one joiner secret per
+/// added key package.
+pub const MLS_WELCOME_VERSION: u8 = 2;
+
+/// Application message schema version.
+pub const MLS_APP_MESSAGE_VERSION: u8 = 1;
+
+/// Proposal message schema version.
+pub const MLS_PROPOSAL_VERSION: u8 = 1;
+
+/// Ciphersuite identifier for MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519.
+pub const MLS_CIPHERSUITE_ID: u16 = 0x0003;
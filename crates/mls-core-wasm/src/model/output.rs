@@ -0,0 +1,433 @@
+use serde::{Deserialize, Serialize};
+
+use super::{state::GroupContextData, wire::KeyPackageLifetimeData};
+
+/// Metadata-only member entry for JavaScript consumers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMemberMetadataOutput {
+    /// User identifier.
+    pub user_id: String,
+    /// Credential identity bytes behind `user_id`.
+    pub identity: Vec<u8>,
+    /// Leaf index.
+    pub leaf_index: u32,
+}
+
+/// Credential a member's leaf carries, for application identity checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberCredentialOutput {
+    /// Leaf index.
+    pub leaf_index: u32,
+    /// User identifier.
+    pub user_id: String,
+    /// Credential identity bytes behind `user_id`.
+    pub identity: Vec<u8>,
+    /// Ed25519 signature public key bytes of the leaf.
+    pub signing_public_key: Vec<u8>,
+}
+
+/// Group state metadata for JavaScript consumers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupStateMetadataOutput {
+    /// Group identifier.
+    pub group_id: String,
+    /// Current epoch.
+    pub epoch: u64,
+    /// Local user identifier.
+    pub self_user_id: String,
+    /// Active members.
+    pub members: Vec<GroupMemberMetadataOutput>,
+    /// Current group context extensions.
+    pub group_context: GroupContextData,
+    /// Whether the local signing key was deleted; omitted when false.
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub signing_key_deleted: bool,
+}
+
+/// Secret-free group snapshot for directory services.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicGroupStateOutput {
+    /// Group identifier.
+    pub group_id: String,
+    /// Current epoch.
+    pub epoch: u64,
+    /// Ciphersuite identifier.
+    pub ciphersuite: u16,
+    /// Number of active members.
+    pub member_count: u32,
+    /// Active members without any key material.
+    pub members: Vec<GroupMemberMetadataOutput>,
+}
+
+/// Credential generation output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedCredentialOutput {
+    /// User id derived from the identity, to construct the owning client with.
+    pub user_id: String,
+    /// Serialized credential bundle bytes.
+    pub credential_bundle: Vec<u8>,
+    /// Ed25519 private key bytes.
+    pub private_key: Vec<u8>,
+    /// Creation timestamp in milliseconds.
+    pub created_at_ms: u64,
+}
+
+/// Key package generation output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratedKeyPackageOutput {
+    /// Serialized key package bytes.
+    pub key_package: Vec<u8>,
+    /// Key package reference (hex SHA-256).
+    pub key_package_ref: String,
+    /// X25519 private key bytes.
+    pub private_key: Vec<u8>,
+    /// Creation timestamp in milliseconds.
+    pub created_at_ms: u64,
+}
+
+/// Public key package contents for delivery-service policy checks. Key and
+/// identity bytes serialize as base64 strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageInfoOutput {
+    /// Key package reference (hex SHA-256).
+    pub key_package_ref: String,
+    /// Ciphersuite identifier.
+    pub ciphersuite: u16,
+    /// Credential identity.
+    pub user_id: String,
+    /// Credential identity bytes behind `user_id`.
+    #[serde(with = "super::base64_bytes")]
+    pub identity: Vec<u8>,
+    /// Ed25519 signature public key bytes.
+    #[serde(with = "super::base64_bytes")]
+    pub signing_public_key: Vec<u8>,
+    /// X25519 init public key bytes used to open welcomes.
+    ///
+    /// This format carries a single HPKE key per package, so it is always
+    /// equal to `leaf_node_encryption_key`; both are reported for callers that
+    /// model them separately.
+    #[serde(with = "super::base64_bytes")]
+    pub init_key: Vec<u8>,
+    /// X25519 leaf node encryption public key bytes; see `init_key`.
+    #[serde(with = "super::base64_bytes")]
+    pub leaf_node_encryption_key: Vec<u8>,
+    /// Validity window in Unix seconds.
+    pub lifetime: KeyPackageLifetimeData,
+    /// Whether the package may be used for more than one add.
+    pub last_resort: bool,
+}
+
+/// Upload-time verdict on a key package, for delivery services filling their pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageValidationOutput {
+    /// Whether the package is well-formed, signed, and within its lifetime.
+    pub valid: bool,
+    /// Key package reference (hex SHA-256) of the submitted bytes.
+    pub key_package_ref: String,
+    /// Credential identity bytes; empty when the package could not be parsed.
+    pub identity: Vec<u8>,
+    /// Ciphersuite identifier; zero when the package could not be parsed.
+    pub ciphersuite: u16,
+    /// Why the package was rejected.
+    pub reason: Option<String>,
+}
+
+/// Add-member output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddMemberOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Serialized commit bytes.
+    pub commit: Vec<u8>,
+    /// Serialized welcome bytes.
+    pub welcome: Vec<u8>,
+    /// Group info bytes.
+    pub group_info: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+}
+
+/// Welcome routing entry for one added key package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberWelcomeOutput {
+    /// Recipient key package reference (hex SHA-256).
+    pub key_package_ref: String,
+    /// Epoch the recipient joins at.
+    pub epoch: u64,
+    /// Ciphersuite identifier of the group.
+    pub ciphersuite: u16,
+    /// Serialized combined welcome bytes shared by all recipients.
+    pub welcome: Vec<u8>,
+}
+
+/// Public header of a welcome, readable before joining.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WelcomeInfoOutput {
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch the welcome joins at.
+    pub epoch: u64,
+    /// Ciphersuite identifier of the group.
+    pub ciphersuite: u16,
+    /// Key package references the welcome carries joiner secrets for.
+    pub key_package_refs: Vec<String>,
+    /// Whether the welcome embeds the member tree, so no out-of-band tree is needed.
+    pub has_ratchet_tree: bool,
+    /// Whether the encrypted group payload is present, so the welcome can be joined.
+    pub encrypted_group_info_present: bool,
+}
+
+/// Integrity report for a persisted group state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupStateReportOutput {
+    /// Whether the state decodes and passes every consistency check.
+    pub valid: bool,
+    /// Epoch recorded in the state; zero when it could not be decoded.
+    pub epoch: u64,
+    /// First integrity failure found.
+    pub reason: Option<String>,
+    /// The state is unusable and the member must rejoin or resync from peers.
+    pub resync_required: bool,
+    /// The state predates leaf keys: it works for messaging, but must send a self update before
+    /// it can follow other members' update paths.
+    pub upgrade_required: bool,
+}
+
+/// Multi-member add output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddMembersOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Serialized commit bytes.
+    pub commit: Vec<u8>,
+    /// Welcome routing entries in key package input order.
+    pub welcomes: Vec<MemberWelcomeOutput>,
+    /// Group info bytes.
+    pub group_info: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+}
+
+/// Multi-member add output with the commit and welcomes framed as one bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddMembersBundleOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Length-prefixed commit and welcome bundle.
+    pub bundle: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+}
+
+/// Welcome entry carried in a commit bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledWelcomeOutput {
+    /// Recipient key package reference (hex SHA-256).
+    pub key_package_ref: String,
+    /// Serialized welcome bytes.
+    pub welcome: Vec<u8>,
+}
+
+/// Commit bundle split back into its parts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitBundleOutput {
+    /// Serialized commit bytes.
+    pub commit: Vec<u8>,
+    /// Welcomes in the order they were packed.
+    pub welcomes: Vec<BundledWelcomeOutput>,
+}
+
+/// Remove-member output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveMemberOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Serialized commit bytes.
+    pub commit: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+}
+
+/// Generic commit output for operations without welcomes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Serialized commit bytes.
+    pub commit: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+}
+
+/// Processed commit output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessCommitOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// New epoch.
+    pub new_epoch: u64,
+    /// Updated group context when the commit changed it.
+    pub group_context_changed: Option<GroupContextData>,
+}
+
+/// Membership effect of a commit that has been verified but not merged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitPreviewOutput {
+    /// Epoch the commit would move the group to.
+    pub new_epoch: u64,
+    /// Members the commit would add.
+    pub added: Vec<GroupMemberMetadataOutput>,
+    /// Members the commit would remove.
+    pub removed: Vec<GroupMemberMetadataOutput>,
+    /// Whether the commit removes the local member.
+    pub removes_self: bool,
+}
+
+/// Forced leave output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForceLeaveOutput {
+    /// Group identifier that was left.
+    pub group_id: String,
+    /// Epoch the leave proposal targets.
+    pub epoch: u64,
+    /// Serialized self-remove proposal bytes for the delivery service.
+    pub proposal: Vec<u8>,
+}
+
+/// Output of storing a received proposal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreProposalOutput {
+    /// Updated serialized group state bytes.
+    pub state: Vec<u8>,
+    /// Reference used to commit the proposal later.
+    pub proposal_ref: String,
+}
+
+/// Outcome of processing an incoming application message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecryptStatus {
+    /// Message was authenticated and decrypted.
+    Decrypted,
+    /// Message was sent by the local leaf and echoed back; plaintext is empty.
+    OwnMessage,
+    /// Sender left the group after sending, or its leaf now belongs to someone
+    /// else. Its signature key is gone, so the signature cannot be checked and
+    /// anyone holding that epoch's secret could have forged the message: the
+    /// plaintext and sender fields are empty.
+    SenderNotInGroup,
+    /// Message is from an epoch this state has not reached yet, typically because
+    /// its commit is still in flight; it is unauthenticated, sender fields and
+    /// plaintext are empty, and it can be retried after processing that commit.
+    FutureEpoch,
+}
+
+/// Whether a welcome should be processed against locally held group state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WelcomeJoinStatus {
+    /// Welcome is newer than the local state, e.g. a re-add after removal.
+    Join,
+    /// Welcome is a replay at or before the local epoch; keep the current state.
+    AlreadyJoined,
+}
+
+/// Decrypted message output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptOutput {
+    /// Processing outcome.
+    pub status: DecryptStatus,
+    /// Epoch the message was sent in.
+    pub epoch: u64,
+    /// Authenticated sender user identifier.
+    pub sender_id: String,
+    /// Credential identity bytes of the sender.
+    pub sender_identity: Vec<u8>,
+    /// Signature key of the sender's leaf, for pinning against unexpected changes.
+    pub sender_signature_key: Vec<u8>,
+    /// Whether the sender's signature was verified; never attribute the
+    /// plaintext of an unauthenticated message to a member.
+    pub authenticated: bool,
+    /// Decrypted plaintext bytes.
+    pub plaintext: Vec<u8>,
+    /// Authenticated metadata bytes.
+    pub authenticated_data: Vec<u8>,
+    /// Canonical message bytes when framing was requested; empty otherwise.
+    pub framing: Vec<u8>,
+}
+
+/// Result of decrypting into a caller-provided buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptIntoOutput {
+    /// Processing outcome.
+    pub status: DecryptStatus,
+    /// Epoch the message was sent in.
+    pub epoch: u64,
+    /// Authenticated sender user identifier.
+    pub sender_id: String,
+    /// Credential identity bytes of the sender.
+    pub sender_identity: Vec<u8>,
+    /// Signature key of the sender's leaf, for pinning against unexpected changes.
+    pub sender_signature_key: Vec<u8>,
+    /// Whether the sender's signature was verified; see [`DecryptOutput`].
+    pub authenticated: bool,
+    /// Number of plaintext bytes written to the start of the buffer.
+    pub plaintext_len: usize,
+    /// Authenticated metadata bytes.
+    pub authenticated_data: Vec<u8>,
+}
+
+/// Result of replaying missed commits and application messages in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatchUpOutput {
+    /// Serialized group state after every commit was merged.
+    pub state: Vec<u8>,
+    /// Epoch the group reached.
+    pub epoch: u64,
+    /// Processed application messages, in the order they became readable.
+    pub messages: Vec<DecryptOutput>,
+    /// Messages still ahead of the reached epoch, to keep buffering.
+    pub pending: Vec<Vec<u8>>,
+}
+
+/// Local group metadata entry for JavaScript consumers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMetadataEntryOutput {
+    /// Metadata key.
+    pub key: String,
+    /// Metadata value.
+    pub value: String,
+}
+
+/// Safety number comparison output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafetyNumberOutput {
+    /// Whether the other authenticator matches the local epoch authenticator.
+    pub matches: bool,
+    /// Local safety number formatted as space-separated five-digit groups.
+    pub safety_number: String,
+}
+
+/// Joined group state plus what the UI needs to render the conversation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinGroupOutput {
+    /// Serialized state of the joined group.
+    pub state: Vec<u8>,
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch the welcome placed us in.
+    pub epoch: u64,
+    /// Ciphersuite identifier of the group.
+    pub ciphersuite: u16,
+    /// Members at the join epoch.
+    pub members: Vec<GroupMemberMetadataOutput>,
+}
+
+/// Group state import output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportStateOutput {
+    /// Normalized serialized state bytes.
+    pub state: Vec<u8>,
+    /// Current epoch in the imported state.
+    pub epoch: u64,
+}
@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::wire::ProposalData;
+
+/// Group member entry in serialized state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMemberData {
+    /// User identifier.
+    pub user_id: String,
+    /// Leaf index in the logical member list.
+    pub leaf_index: u32,
+    /// Ed25519 public key bytes.
+    pub signing_public_key: Vec<u8>,
+    /// X25519 HPKE public key bytes.
+    pub hpke_public_key: Vec<u8>,
+    /// Reference of the key package this leaf was added from; empty for the creator.
+    #[serde(default)]
+    pub key_package_ref: String,
+    /// Epoch in which this member took the leaf; earlier messages at the same
+    /// index came from whoever held it before.
+    #[serde(default)]
+    pub leaf_epoch: u64,
+}
+
+/// External sender authorized to submit proposals to the group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalSenderData {
+    /// External sender identifier.
+    pub user_id: String,
+    /// Ed25519 public key bytes.
+    pub signing_public_key: Vec<u8>,
+}
+
+/// Shared group context extensions agreed through commits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupContextData {
+    /// Display name of the group.
+    pub name: Option<String>,
+    /// External senders allowed to submit proposals.
+    pub external_senders: Vec<ExternalSenderData>,
+    /// Welcomes omit the member tree, so inviters must hand joiners the tree
+    /// from [`crate::mls_export_ratchet_tree`] separately.
+    ///
+    /// Embedding the tree is convenient but grows every welcome with group size;
+    /// large groups can switch it off once a tree distribution channel exists.
+    #[serde(default)]
+    pub ratchet_tree_out_of_band: bool,
+}
+
+/// Local policy applied when this member authors commits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupPolicyData {
+    /// Attach an update path to every removal so removed members lose the next epoch.
+    #[serde(default)]
+    pub rekey_on_remove: bool,
+    /// Refuse removals that would leave fewer members than this; zero disables the check.
+    #[serde(default)]
+    pub min_members: u32,
+    /// Follow the group without authoring messages or commits, e.g. for compliance recording.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Accept key packages advertising extensions this build does not know, ignoring them.
+    #[serde(default)]
+    pub allow_unknown_extensions: bool,
+}
+
+/// Epoch secret entry in serialized state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochSecretData {
+    /// Epoch number.
+    pub epoch: u64,
+    /// Epoch secret bytes.
+    pub secret: Vec<u8>,
+}
+
+/// Persisted group state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupStateData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Current epoch.
+    pub epoch: u64,
+    /// Ciphersuite identifier.
+    pub ciphersuite: u16,
+    /// Local user identifier.
+    pub self_user_id: String,
+    /// Local Ed25519 private key bytes.
+    pub self_signing_private_key: Vec<u8>,
+    /// Local Ed25519 public key bytes.
+    pub self_signing_public_key: Vec<u8>,
+    /// Local X25519 leaf private key bytes matching the self member's HPKE key.
+    #[serde(default)]
+    pub self_hpke_private_key: Vec<u8>,
+    /// Epoch in which the local leaf key was last refreshed.
+    #[serde(default)]
+    pub last_update_epoch: u64,
+    /// Active group members.
+    pub members: Vec<GroupMemberData>,
+    /// Epoch secret history.
+    pub epoch_secrets: Vec<EpochSecretData>,
+    /// Current group context extensions.
+    #[serde(default)]
+    pub group_context: GroupContextData,
+    /// Running hash over every commit applied since group creation.
+    #[serde(default)]
+    pub confirmed_transcript_hash: Vec<u8>,
+    /// Local-only application metadata; never shared with other members.
+    #[serde(default)]
+    pub local_metadata: BTreeMap<String, String>,
+    /// Local commit policy; not shared with other members.
+    #[serde(default)]
+    pub policy: GroupPolicyData,
+    /// Single-member notes-to-self group that decrypts its own messages.
+    #[serde(default)]
+    pub solo: bool,
+    /// Externally agreed pre-shared keys by identifier.
+    #[serde(default)]
+    pub external_psks: BTreeMap<String, Vec<u8>>,
+    /// References of one-time key packages this member has already added.
+    #[serde(default)]
+    pub consumed_key_package_refs: BTreeSet<String>,
+    /// Verified proposals for the current epoch awaiting a commit, by reference.
+    #[serde(default)]
+    pub pending_proposals: BTreeMap<String, ProposalData>,
+}
@@ -0,0 +1,414 @@
+use serde::{Deserialize, Serialize};
+
+use super::state::{GroupContextData, GroupMemberData};
+
+/// Stored credential bundle data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialBundleData {
+    /// Schema version.
+    pub version: u8,
+    /// User identifier.
+    pub user_id: String,
+    /// Ed25519 public key bytes.
+    pub signing_public_key: Vec<u8>,
+    /// Creation timestamp in milliseconds.
+    pub created_at_ms: u64,
+}
+
+/// Key package validity window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageLifetimeData {
+    /// First valid instant in Unix seconds.
+    pub not_before: u64,
+    /// Last valid instant in Unix seconds.
+    pub not_after: u64,
+}
+
+/// Key package payload without signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedKeyPackageData {
+    /// Schema version.
+    pub version: u8,
+    /// Ciphersuite the key package was generated for.
+    pub ciphersuite: u16,
+    /// User identifier.
+    pub user_id: String,
+    /// Ed25519 public key bytes.
+    pub signing_public_key: Vec<u8>,
+    /// X25519 HPKE public key bytes.
+    pub hpke_public_key: Vec<u8>,
+    /// Creation timestamp in milliseconds.
+    pub created_at_ms: u64,
+    /// Validity window in Unix seconds.
+    pub lifetime: KeyPackageLifetimeData,
+    /// Whether the package may be used for more than one add.
+    #[serde(default)]
+    pub last_resort: bool,
+    /// Extensions advertised by the package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<KeyPackageExtensionData>,
+}
+
+/// Extension advertised in a key package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageExtensionData {
+    /// Extension type identifier.
+    pub extension_type: u16,
+    /// Opaque extension payload.
+    pub data: Vec<u8>,
+}
+
+/// Signed key package payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPackageData {
+    /// Schema version.
+    pub version: u8,
+    /// Ciphersuite the key package was generated for.
+    pub ciphersuite: u16,
+    /// User identifier.
+    pub user_id: String,
+    /// Ed25519 public key bytes.
+    pub signing_public_key: Vec<u8>,
+    /// X25519 HPKE public key bytes.
+    pub hpke_public_key: Vec<u8>,
+    /// Creation timestamp in milliseconds.
+    pub created_at_ms: u64,
+    /// Validity window in Unix seconds.
+    pub lifetime: KeyPackageLifetimeData,
+    /// Whether the package may be used for more than one add.
+    #[serde(default)]
+    pub last_resort: bool,
+    /// Extensions advertised by the package.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<KeyPackageExtensionData>,
+    /// Ed25519 signature over [`UnsignedKeyPackageData`].
+    pub signature: Vec<u8>,
+}
+
+/// Path secret sealed to one member's leaf key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathSecretData {
+    /// Recipient leaf index.
+    pub leaf_index: u32,
+    /// Ephemeral X25519 public key bytes.
+    pub ephemeral_public_key: Vec<u8>,
+    /// AEAD nonce bytes.
+    pub nonce: Vec<u8>,
+    /// Encrypted path secret bytes.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Reference to a pre-shared key resolved from local state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PskReferenceData {
+    /// Externally agreed PSK stored under an identifier.
+    External {
+        /// PSK identifier.
+        psk_id: String,
+    },
+    /// Resumption PSK taken from a retained epoch secret.
+    Resumption {
+        /// Epoch whose secret is used.
+        epoch: u64,
+    },
+}
+
+/// Committer leaf key refresh plus fresh path secret for every other member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdatePathData {
+    /// Committer's new X25519 leaf public key bytes.
+    pub hpke_public_key: Vec<u8>,
+    /// Path secret sealed to every other member.
+    pub path_secrets: Vec<PathSecretData>,
+}
+
+/// Commit operation details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CommitOperationData {
+    /// Add new members.
+    Add {
+        /// Added member descriptors.
+        members: Vec<GroupMemberData>,
+        /// References of the one-time key packages consumed by this add.
+        #[serde(default)]
+        key_package_refs: Vec<String>,
+    },
+    /// Refresh only the committer's leaf key; requires an update path.
+    Update,
+    /// Inject a pre-shared key into the next epoch secret.
+    Psk {
+        /// Pre-shared key every member resolves locally.
+        psk: PskReferenceData,
+    },
+    /// Remove an existing member.
+    Remove {
+        /// Removed leaf index.
+        leaf_index: u32,
+    },
+    /// Remove a leaf and add its replacement in the same commit.
+    Replace {
+        /// Removed leaf index.
+        leaf_index: u32,
+        /// Replacement member descriptor.
+        member: GroupMemberData,
+        /// References of the one-time key packages consumed by this add.
+        #[serde(default)]
+        key_package_refs: Vec<String>,
+    },
+    /// Replace the group context extensions.
+    GroupContextExtensions {
+        /// New group context.
+        group_context: GroupContextData,
+    },
+    /// Apply signed proposals received from other members or external senders.
+    Proposals {
+        /// Committed proposals in application order.
+        proposals: Vec<ProposalData>,
+    },
+}
+
+/// Proposal operation details.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalOperationData {
+    /// Add the owner of a key package; its leaf index is assigned when the commit is applied.
+    Add {
+        /// Serialized key package of the member to add.
+        key_package: Vec<u8>,
+    },
+    /// Refresh the committer's leaf key and inject a fresh path secret.
+    Update {
+        /// Committer's new X25519 leaf public key bytes.
+        hpke_public_key: Vec<u8>,
+        /// Path secret sealed to every other member.
+        path_secrets: Vec<PathSecretData>,
+    },
+    /// Inject a pre-shared key into the next epoch secret.
+    Psk {
+        /// Pre-shared key every member resolves locally.
+        psk: PskReferenceData,
+    },
+    /// Remove an existing member.
+    Remove {
+        /// Removed leaf index.
+        leaf_index: u32,
+    },
+}
+
+/// Proposal sender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProposalSenderData {
+    /// Current group member.
+    Member {
+        /// Sender leaf index.
+        leaf_index: u32,
+    },
+    /// External sender listed in the group context.
+    External {
+        /// External sender identifier.
+        user_id: String,
+    },
+}
+
+/// Proposal payload without signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedProposalData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch the proposal applies to.
+    pub epoch: u64,
+    /// Proposal sender.
+    pub sender: ProposalSenderData,
+    /// Proposal operation.
+    pub operation: ProposalOperationData,
+}
+
+/// Signed proposal payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch the proposal applies to.
+    pub epoch: u64,
+    /// Proposal sender.
+    pub sender: ProposalSenderData,
+    /// Proposal operation.
+    pub operation: ProposalOperationData,
+    /// Ed25519 signature over [`UnsignedProposalData`].
+    pub signature: Vec<u8>,
+}
+
+/// Commit payload without signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedCommitData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Previous epoch.
+    pub previous_epoch: u64,
+    /// New epoch.
+    pub new_epoch: u64,
+    /// Proposer leaf index.
+    pub proposer_leaf_index: u32,
+    /// Commit operation.
+    pub operation: CommitOperationData,
+    /// Optional update path injecting fresh entropy.
+    pub path: Option<UpdatePathData>,
+}
+
+/// Signed commit payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Previous epoch.
+    pub previous_epoch: u64,
+    /// New epoch.
+    pub new_epoch: u64,
+    /// Proposer leaf index.
+    pub proposer_leaf_index: u32,
+    /// Commit operation.
+    pub operation: CommitOperationData,
+    /// Optional update path injecting fresh entropy.
+    pub path: Option<UpdatePathData>,
+    /// Ed25519 signature over [`UnsignedCommitData`].
+    pub signature: Vec<u8>,
+}
+
+/// Encrypted welcome payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WelcomeEncryptedData {
+    /// Group identifier.
+    pub group_id: String,
+    /// Joined epoch.
+    pub epoch: u64,
+    /// Epoch secret bytes for the joined epoch.
+    pub epoch_secret: Vec<u8>,
+    /// Active members at the joined epoch; empty when the tree is sent out of band.
+    pub members: Vec<GroupMemberData>,
+    /// SHA-256 of the serialized member list, binding an out-of-band tree to this welcome.
+    pub ratchet_tree_hash: Vec<u8>,
+    /// Ciphersuite identifier.
+    pub ciphersuite: u16,
+    /// Group context extensions at the joined epoch.
+    pub group_context: GroupContextData,
+    /// Confirmed transcript hash at the joined epoch.
+    pub confirmed_transcript_hash: Vec<u8>,
+}
+
+/// Per-recipient joiner secret sealed to a key package's HPKE key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WelcomeSecretData {
+    /// Key package reference of the recipient.
+    pub key_package_ref: String,
+    /// Ephemeral X25519 public key bytes.
+    pub ephemeral_public_key: Vec<u8>,
+    /// AEAD nonce bytes.
+    pub nonce: Vec<u8>,
+    /// Encrypted joiner secret bytes.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Welcome payload without signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedWelcomeData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Joined epoch.
+    pub epoch: u64,
+    /// Ciphersuite identifier, checked before decrypting.
+    pub ciphersuite: u16,
+    /// Leaf index of the inviter.
+    pub inviter_leaf_index: u32,
+    /// Leaf index of the signer.
+    pub signer_leaf_index: u32,
+    /// Joiner secrets, one per added key package.
+    pub secrets: Vec<WelcomeSecretData>,
+    /// AEAD nonce bytes for the shared group payload.
+    pub nonce: Vec<u8>,
+    /// Encrypted [`WelcomeEncryptedData`] bytes.
+    pub ciphertext: Vec<u8>,
+    /// Whether the encrypted payload leaves the member tree out.
+    #[serde(default)]
+    pub ratchet_tree_omitted: bool,
+}
+
+/// Signed welcome payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WelcomeData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Joined epoch.
+    pub epoch: u64,
+    /// Ciphersuite identifier, checked before decrypting.
+    pub ciphersuite: u16,
+    /// Leaf index of the inviter.
+    pub inviter_leaf_index: u32,
+    /// Leaf index of the signer.
+    pub signer_leaf_index: u32,
+    /// Joiner secrets, one per added key package.
+    pub secrets: Vec<WelcomeSecretData>,
+    /// AEAD nonce bytes for the shared group payload.
+    pub nonce: Vec<u8>,
+    /// Encrypted [`WelcomeEncryptedData`] bytes.
+    pub ciphertext: Vec<u8>,
+    /// Whether the encrypted payload leaves the member tree out.
+    #[serde(default)]
+    pub ratchet_tree_omitted: bool,
+    /// Ed25519 signature over [`UnsignedWelcomeData`].
+    pub signature: Vec<u8>,
+    /// Out-of-band member tree attached by the joiner; unsigned, and checked
+    /// against the encrypted payload's `ratchet_tree_hash` instead.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ratchet_tree: Vec<GroupMemberData>,
+}
+
+/// Application message payload without signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedAppMessageData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch.
+    pub epoch: u64,
+    /// Sender leaf index.
+    pub sender_leaf_index: u32,
+    /// AEAD nonce bytes.
+    pub nonce: Vec<u8>,
+    /// AEAD ciphertext bytes.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Signed application message payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppMessageData {
+    /// Schema version.
+    pub version: u8,
+    /// Group identifier.
+    pub group_id: String,
+    /// Epoch.
+    pub epoch: u64,
+    /// Sender leaf index.
+    pub sender_leaf_index: u32,
+    /// AEAD nonce bytes.
+    pub nonce: Vec<u8>,
+    /// AEAD ciphertext bytes.
+    pub ciphertext: Vec<u8>,
+    /// Ed25519 signature over [`UnsignedAppMessageData`].
+    pub signature: Vec<u8>,
+}
@@ -1,20 +1,23 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
 use crate::{
-    crypto::{
-        decrypt_chacha20, derive_epoch_secret, derive_welcome_key, encrypt_chacha20,
-        generate_x25519_key_pair, random_nonce, require_key_bytes, sha256, sign_bytes,
-        verify_signature, x25519_shared_secret,
-    },
-    error::MlsError,
+    commit::{advance_epoch, sign_commit},
+    crypto::require_key_bytes,
+    diagnostics::debug_log,
+    error::{ErrorContext, MlsError},
+    key_package::{decode_key_package, ensure_known_extensions, key_package_ref},
     model::{
-        AddMemberOutput, CommitData, CommitOperationData, GroupMemberData, MLS_CIPHERSUITE_ID,
-        MLS_COMMIT_VERSION, MLS_WELCOME_VERSION, RemoveMemberOutput, UnsignedCommitData,
-        UnsignedWelcomeData, WelcomeData, WelcomeEncryptedData,
+        AddMemberOutput, AddMembersOutput, CommitOperationData, EpochSecretData, GroupMemberData,
+        GroupPolicyData, GroupStateData, GroupStateMetadataOutput, JoinGroupOutput, KeyPackageData,
+        MLS_STATE_VERSION, MemberWelcomeOutput, RemoveMemberOutput,
     },
+    path_update::commit_with_path,
+    policy::ensure_minimum_group_size,
     protocol::{
-        WelcomeAeadMetadata, add_epoch_secret, current_epoch_secret, decode_group_state,
-        decode_key_package, encode_group_state, ensure_non_empty, self_leaf_index, serialize_json,
-        verify_credential, welcome_metadata_bytes,
+        decode_group_state, encode_group_state, ensure_non_empty, member_metadata, self_leaf_index,
+        serialize_json, verify_credential,
     },
+    welcome::{WelcomeRecipient, build_welcome, open_welcome},
 };
 
 pub(crate) fn join_group(
@@ -24,101 +27,18 @@ pub(crate) fn join_group(
     key_package_private_key_bytes: &[u8],
     credential_bundle_bytes: &[u8],
     credential_private_key_bytes: &[u8],
-) -> Result<Vec<u8>, MlsError> {
+) -> Result<JoinGroupOutput, MlsError> {
     ensure_non_empty(group_id, "group_id")?;
     ensure_non_empty(key_package_ref, "key_package_ref")?;
 
     let credential = verify_credential(credential_bundle_bytes, credential_private_key_bytes)?;
     require_key_bytes::<32>(key_package_private_key_bytes, "key_package_private_key")?;
 
-    let welcome: WelcomeData = crate::protocol::deserialize_json(welcome_bytes, "welcome")?;
-    if welcome.version != MLS_WELCOME_VERSION {
-        return Err(MlsError::InvalidInput(format!(
-            "unsupported welcome version {}",
-            welcome.version
-        )));
-    }
-
-    if welcome.group_id != group_id.trim() {
-        return Err(MlsError::InvalidInput(format!(
-            "welcome group mismatch: expected {}, got {}",
-            group_id.trim(),
-            welcome.group_id
-        )));
-    }
-
-    if welcome.key_package_ref != key_package_ref.trim() {
-        return Err(MlsError::InvalidInput(
-            "welcome key package reference mismatch".to_owned(),
-        ));
-    }
-
-    let welcome_metadata = WelcomeAeadMetadata {
-        group_id: welcome.group_id.clone(),
-        epoch: welcome.epoch,
-        key_package_ref: welcome.key_package_ref.clone(),
-        inviter_leaf_index: welcome.inviter_leaf_index,
-        signer_leaf_index: welcome.signer_leaf_index,
-        ephemeral_public_key: welcome.ephemeral_public_key.clone(),
-    };
-    let aad = welcome_metadata_bytes(&welcome_metadata)?;
-
-    let shared_secret =
-        x25519_shared_secret(key_package_private_key_bytes, &welcome.ephemeral_public_key)?;
-
-    let welcome_key = derive_welcome_key(
-        &shared_secret,
-        &welcome.group_id,
-        welcome.epoch,
-        &welcome.key_package_ref,
-    )?;
-
-    let decrypted = decrypt_chacha20(&welcome_key, &welcome.nonce, &welcome.ciphertext, &aad)?;
-    let welcome_payload: WelcomeEncryptedData =
-        crate::protocol::deserialize_json(&decrypted, "welcome encrypted payload")?;
-
-    if welcome_payload.group_id != welcome.group_id {
-        return Err(MlsError::InvalidInput(
-            "welcome encrypted payload group mismatch".to_owned(),
-        ));
-    }
-
-    if welcome_payload.epoch != welcome.epoch {
-        return Err(MlsError::InvalidInput(
-            "welcome encrypted payload epoch mismatch".to_owned(),
-        ));
-    }
-
-    if welcome_payload.ciphersuite != MLS_CIPHERSUITE_ID {
-        return Err(MlsError::InvalidInput(format!(
-            "unsupported ciphersuite {}",
-            welcome_payload.ciphersuite
-        )));
-    }
-
-    let signer = welcome_payload
-        .members
-        .iter()
-        .find(|member| member.leaf_index == welcome.signer_leaf_index)
-        .ok_or_else(|| MlsError::NotFound("welcome signer leaf index not found".to_owned()))?;
-
-    let unsigned_welcome = UnsignedWelcomeData {
-        version: welcome.version,
-        group_id: welcome.group_id.clone(),
-        epoch: welcome.epoch,
-        key_package_ref: welcome.key_package_ref.clone(),
-        inviter_leaf_index: welcome.inviter_leaf_index,
-        signer_leaf_index: welcome.signer_leaf_index,
-        ephemeral_public_key: welcome.ephemeral_public_key.clone(),
-        nonce: welcome.nonce.clone(),
-        ciphertext: welcome.ciphertext.clone(),
-    };
-    let unsigned_welcome_bytes = serialize_json(&unsigned_welcome)?;
-
-    verify_signature(
-        &signer.signing_public_key,
-        &unsigned_welcome_bytes,
-        &welcome.signature,
+    let welcome_payload = open_welcome(
+        welcome_bytes,
+        group_id.trim(),
+        key_package_ref.trim(),
+        key_package_private_key_bytes,
     )?;
 
     let self_member = welcome_payload
@@ -135,179 +55,255 @@ pub(crate) fn join_group(
         ));
     }
 
-    let state = crate::model::GroupStateData {
-        version: crate::model::MLS_STATE_VERSION,
-        group_id: welcome.group_id,
-        epoch: welcome.epoch,
+    let state = GroupStateData {
+        version: MLS_STATE_VERSION,
+        group_id: welcome_payload.group_id,
+        epoch: welcome_payload.epoch,
         ciphersuite: welcome_payload.ciphersuite,
         self_user_id: credential.user_id,
         self_signing_private_key: credential_private_key_bytes.to_vec(),
         self_signing_public_key: credential.signing_public_key,
+        self_hpke_private_key: key_package_private_key_bytes.to_vec(),
+        last_update_epoch: welcome_payload.epoch,
         members: welcome_payload.members,
-        epoch_secrets: vec![crate::model::EpochSecretData {
-            epoch: welcome.epoch,
+        epoch_secrets: vec![EpochSecretData {
+            epoch: welcome_payload.epoch,
             secret: welcome_payload.epoch_secret,
         }],
+        group_context: welcome_payload.group_context,
+        confirmed_transcript_hash: welcome_payload.confirmed_transcript_hash,
+        local_metadata: BTreeMap::new(),
+        external_psks: BTreeMap::new(),
+        consumed_key_package_refs: BTreeSet::new(),
+        pending_proposals: BTreeMap::new(),
+        policy: GroupPolicyData::default(),
+        solo: false,
     };
-
-    encode_group_state(&state)
+    debug_log(|| {
+        format!(
+            "join_group {}: joined epoch {} with {} members",
+            state.group_id,
+            state.epoch,
+            state.members.len()
+        )
+    });
+
+    Ok(JoinGroupOutput {
+        state: encode_group_state(&state)?,
+        members: member_metadata(&state.members)?,
+        group_id: state.group_id,
+        epoch: state.epoch,
+        ciphersuite: state.ciphersuite,
+    })
 }
 
-pub(crate) fn add_member(
-    group_state_bytes: &[u8],
-    member_key_package_bytes: &[u8],
-) -> Result<AddMemberOutput, MlsError> {
-    let mut state = decode_group_state(group_state_bytes)?;
-    let member_key_package = decode_key_package(member_key_package_bytes)?;
+/// Returns whether a one-time key package was already consumed or shares a leaf's init key.
+///
+/// `pending_members` and `pending_refs` cover entries staged earlier in the same commit.
+pub(crate) fn key_package_reused(
+    state: &GroupStateData,
+    key_package: &KeyPackageData,
+    key_package_ref: &str,
+    pending_members: &[GroupMemberData],
+    pending_refs: &[String],
+) -> bool {
+    if key_package.last_resort {
+        return false;
+    }
 
-    if state
+    state
         .members
         .iter()
-        .any(|member| member.user_id == member_key_package.user_id)
-    {
-        return Err(MlsError::InvalidInput(format!(
-            "member {} already exists in group",
-            member_key_package.user_id
+        .chain(pending_members)
+        .any(|member| member.hpke_public_key == key_package.hpke_public_key)
+        || state.consumed_key_package_refs.contains(key_package_ref)
+        || pending_refs
+            .iter()
+            .any(|pending| pending == key_package_ref)
+}
+
+pub(crate) fn group_info_bytes(state: &GroupStateData) -> Result<Vec<u8>, MlsError> {
+    serialize_json(&GroupStateMetadataOutput {
+        group_id: state.group_id.clone(),
+        epoch: state.epoch,
+        self_user_id: state.self_user_id.clone(),
+        members: member_metadata(&state.members)?,
+        group_context: state.group_context.clone(),
+        signing_key_deleted: false,
+    })
+}
+
+/// Adds every key package in one commit and returns a combined welcome routed per recipient.
+///
+/// New leaves and welcome entries follow the order of `member_key_packages`, never ref order,
+/// so callers can zip the output with their input.
+///
+/// Nothing is returned unless every stage succeeds, so the caller's stored state stays at the
+/// prior epoch on failure; errors are prefixed with the failing stage.
+///
+/// Adds need no update path, so one is only sent with `force_path_update`: that costs a sealed
+/// secret per member but also rotates the committer's leaf key for post-compromise security.
+/// Removals and self updates are unaffected by the flag.
+pub(crate) fn add_members(
+    group_state_bytes: &[u8],
+    member_key_packages: &[Vec<u8>],
+    force_path_update: bool,
+) -> Result<AddMembersOutput, MlsError> {
+    let validate_error = |error: MlsError| error.with_context("add_members validate");
+    let mut state = decode_group_state(group_state_bytes).map_err(validate_error)?;
+    if state.solo {
+        return Err(validate_error(MlsError::InvalidInput(
+            "solo groups cannot add members".to_owned(),
+        )));
+    }
+
+    if member_key_packages.is_empty() {
+        return Err(validate_error(MlsError::InvalidInput(
+            "at least one key package is required".to_owned(),
         )));
     }
 
-    let next_leaf_index = state
+    let mut next_leaf_index = state
         .members
         .iter()
         .map(|member| member.leaf_index)
         .max()
         .map(|leaf| leaf.saturating_add(1))
-        .ok_or_else(|| MlsError::InvalidState("group has no members".to_owned()))?;
+        .ok_or_else(|| validate_error(MlsError::InvalidState("group has no members".to_owned())))?;
+    let proposer_leaf_index = self_leaf_index(&state).map_err(validate_error)?;
 
-    let added_member = GroupMemberData {
-        user_id: member_key_package.user_id,
-        leaf_index: next_leaf_index,
-        signing_public_key: member_key_package.signing_public_key,
-        hpke_public_key: member_key_package.hpke_public_key,
-    };
+    let mut user_ids: HashSet<String> = state
+        .members
+        .iter()
+        .map(|member| member.user_id.clone())
+        .collect();
+    let mut consumed_refs = Vec::new();
+    let mut added_members = Vec::with_capacity(member_key_packages.len());
+    let mut recipients = Vec::with_capacity(member_key_packages.len());
+
+    for (index, key_package_bytes) in member_key_packages.iter().enumerate() {
+        let key_package_ref = key_package_ref(key_package_bytes);
+        let item_error = |error: MlsError| {
+            error
+                .for_item(ErrorContext::KeyPackageRef(key_package_ref.clone()))
+                .with_context(&format!("add_members validate key package {index}"))
+        };
+        let key_package = decode_key_package(key_package_bytes)
+            .and_then(|key_package| {
+                ensure_known_extensions(&state.policy, &key_package)?;
+                Ok(key_package)
+            })
+            .map_err(item_error)?;
+        if key_package_reused(
+            &state,
+            &key_package,
+            &key_package_ref,
+            &added_members,
+            &consumed_refs,
+        ) {
+            return Err(item_error(MlsError::KeyPackageReused(
+                "was already used".to_owned(),
+            )));
+        }
+        if !key_package.last_resort {
+            consumed_refs.push(key_package_ref.clone());
+        }
 
-    let proposer_leaf_index = self_leaf_index(&state)?;
-    let new_epoch = state.epoch.saturating_add(1);
+        if !user_ids.insert(key_package.user_id.clone()) {
+            return Err(item_error(MlsError::InvalidInput(format!(
+                "member {} already exists in group",
+                key_package.user_id
+            ))));
+        }
 
-    let unsigned_commit = UnsignedCommitData {
-        version: MLS_COMMIT_VERSION,
-        group_id: state.group_id.clone(),
-        previous_epoch: state.epoch,
-        new_epoch,
-        proposer_leaf_index,
-        operation: CommitOperationData::Add {
-            member: added_member.clone(),
-        },
-    };
-    let unsigned_commit_bytes = serialize_json(&unsigned_commit)?;
-    let signature = sign_bytes(&state.self_signing_private_key, &unsigned_commit_bytes)?;
+        recipients.push(WelcomeRecipient {
+            key_package_ref: key_package_ref.clone(),
+            hpke_public_key: key_package.hpke_public_key.clone(),
+        });
+        added_members.push(GroupMemberData {
+            user_id: key_package.user_id,
+            leaf_index: next_leaf_index,
+            signing_public_key: key_package.signing_public_key,
+            hpke_public_key: key_package.hpke_public_key,
+            key_package_ref,
+            leaf_epoch: state.epoch.saturating_add(1),
+        });
+        next_leaf_index = next_leaf_index.saturating_add(1);
+    }
 
-    let commit = CommitData {
-        version: unsigned_commit.version,
-        group_id: unsigned_commit.group_id.clone(),
-        previous_epoch: unsigned_commit.previous_epoch,
-        new_epoch: unsigned_commit.new_epoch,
-        proposer_leaf_index: unsigned_commit.proposer_leaf_index,
-        operation: unsigned_commit.operation,
-        signature,
+    let operation = CommitOperationData::Add {
+        members: added_members.clone(),
+        key_package_refs: consumed_refs.clone(),
     };
-    let commit_bytes = serialize_json(&commit)?;
-
-    let current_secret = current_epoch_secret(&state)?;
-    let next_epoch_secret = derive_epoch_secret(&current_secret, &unsigned_commit_bytes)?.to_vec();
-
-    state.epoch = new_epoch;
-    state.members.push(added_member.clone());
-    add_epoch_secret(&mut state, new_epoch, next_epoch_secret.clone());
-
-    let key_package_ref = hex::encode(sha256(member_key_package_bytes));
-
-    let welcome_payload = WelcomeEncryptedData {
-        group_id: state.group_id.clone(),
-        epoch: state.epoch,
-        epoch_secret: next_epoch_secret,
-        members: state.members.clone(),
-        ciphersuite: MLS_CIPHERSUITE_ID,
+    let signed = if force_path_update {
+        state.members.extend(added_members);
+        state.consumed_key_package_refs.extend(consumed_refs);
+        commit_with_path(&mut state, operation)
+            .map_err(|error| error.with_context("add_members commit"))?
+    } else {
+        let signed = sign_commit(&state, operation)
+            .map_err(|error| error.with_context("add_members commit"))?;
+        state.members.extend(added_members);
+        state.consumed_key_package_refs.extend(consumed_refs);
+        advance_epoch(
+            &mut state,
+            signed.commit.new_epoch,
+            &signed.unsigned_commit_bytes,
+        )
+        .map_err(|error| error.with_context("add_members merge"))?;
+        signed
     };
-    let welcome_payload_bytes = serialize_json(&welcome_payload)?;
-
-    let (ephemeral_private_key, ephemeral_public_key) = generate_x25519_key_pair()?;
-    let shared_secret =
-        x25519_shared_secret(&ephemeral_private_key, &added_member.hpke_public_key)?;
-
-    let welcome_metadata = WelcomeAeadMetadata {
-        group_id: state.group_id.clone(),
-        epoch: state.epoch,
-        key_package_ref,
-        inviter_leaf_index: proposer_leaf_index,
-        signer_leaf_index: proposer_leaf_index,
-        ephemeral_public_key: ephemeral_public_key.clone(),
-    };
-    let welcome_aad = welcome_metadata_bytes(&welcome_metadata)?;
-
-    let welcome_key = derive_welcome_key(
-        &shared_secret,
-        &state.group_id,
-        state.epoch,
-        &welcome_metadata.key_package_ref,
-    )?;
+    let new_epoch = signed.commit.new_epoch;
+    let commit =
+        serialize_json(&signed.commit).map_err(|error| error.with_context("add_members commit"))?;
+    let state_bytes =
+        encode_group_state(&state).map_err(|error| error.with_context("add_members merge"))?;
+
+    let welcome = build_welcome(&state, proposer_leaf_index, &recipients)
+        .map_err(|error| error.with_context("add_members welcome"))?;
+    let group_info =
+        group_info_bytes(&state).map_err(|error| error.with_context("add_members welcome"))?;
+    let welcomes = recipients
+        .into_iter()
+        .map(|recipient| MemberWelcomeOutput {
+            key_package_ref: recipient.key_package_ref,
+            epoch: state.epoch,
+            ciphersuite: state.ciphersuite,
+            welcome: welcome.clone(),
+        })
+        .collect();
+
+    Ok(AddMembersOutput {
+        state: state_bytes,
+        commit,
+        welcomes,
+        group_info,
+        new_epoch,
+    })
+}
 
-    let welcome_nonce = random_nonce()?.to_vec();
-    let welcome_ciphertext = encrypt_chacha20(
-        &welcome_key,
-        &welcome_nonce,
-        &welcome_payload_bytes,
-        &welcome_aad,
+pub(crate) fn add_member(
+    group_state_bytes: &[u8],
+    member_key_package_bytes: &[u8],
+) -> Result<AddMemberOutput, MlsError> {
+    let output = add_members(
+        group_state_bytes,
+        &[member_key_package_bytes.to_vec()],
+        false,
     )?;
-
-    let unsigned_welcome = UnsignedWelcomeData {
-        version: MLS_WELCOME_VERSION,
-        group_id: state.group_id.clone(),
-        epoch: state.epoch,
-        key_package_ref: welcome_metadata.key_package_ref,
-        inviter_leaf_index: welcome_metadata.inviter_leaf_index,
-        signer_leaf_index: welcome_metadata.signer_leaf_index,
-        ephemeral_public_key,
-        nonce: welcome_nonce,
-        ciphertext: welcome_ciphertext,
-    };
-    let unsigned_welcome_bytes = serialize_json(&unsigned_welcome)?;
-
-    let welcome_signature = sign_bytes(&state.self_signing_private_key, &unsigned_welcome_bytes)?;
-    let welcome = WelcomeData {
-        version: unsigned_welcome.version,
-        group_id: unsigned_welcome.group_id,
-        epoch: unsigned_welcome.epoch,
-        key_package_ref: unsigned_welcome.key_package_ref,
-        inviter_leaf_index: unsigned_welcome.inviter_leaf_index,
-        signer_leaf_index: unsigned_welcome.signer_leaf_index,
-        ephemeral_public_key: unsigned_welcome.ephemeral_public_key,
-        nonce: unsigned_welcome.nonce,
-        ciphertext: unsigned_welcome.ciphertext,
-        signature: welcome_signature,
-    };
-
-    let state_bytes = encode_group_state(&state)?;
-    let group_info = serialize_json(&crate::model::GroupStateMetadataOutput {
-        group_id: state.group_id,
-        epoch: state.epoch,
-        self_user_id: state.self_user_id,
-        members: state
-            .members
-            .iter()
-            .map(|member| crate::model::GroupMemberMetadataOutput {
-                user_id: member.user_id.clone(),
-                leaf_index: member.leaf_index,
-            })
-            .collect(),
-    })?;
+    let welcome = output
+        .welcomes
+        .into_iter()
+        .next()
+        .map(|entry| entry.welcome)
+        .ok_or_else(|| MlsError::InvalidState("add produced no welcome".to_owned()))?;
 
     Ok(AddMemberOutput {
-        state: state_bytes,
-        commit: commit_bytes,
-        welcome: serialize_json(&welcome)?,
-        group_info,
-        new_epoch,
+        state: output.state,
+        commit: output.commit,
+        welcome,
+        group_info: output.group_info,
+        new_epoch: output.new_epoch,
     })
 }
 
@@ -316,12 +312,13 @@ pub(crate) fn remove_member(
     leaf_index: u32,
 ) -> Result<RemoveMemberOutput, MlsError> {
     let mut state = decode_group_state(group_state_bytes)?;
+    let item_error = |error: MlsError| error.for_item(ErrorContext::LeafIndex(leaf_index));
 
     let self_leaf = self_leaf_index(&state)?;
     if leaf_index == self_leaf {
-        return Err(MlsError::InvalidInput(
+        return Err(item_error(MlsError::InvalidInput(
             "cannot remove local member from local state".to_owned(),
-        ));
+        )));
     }
 
     if !state
@@ -329,167 +326,35 @@ pub(crate) fn remove_member(
         .iter()
         .any(|member| member.leaf_index == leaf_index)
     {
-        return Err(MlsError::NotFound(format!(
-            "leaf index {leaf_index} not found in group"
+        return Err(item_error(MlsError::NotFound(
+            "not found in group".to_owned(),
         )));
     }
 
-    let proposer_leaf_index = self_leaf;
-    let new_epoch = state.epoch.saturating_add(1);
-
-    let unsigned_commit = UnsignedCommitData {
-        version: MLS_COMMIT_VERSION,
-        group_id: state.group_id.clone(),
-        previous_epoch: state.epoch,
-        new_epoch,
-        proposer_leaf_index,
-        operation: CommitOperationData::Remove { leaf_index },
-    };
-    let unsigned_commit_bytes = serialize_json(&unsigned_commit)?;
-    let signature = sign_bytes(&state.self_signing_private_key, &unsigned_commit_bytes)?;
-
-    let commit = CommitData {
-        version: unsigned_commit.version,
-        group_id: unsigned_commit.group_id.clone(),
-        previous_epoch: unsigned_commit.previous_epoch,
-        new_epoch: unsigned_commit.new_epoch,
-        proposer_leaf_index,
-        operation: unsigned_commit.operation,
-        signature,
+    ensure_minimum_group_size(&state, 1).map_err(item_error)?;
+    let operation = CommitOperationData::Remove { leaf_index };
+    let signed = if state.policy.rekey_on_remove {
+        state
+            .members
+            .retain(|member| member.leaf_index != leaf_index);
+        commit_with_path(&mut state, operation)?
+    } else {
+        let signed = sign_commit(&state, operation)?;
+        state
+            .members
+            .retain(|member| member.leaf_index != leaf_index);
+        advance_epoch(
+            &mut state,
+            signed.commit.new_epoch,
+            &signed.unsigned_commit_bytes,
+        )?;
+        signed
     };
-
-    let current_secret = current_epoch_secret(&state)?;
-    let next_epoch_secret = derive_epoch_secret(&current_secret, &unsigned_commit_bytes)?.to_vec();
-
-    state
-        .members
-        .retain(|member| member.leaf_index != leaf_index);
-    state.epoch = new_epoch;
-    add_epoch_secret(&mut state, new_epoch, next_epoch_secret);
+    let new_epoch = signed.commit.new_epoch;
 
     Ok(RemoveMemberOutput {
         state: encode_group_state(&state)?,
-        commit: serialize_json(&commit)?,
+        commit: serialize_json(&signed.commit)?,
         new_epoch,
     })
 }
-
-pub(crate) fn process_commit(
-    group_state_bytes: &[u8],
-    commit_bytes: &[u8],
-) -> Result<Vec<u8>, MlsError> {
-    let mut state = decode_group_state(group_state_bytes)?;
-    let commit: CommitData = crate::protocol::deserialize_json(commit_bytes, "commit")?;
-
-    if commit.version != MLS_COMMIT_VERSION {
-        return Err(MlsError::InvalidInput(format!(
-            "unsupported commit version {}",
-            commit.version
-        )));
-    }
-
-    if commit.group_id != state.group_id {
-        return Err(MlsError::InvalidInput(format!(
-            "commit group mismatch: expected {}, got {}",
-            state.group_id, commit.group_id
-        )));
-    }
-
-    if commit.previous_epoch != state.epoch {
-        return Err(MlsError::InvalidInput(format!(
-            "commit previous_epoch mismatch: expected {}, got {}",
-            state.epoch, commit.previous_epoch
-        )));
-    }
-
-    if commit.new_epoch != state.epoch.saturating_add(1) {
-        return Err(MlsError::InvalidInput(format!(
-            "commit new_epoch mismatch: expected {}, got {}",
-            state.epoch.saturating_add(1),
-            commit.new_epoch
-        )));
-    }
-
-    let proposer = state
-        .members
-        .iter()
-        .find(|member| member.leaf_index == commit.proposer_leaf_index)
-        .ok_or_else(|| {
-            MlsError::NotFound(format!(
-                "commit proposer leaf {} not found",
-                commit.proposer_leaf_index
-            ))
-        })?;
-
-    let unsigned_commit = UnsignedCommitData {
-        version: commit.version,
-        group_id: commit.group_id.clone(),
-        previous_epoch: commit.previous_epoch,
-        new_epoch: commit.new_epoch,
-        proposer_leaf_index: commit.proposer_leaf_index,
-        operation: commit.operation.clone(),
-    };
-    let unsigned_commit_bytes = serialize_json(&unsigned_commit)?;
-
-    verify_signature(
-        &proposer.signing_public_key,
-        &unsigned_commit_bytes,
-        &commit.signature,
-    )?;
-
-    let current_secret = current_epoch_secret(&state)?;
-    let next_epoch_secret = derive_epoch_secret(&current_secret, &unsigned_commit_bytes)?.to_vec();
-
-    match commit.operation {
-        CommitOperationData::Add { member } => {
-            if state
-                .members
-                .iter()
-                .any(|existing| existing.leaf_index == member.leaf_index)
-            {
-                return Err(MlsError::InvalidInput(format!(
-                    "cannot apply add commit with existing leaf index {}",
-                    member.leaf_index
-                )));
-            }
-
-            if state
-                .members
-                .iter()
-                .any(|existing| existing.user_id == member.user_id)
-            {
-                return Err(MlsError::InvalidInput(format!(
-                    "cannot apply add commit with existing user {}",
-                    member.user_id
-                )));
-            }
-
-            state.members.push(member);
-        }
-        CommitOperationData::Remove { leaf_index } => {
-            let removed_member = state
-                .members
-                .iter()
-                .find(|member| member.leaf_index == leaf_index)
-                .ok_or_else(|| {
-                    MlsError::NotFound(format!(
-                        "cannot apply remove commit for unknown leaf index {leaf_index}"
-                    ))
-                })?;
-
-            if removed_member.user_id == state.self_user_id {
-                return Err(MlsError::InvalidState(
-                    "local member has been removed from group".to_owned(),
-                ));
-            }
-
-            state
-                .members
-                .retain(|member| member.leaf_index != leaf_index);
-        }
-    }
-
-    state.epoch = commit.new_epoch;
-    add_epoch_secret(&mut state, commit.new_epoch, next_epoch_secret);
-    encode_group_state(&state)
-}
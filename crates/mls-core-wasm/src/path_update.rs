@@ -0,0 +1,166 @@
+use crate::{
+    commit::{SignedCommit, advance_epoch_with_secret, sign_commit_with_path},
+    crypto::{
+        decrypt_chacha20, derive_path_secret_key, encrypt_chacha20, generate_x25519_key_pair,
+        random_bytes, random_nonce, require_key_bytes, x25519_shared_secret,
+    },
+    error::MlsError,
+    model::{CommitOperationData, CommitOutput, GroupStateData, PathSecretData, UpdatePathData},
+    protocol::{
+        decode_group_state, encode_group_state, metadata_bytes, self_leaf_index, serialize_json,
+    },
+};
+
+fn seal_path_secret(
+    state: &GroupStateData,
+    new_epoch: u64,
+    path_secret: &[u8],
+) -> Result<Vec<PathSecretData>, MlsError> {
+    let self_leaf = self_leaf_index(state)?;
+    let mut sealed = Vec::with_capacity(state.members.len());
+
+    for member in state
+        .members
+        .iter()
+        .filter(|member| member.leaf_index != self_leaf)
+    {
+        if member.hpke_public_key.is_empty() {
+            return Err(MlsError::InvalidState(format!(
+                "member leaf {} has no HPKE key for path update",
+                member.leaf_index
+            )));
+        }
+
+        let (ephemeral_private_key, ephemeral_public_key) = generate_x25519_key_pair()?;
+        let shared_secret = x25519_shared_secret(&ephemeral_private_key, &member.hpke_public_key)?;
+        let key = derive_path_secret_key(
+            &shared_secret,
+            &state.group_id,
+            new_epoch,
+            member.leaf_index,
+        )?;
+        let nonce = random_nonce()?.to_vec();
+        let aad = metadata_bytes(&state.group_id, new_epoch, member.leaf_index)?;
+
+        sealed.push(PathSecretData {
+            leaf_index: member.leaf_index,
+            ephemeral_public_key,
+            ciphertext: encrypt_chacha20(&key, &nonce, path_secret, &aad)?,
+            nonce,
+        });
+    }
+
+    Ok(sealed)
+}
+
+/// Decrypts the path secret addressed to the local leaf of an update commit.
+fn open_path_secret(
+    state: &GroupStateData,
+    new_epoch: u64,
+    path_secrets: &[PathSecretData],
+) -> Result<Vec<u8>, MlsError> {
+    if state.self_hpke_private_key.is_empty() {
+        return Err(MlsError::LegacyLeafKey(
+            "group state has no local HPKE key; send a self update first, or rejoin through a new welcome"
+                .to_owned(),
+        ));
+    }
+
+    let self_leaf = self_leaf_index(state)?;
+    let sealed = path_secrets
+        .iter()
+        .find(|entry| entry.leaf_index == self_leaf)
+        .ok_or_else(|| {
+            MlsError::NotFound(format!(
+                "update commit has no path secret for leaf {self_leaf}"
+            ))
+        })?;
+
+    let shared_secret =
+        x25519_shared_secret(&state.self_hpke_private_key, &sealed.ephemeral_public_key)?;
+    let key = derive_path_secret_key(&shared_secret, &state.group_id, new_epoch, self_leaf)?;
+    let aad = metadata_bytes(&state.group_id, new_epoch, self_leaf)?;
+    decrypt_chacha20(&key, &sealed.nonce, &sealed.ciphertext, &aad)
+}
+
+/// Applies a received update path: opens our path secret and installs the committer's key.
+pub(crate) fn apply_update_path(
+    state: &mut GroupStateData,
+    proposer_leaf_index: u32,
+    new_epoch: u64,
+    path: &UpdatePathData,
+) -> Result<Vec<u8>, MlsError> {
+    require_key_bytes::<32>(&path.hpke_public_key, "commit.path.hpke_public_key")?;
+    let path_secret = open_path_secret(state, new_epoch, &path.path_secrets)?;
+
+    let proposer = state
+        .members
+        .iter_mut()
+        .find(|member| member.leaf_index == proposer_leaf_index)
+        .ok_or_else(|| {
+            MlsError::NotFound(format!(
+                "commit proposer leaf {proposer_leaf_index} not found"
+            ))
+        })?;
+    proposer.hpke_public_key = path.hpke_public_key.clone();
+    proposer.key_package_ref.clear();
+
+    Ok(path_secret)
+}
+
+/// Signs `operation` with a fresh update path and advances `state` into the new epoch.
+///
+/// `state` must already reflect the operation's membership change so removed members
+/// receive no path secret.
+pub(crate) fn commit_with_path(
+    state: &mut GroupStateData,
+    operation: CommitOperationData,
+) -> Result<SignedCommit, MlsError> {
+    let self_leaf = self_leaf_index(state)?;
+    let new_epoch = state.epoch.saturating_add(1);
+
+    let path_secret = random_bytes::<32>()?;
+    let (hpke_private_key, hpke_public_key) = generate_x25519_key_pair()?;
+    let path = UpdatePathData {
+        hpke_public_key: hpke_public_key.clone(),
+        path_secrets: seal_path_secret(state, new_epoch, &path_secret)?,
+    };
+    let signed = sign_commit_with_path(state, operation, Some(path))?;
+
+    if let Some(member) = state
+        .members
+        .iter_mut()
+        .find(|member| member.leaf_index == self_leaf)
+    {
+        member.hpke_public_key = hpke_public_key;
+        member.key_package_ref.clear();
+    }
+    state.self_hpke_private_key = hpke_private_key;
+    state.last_update_epoch = new_epoch;
+    advance_epoch_with_secret(
+        state,
+        new_epoch,
+        &signed.unsigned_commit_bytes,
+        &path_secret,
+    )?;
+
+    Ok(signed)
+}
+
+/// Rotates the local leaf key and commits a fresh path secret to all other members.
+pub(crate) fn self_update(group_state_bytes: &[u8]) -> Result<CommitOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    let signed = commit_with_path(&mut state, CommitOperationData::Update)?;
+
+    Ok(CommitOutput {
+        state: encode_group_state(&state)?,
+        commit: serialize_json(&signed.commit)?,
+        new_epoch: signed.commit.new_epoch,
+    })
+}
+
+/// Returns whether more than `max_epochs` epochs have passed since the local leaf key changed.
+pub(crate) fn should_rekey(group_state_bytes: &[u8], max_epochs: u64) -> Result<bool, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    Ok(state.epoch.saturating_sub(state.last_update_epoch) > max_epochs)
+}
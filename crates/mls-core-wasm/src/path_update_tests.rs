@@ -0,0 +1,70 @@
+use crate::{
+    commit::process_commit,
+    messaging::{decrypt_message, encrypt_message},
+    operations::remove_member,
+    path_update::{self_update, should_rekey},
+    protocol::{export_group_state, import_group_state},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn self_update_is_processed_by_other_members() {
+    let alice_state = create_single_member_state("group-pcs", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-pcs", "bob");
+
+    let update = must(self_update(&bob_state), "bob self-updates");
+    assert_eq!(update.new_epoch, 2);
+
+    let processed = must(
+        process_commit(&added.state, &update.commit),
+        "alice processes update",
+    );
+    assert_eq!(processed.new_epoch, 2);
+
+    let ciphertext = must(
+        encrypt_message(&processed.state, b"fresh"),
+        "alice encrypts",
+    );
+    let decrypted = must(
+        decrypt_message(&update.state, &ciphertext, false),
+        "bob decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"fresh");
+}
+
+#[test]
+fn removed_member_cannot_follow_later_self_update() {
+    let alice_state = create_single_member_state("group-pcs-2", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-pcs-2", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added.state, "group-pcs-2", "carol");
+    let bob_state = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob processes carol add",
+    )
+    .state;
+
+    let removed = must(remove_member(&added_carol.state, 1), "remove bob");
+    let update = must(self_update(&removed.state), "alice self-updates");
+
+    assert!(process_commit(&bob_state, &update.commit).is_err());
+}
+
+#[test]
+fn should_rekey_tracks_epochs_since_last_update_across_export() {
+    let alice_state = create_single_member_state("group-pcs-3", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-pcs-3", "bob");
+    assert!(must(should_rekey(&added.state, 0), "alice one epoch stale"));
+    assert!(!must(
+        should_rekey(&added.state, 1),
+        "alice within threshold"
+    ));
+    assert!(!must(should_rekey(&bob_state, 0), "bob just joined"));
+
+    let update = must(self_update(&added.state), "alice self-updates");
+    let exported = must(export_group_state(&update.state), "export");
+    let imported = must(import_group_state("group-pcs-3", &exported), "import");
+    assert!(!must(
+        should_rekey(&imported.state, 0),
+        "alice after update"
+    ));
+}
@@ -0,0 +1,70 @@
+use crate::{
+    error::MlsError,
+    model::{GroupPolicyData, GroupStateData},
+    protocol::{decode_group_state, encode_group_state},
+};
+
+/// Replaces the local commit policy stored with the group state.
+pub(crate) fn set_group_policy(
+    group_state_bytes: &[u8],
+    policy: GroupPolicyData,
+) -> Result<Vec<u8>, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    state.policy = policy;
+    encode_group_state(&state)
+}
+
+pub(crate) fn group_policy(group_state_bytes: &[u8]) -> Result<GroupPolicyData, MlsError> {
+    Ok(decode_group_state(group_state_bytes)?.policy)
+}
+
+/// Refuses to remove `removed` members when that would breach `policy.min_members`.
+pub(crate) fn ensure_minimum_group_size(
+    state: &GroupStateData,
+    removed: usize,
+) -> Result<(), MlsError> {
+    let current = state.members.len();
+    let minimum = state.policy.min_members as usize;
+    let remaining = current.saturating_sub(removed);
+    if remaining < minimum {
+        return Err(MlsError::MinimumGroupSize(format!(
+            "removal would leave {remaining} of {current} members, below the minimum of {minimum}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Erases the local signing private key, e.g. on logout, so the state can still read the group
+/// but every signing path fails with `SigningKeyDeleted`. The public key stays so our own
+/// member entry still checks out.
+pub(crate) fn delete_signing_key(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    state.self_signing_private_key.fill(0);
+    state.self_signing_private_key.clear();
+    encode_group_state(&state)
+}
+
+/// Returns the local signing private key, or `SigningKeyDeleted` once it was erased.
+pub(crate) fn self_signing_key(state: &GroupStateData) -> Result<&[u8], MlsError> {
+    if state.self_signing_private_key.is_empty() {
+        return Err(MlsError::SigningKeyDeleted(
+            "local signing key was deleted".to_owned(),
+        ));
+    }
+
+    Ok(&state.self_signing_private_key)
+}
+
+/// Refuses to author `action` when the local policy marks this member as a read-only observer.
+///
+/// Peers cannot tell observers apart; this is a local application constraint.
+pub(crate) fn ensure_can_author(state: &GroupStateData, action: &str) -> Result<(), MlsError> {
+    if state.policy.read_only {
+        return Err(MlsError::ReadOnlyClient(format!(
+            "read-only client cannot author {action}"
+        )));
+    }
+
+    Ok(())
+}
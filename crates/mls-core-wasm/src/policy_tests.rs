@@ -0,0 +1,246 @@
+use crate::{
+    authenticator::epoch_authenticator,
+    commit::{process_commit, unsigned_commit},
+    crypto::{derive_epoch_authenticator, derive_epoch_secret},
+    error::{ErrorContext, MlsError},
+    key_package::generate_key_package,
+    messaging::{decrypt_message, encrypt_message},
+    model::{CommitData, GroupPolicyData, GroupStateData},
+    operations::{add_member, remove_member},
+    path_update::self_update,
+    policy::{delete_signing_key, group_policy, set_group_policy},
+    proposals::force_leave,
+    protocol::serialize_json,
+    protocol::{generate_credential, group_state_metadata},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+struct Trio {
+    alice: Vec<u8>,
+    bob: Vec<u8>,
+    carol: Vec<u8>,
+}
+
+fn trio(group_id: &str) -> Trio {
+    let alice_state = create_single_member_state(group_id, "alice");
+    let (added_bob, bob_state) = add_and_join(&alice_state, group_id, "bob");
+    let (added_carol, carol_state) = add_and_join(&added_bob.state, group_id, "carol");
+    let bob_state = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob processes carol add",
+    )
+    .state;
+
+    Trio {
+        alice: added_carol.state,
+        bob: bob_state,
+        carol: carol_state,
+    }
+}
+
+/// Authenticator a removed member would compute by ratcheting its old secret over the commit.
+fn removed_member_guess(removed_state: &[u8], commit_bytes: &[u8]) -> Vec<u8> {
+    let state: GroupStateData = must(serde_json::from_slice(removed_state), "parse state");
+    let commit: CommitData = must(serde_json::from_slice(commit_bytes), "parse commit");
+    let unsigned_bytes = must(serialize_json(&unsigned_commit(&commit)), "unsigned commit");
+    let Some(current) = state.epoch_secrets.last() else {
+        panic!("removed state has no epoch secret");
+    };
+    let guess = must(
+        derive_epoch_secret(&current.secret, &unsigned_bytes),
+        "derive guess",
+    );
+    must(
+        derive_epoch_authenticator(&guess, &commit.group_id, commit.new_epoch),
+        "guess authenticator",
+    )
+    .to_vec()
+}
+
+fn member_hpke_key(state_bytes: &[u8], user_id: &str) -> Vec<u8> {
+    let state: GroupStateData = must(serde_json::from_slice(state_bytes), "parse state");
+    state
+        .members
+        .into_iter()
+        .find(|member| member.user_id == user_id)
+        .map(|member| member.hpke_public_key)
+        .unwrap_or_default()
+}
+
+#[test]
+fn rekey_on_remove_locks_removed_member_out_of_next_epoch() {
+    let group = trio("group-policy");
+    let alice_state = must(
+        set_group_policy(
+            &group.alice,
+            GroupPolicyData {
+                rekey_on_remove: true,
+                ..GroupPolicyData::default()
+            },
+        ),
+        "enable rekey on remove",
+    );
+    assert!(must(group_policy(&alice_state), "read policy").rekey_on_remove);
+
+    let removed = must(remove_member(&alice_state, 1), "alice removes bob");
+    let carol = must(
+        process_commit(&group.carol, &removed.commit),
+        "carol processes removal",
+    );
+
+    let alice_authenticator = must(epoch_authenticator(&removed.state), "alice authenticator");
+    assert_eq!(
+        alice_authenticator,
+        must(epoch_authenticator(&carol.state), "carol authenticator")
+    );
+    assert_ne!(
+        alice_authenticator,
+        removed_member_guess(&group.bob, &removed.commit)
+    );
+    assert!(process_commit(&group.bob, &removed.commit).is_err());
+    assert_ne!(
+        member_hpke_key(&group.carol, "alice"),
+        member_hpke_key(&carol.state, "alice")
+    );
+}
+
+#[test]
+fn plain_remove_keeps_path_free_commit() {
+    let group = trio("group-policy-2");
+
+    let removed = must(remove_member(&group.alice, 1), "alice removes bob");
+    let carol = must(
+        process_commit(&group.carol, &removed.commit),
+        "carol processes removal",
+    );
+
+    assert_eq!(
+        must(epoch_authenticator(&carol.state), "carol authenticator"),
+        removed_member_guess(&group.bob, &removed.commit)
+    );
+    assert_eq!(
+        member_hpke_key(&group.carol, "alice"),
+        member_hpke_key(&carol.state, "alice")
+    );
+}
+
+#[test]
+fn min_members_policy_refuses_removal_below_minimum() {
+    let group = trio("group-min-size");
+    let alice_state = must(
+        set_group_policy(
+            &group.alice,
+            GroupPolicyData {
+                min_members: 2,
+                ..GroupPolicyData::default()
+            },
+        ),
+        "set minimum group size",
+    );
+
+    let removed = must(remove_member(&alice_state, 1), "alice removes bob");
+    let Err(error) = remove_member(&removed.state, 2) else {
+        panic!("expected minimum group size error");
+    };
+    assert_eq!(error.item_context(), Some(&ErrorContext::LeafIndex(2)));
+    let error = match error.kind() {
+        MlsError::MinimumGroupSize(message) => message.clone(),
+        other => panic!("expected minimum group size error, got {other:?}"),
+    };
+    assert!(error.contains("leave 1 of 2 members"), "{error}");
+    assert!(error.contains("minimum of 2"), "{error}");
+}
+
+#[test]
+fn read_only_member_follows_but_cannot_author() {
+    let group = trio("group-observer");
+    let observer = must(
+        set_group_policy(
+            &group.bob,
+            GroupPolicyData {
+                read_only: true,
+                ..GroupPolicyData::default()
+            },
+        ),
+        "mark bob read-only",
+    );
+
+    let credential = must(generate_credential("dave"), "generate credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    assert!(matches!(
+        encrypt_message(&observer, b"hi"),
+        Err(MlsError::ReadOnlyClient(_))
+    ));
+    assert!(matches!(
+        add_member(&observer, &key_package.key_package),
+        Err(MlsError::ReadOnlyClient(_))
+    ));
+    assert!(matches!(
+        remove_member(&observer, 2),
+        Err(MlsError::ReadOnlyClient(_))
+    ));
+    assert!(matches!(
+        self_update(&observer),
+        Err(MlsError::ReadOnlyClient(_))
+    ));
+
+    let removed = must(remove_member(&group.alice, 2), "alice removes carol");
+    let followed = must(
+        process_commit(&observer, &removed.commit),
+        "observer processes commit",
+    );
+    let ciphertext = must(
+        encrypt_message(&removed.state, b"recorded"),
+        "alice encrypts",
+    );
+    let decrypted = must(
+        decrypt_message(&followed.state, &ciphertext, false),
+        "observer decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"recorded");
+    assert!(must(group_policy(&followed.state), "policy survives commit").read_only);
+}
+
+#[test]
+fn deleted_signing_key_refuses_every_signing_path() {
+    let group = trio("group-deleted-key");
+    let bob = must(delete_signing_key(&group.bob), "delete bob signing key");
+    assert!(must(group_state_metadata(&bob), "bob metadata").signing_key_deleted);
+    assert!(!must(group_state_metadata(&group.bob), "old metadata").signing_key_deleted);
+
+    let credential = must(generate_credential("dave"), "generate credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    assert!(matches!(
+        encrypt_message(&bob, b"hi"),
+        Err(MlsError::SigningKeyDeleted(_))
+    ));
+    assert!(matches!(
+        add_member(&bob, &key_package.key_package),
+        Err(MlsError::SigningKeyDeleted(_))
+    ));
+    assert!(matches!(
+        remove_member(&bob, 2),
+        Err(MlsError::SigningKeyDeleted(_))
+    ));
+    assert!(matches!(
+        self_update(&bob),
+        Err(MlsError::SigningKeyDeleted(_))
+    ));
+    assert!(matches!(
+        force_leave(&mut bob.clone()),
+        Err(MlsError::SigningKeyDeleted(_))
+    ));
+
+    let ciphertext = must(encrypt_message(&group.alice, b"still"), "alice encrypts");
+    let decrypted = must(
+        decrypt_message(&bob, &ciphertext, false),
+        "bob still decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"still");
+}
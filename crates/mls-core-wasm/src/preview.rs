@@ -0,0 +1,65 @@
+use crate::{
+    commit::{apply_operation, decode_commit, verify_commit},
+    error::MlsError,
+    model::{CommitOperationData, CommitPreviewOutput, GroupMemberData, ProposalOperationData},
+    protocol::{decode_group_state, member_metadata, self_leaf_index},
+};
+
+fn removed_leaves(operation: &CommitOperationData) -> Vec<u32> {
+    match operation {
+        CommitOperationData::Remove { leaf_index }
+        | CommitOperationData::Replace { leaf_index, .. } => vec![*leaf_index],
+        CommitOperationData::Proposals { proposals } => proposals
+            .iter()
+            .filter_map(|proposal| match proposal.operation {
+                ProposalOperationData::Remove { leaf_index } => Some(leaf_index),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn members_missing_from(
+    members: &[GroupMemberData],
+    other: &[GroupMemberData],
+) -> Vec<GroupMemberData> {
+    members
+        .iter()
+        .filter(|member| {
+            !other
+                .iter()
+                .any(|candidate| candidate.leaf_index == member.leaf_index)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Verifies a commit and reports its membership effect without advancing the state.
+pub(crate) fn preview_commit(
+    group_state_bytes: &[u8],
+    commit_bytes: &[u8],
+) -> Result<CommitPreviewOutput, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let commit = decode_commit(commit_bytes)?;
+    verify_commit(&state, &commit)?;
+
+    let removed = removed_leaves(&commit.operation);
+    let removes_self = removed.contains(&self_leaf_index(&state)?);
+    let mut staged = state.clone();
+    // Applying a self-removal fails by design, so only drop the removed leaves.
+    if removes_self {
+        staged
+            .members
+            .retain(|member| !removed.contains(&member.leaf_index));
+    } else {
+        apply_operation(&mut staged, commit.operation)?;
+    }
+
+    Ok(CommitPreviewOutput {
+        new_epoch: commit.new_epoch,
+        added: member_metadata(&members_missing_from(&staged.members, &state.members))?,
+        removed: member_metadata(&members_missing_from(&state.members, &staged.members))?,
+        removes_self,
+    })
+}
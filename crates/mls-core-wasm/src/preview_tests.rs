@@ -0,0 +1,72 @@
+use crate::{
+    commit::process_commit,
+    operations::remove_member,
+    preview::preview_commit,
+    test_support::{add_and_join, create_single_member_state, legacy_payload, must},
+};
+
+#[test]
+fn preview_reports_added_members_and_matches_processing() {
+    let alice_state = create_single_member_state("group-preview", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-preview", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added.state, "group-preview", "carol");
+
+    let preview = must(
+        preview_commit(&bob_state, &added_carol.commit),
+        "bob previews carol add",
+    );
+    assert_eq!(preview.new_epoch, added_carol.new_epoch);
+    assert!(!preview.removes_self);
+    assert!(preview.removed.is_empty());
+    let added_ids: Vec<&str> = preview
+        .added
+        .iter()
+        .map(|member| member.user_id.as_str())
+        .collect();
+    assert_eq!(added_ids, ["carol"]);
+
+    let processed = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob merges carol add",
+    );
+    assert_eq!(processed.new_epoch, preview.new_epoch);
+}
+
+#[test]
+fn preview_flags_commits_that_remove_the_local_member() {
+    let alice_state = create_single_member_state("group-preview-2", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-preview-2", "bob");
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+
+    let preview = must(
+        preview_commit(&bob_state, &removed.commit),
+        "bob previews removal",
+    );
+    assert!(preview.removes_self);
+    assert!(preview.added.is_empty());
+    assert_eq!(preview.removed.len(), 1);
+    assert_eq!(preview.removed[0].user_id, "bob");
+
+    assert!(preview_commit(&removed.state, &removed.commit).is_err());
+}
+
+#[test]
+fn version_one_commits_are_rejected_with_a_version_error() {
+    let alice_state = create_single_member_state("group-preview-3", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-preview-3", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added.state, "group-preview-3", "carol");
+    let legacy = legacy_payload(&added_carol.commit, 1, &["path"]);
+
+    for result in [
+        preview_commit(&bob_state, &legacy).map(|_| ()),
+        process_commit(&bob_state, &legacy).map(|_| ()),
+    ] {
+        let Err(error) = result else {
+            panic!("version 1 commit was accepted");
+        };
+        assert_eq!(
+            error.to_string(),
+            "unsupported commit version 1; expected 2"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    commit::{SignedCommit, advance_epoch, apply_operation, sign_commit},
+    crypto::{sha256, sign_bytes, verify_signature},
+    error::MlsError,
+    model::{
+        AddMembersOutput, CommitOperationData, ForceLeaveOutput, GroupStateData,
+        MLS_PROPOSAL_VERSION, MemberWelcomeOutput, ProposalData, ProposalOperationData,
+        ProposalSenderData, StoreProposalOutput, UnsignedProposalData,
+    },
+    operations::group_info_bytes,
+    path_update::commit_with_path,
+    policy::{ensure_minimum_group_size, self_signing_key},
+    protocol::{
+        decode_group_state, deserialize_json, encode_group_state, self_leaf_index, serialize_json,
+    },
+    welcome::{WelcomeRecipient, build_welcome},
+};
+
+pub(crate) fn sign_member_proposal(
+    state: &GroupStateData,
+    operation: ProposalOperationData,
+) -> Result<ProposalData, MlsError> {
+    let unsigned = UnsignedProposalData {
+        version: MLS_PROPOSAL_VERSION,
+        group_id: state.group_id.clone(),
+        epoch: state.epoch,
+        sender: ProposalSenderData::Member {
+            leaf_index: self_leaf_index(state)?,
+        },
+        operation,
+    };
+    let signature = sign_bytes(self_signing_key(state)?, &serialize_json(&unsigned)?)?;
+
+    Ok(ProposalData {
+        version: unsigned.version,
+        group_id: unsigned.group_id,
+        epoch: unsigned.epoch,
+        sender: unsigned.sender,
+        operation: unsigned.operation,
+        signature,
+    })
+}
+
+/// Emits a self-remove proposal and wipes the caller's serialized state buffer.
+///
+/// The local client should drop the group immediately. Remaining members keep
+/// the leaver in their member list until one of them commits the proposal.
+pub(crate) fn force_leave(group_state_bytes: &mut [u8]) -> Result<ForceLeaveOutput, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let self_leaf = self_leaf_index(&state)?;
+    let proposal = sign_member_proposal(
+        &state,
+        ProposalOperationData::Remove {
+            leaf_index: self_leaf,
+        },
+    )?;
+
+    let output = ForceLeaveOutput {
+        group_id: state.group_id,
+        epoch: state.epoch,
+        proposal: serialize_json(&proposal)?,
+    };
+    group_state_bytes.fill(0);
+
+    Ok(output)
+}
+
+/// Checks that a proposal targets the current epoch and is signed by a member or
+/// an external sender listed in the group context.
+pub(crate) fn verify_proposal(
+    state: &GroupStateData,
+    proposal: &ProposalData,
+) -> Result<(), MlsError> {
+    if proposal.version != MLS_PROPOSAL_VERSION {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported proposal version {}",
+            proposal.version
+        )));
+    }
+
+    if proposal.group_id != state.group_id || proposal.epoch != state.epoch {
+        return Err(MlsError::InvalidInput(format!(
+            "proposal targets {} epoch {}, expected {} epoch {}",
+            proposal.group_id, proposal.epoch, state.group_id, state.epoch
+        )));
+    }
+
+    let signing_public_key = match &proposal.sender {
+        ProposalSenderData::Member { leaf_index } => state
+            .members
+            .iter()
+            .find(|member| member.leaf_index == *leaf_index)
+            .map(|member| &member.signing_public_key)
+            .ok_or_else(|| {
+                MlsError::NotFound(format!("proposal sender leaf {leaf_index} not found"))
+            })?,
+        ProposalSenderData::External { user_id } => state
+            .group_context
+            .external_senders
+            .iter()
+            .find(|sender| &sender.user_id == user_id)
+            .map(|sender| &sender.signing_public_key)
+            .ok_or_else(|| {
+                MlsError::NotFound(format!("external proposal sender {user_id} not found"))
+            })?,
+    };
+
+    let unsigned = UnsignedProposalData {
+        version: proposal.version,
+        group_id: proposal.group_id.clone(),
+        epoch: proposal.epoch,
+        sender: proposal.sender.clone(),
+        operation: proposal.operation.clone(),
+    };
+    verify_signature(
+        signing_public_key,
+        &serialize_json(&unsigned)?,
+        &proposal.signature,
+    )
+}
+
+/// Verifies a received proposal and keeps it pending until it is committed.
+pub(crate) fn store_proposal(
+    group_state_bytes: &[u8],
+    proposal_bytes: &[u8],
+) -> Result<StoreProposalOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    let proposal: ProposalData = deserialize_json(proposal_bytes, "proposal")?;
+    verify_proposal(&state, &proposal)?;
+
+    let proposal_ref = hex::encode(sha256(proposal_bytes));
+    state
+        .pending_proposals
+        .insert(proposal_ref.clone(), proposal);
+
+    Ok(StoreProposalOutput {
+        state: encode_group_state(&state)?,
+        proposal_ref,
+    })
+}
+
+/// Counts verified proposals awaiting a commit in the current epoch.
+pub(crate) fn pending_proposal_count(group_state_bytes: &[u8]) -> Result<u32, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    u32::try_from(state.pending_proposals.len())
+        .map_err(|_| MlsError::InvalidState("pending proposal count is out of range".to_owned()))
+}
+
+/// Commits only the pending proposals named by `proposal_refs`, in the given order.
+///
+/// The commit embeds the proposals by value, so receivers need not have stored them.
+/// Members added by proposals get one shared welcome. Pending proposals left out of
+/// the commit expire with the epoch it ends.
+pub(crate) fn commit_received_proposals(
+    group_state_bytes: &[u8],
+    proposal_refs: &[String],
+) -> Result<AddMembersOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    if proposal_refs.is_empty() {
+        return Err(MlsError::InvalidInput(
+            "at least one proposal reference is required".to_owned(),
+        ));
+    }
+
+    let mut seen = BTreeSet::new();
+    let mut proposals = Vec::with_capacity(proposal_refs.len());
+    for proposal_ref in proposal_refs {
+        if !seen.insert(proposal_ref.as_str()) {
+            return Err(MlsError::InvalidInput(format!(
+                "proposal {proposal_ref} referenced more than once"
+            )));
+        }
+        let proposal = state
+            .pending_proposals
+            .get(proposal_ref)
+            .ok_or_else(|| MlsError::NotFound(format!("proposal {proposal_ref} is not pending")))?;
+        proposals.push(proposal.clone());
+    }
+
+    let removals = proposals
+        .iter()
+        .filter(|proposal| matches!(proposal.operation, ProposalOperationData::Remove { .. }))
+        .count();
+    ensure_minimum_group_size(&state, removals)?;
+    let self_leaf = self_leaf_index(&state)?;
+    let previous_leaves: BTreeSet<u32> = state
+        .members
+        .iter()
+        .map(|member| member.leaf_index)
+        .collect();
+    let operation = CommitOperationData::Proposals { proposals };
+    let signed: SignedCommit = if state.policy.rekey_on_remove {
+        apply_operation(&mut state, operation.clone())?;
+        commit_with_path(&mut state, operation)?
+    } else {
+        let signed = sign_commit(&state, operation.clone())?;
+        apply_operation(&mut state, operation)?;
+        advance_epoch(
+            &mut state,
+            signed.commit.new_epoch,
+            &signed.unsigned_commit_bytes,
+        )?;
+        signed
+    };
+
+    let recipients: Vec<WelcomeRecipient> = state
+        .members
+        .iter()
+        .filter(|member| !previous_leaves.contains(&member.leaf_index))
+        .map(|member| WelcomeRecipient {
+            key_package_ref: member.key_package_ref.clone(),
+            hpke_public_key: member.hpke_public_key.clone(),
+        })
+        .collect();
+    let welcomes = if recipients.is_empty() {
+        Vec::new()
+    } else {
+        let welcome = build_welcome(&state, self_leaf, &recipients)?;
+        recipients
+            .into_iter()
+            .map(|recipient| MemberWelcomeOutput {
+                key_package_ref: recipient.key_package_ref,
+                epoch: state.epoch,
+                ciphersuite: state.ciphersuite,
+                welcome: welcome.clone(),
+            })
+            .collect()
+    };
+
+    Ok(AddMembersOutput {
+        state: encode_group_state(&state)?,
+        commit: serialize_json(&signed.commit)?,
+        welcomes,
+        group_info: group_info_bytes(&state)?,
+        new_epoch: signed.commit.new_epoch,
+    })
+}
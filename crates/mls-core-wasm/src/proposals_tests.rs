@@ -0,0 +1,304 @@
+use crate::{
+    commit::process_commit,
+    crypto::{random_bytes, sign_bytes, signing_key_from_private, verify_signature},
+    error::MlsError,
+    group_context::update_group_context,
+    key_package::generate_key_package,
+    messaging::{decrypt_message, encrypt_message},
+    model::{
+        ExternalSenderData, GroupContextData, MLS_PROPOSAL_VERSION, ProposalData,
+        ProposalOperationData, ProposalSenderData, UnsignedProposalData,
+    },
+    operations::join_group,
+    preview::preview_commit,
+    proposals::{
+        commit_received_proposals, force_leave, pending_proposal_count, sign_member_proposal,
+        store_proposal,
+    },
+    protocol::{
+        decode_group_state, deserialize_json, generate_credential, group_state_metadata,
+        serialize_json,
+    },
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+/// Proposal signed by the `delivery-service` external sender.
+fn external_proposal(
+    private_key: &[u8],
+    group_id: &str,
+    epoch: u64,
+    operation: ProposalOperationData,
+) -> Vec<u8> {
+    let unsigned = UnsignedProposalData {
+        version: MLS_PROPOSAL_VERSION,
+        group_id: group_id.to_owned(),
+        epoch,
+        sender: ProposalSenderData::External {
+            user_id: "delivery-service".to_owned(),
+        },
+        operation,
+    };
+    let signature = must(
+        sign_bytes(
+            private_key,
+            &must(serialize_json(&unsigned), "encode unsigned proposal"),
+        ),
+        "sign external proposal",
+    );
+    must(
+        serialize_json(&ProposalData {
+            version: unsigned.version,
+            group_id: unsigned.group_id,
+            epoch: unsigned.epoch,
+            sender: unsigned.sender,
+            operation: unsigned.operation,
+            signature,
+        }),
+        "encode external proposal",
+    )
+}
+
+#[test]
+fn force_leave_emits_signed_self_remove_and_wipes_state() {
+    let alice_state = create_single_member_state("group-leave", "alice");
+    let (_added, bob_state) = add_and_join(&alice_state, "group-leave", "bob");
+    let bob_decoded = must(decode_group_state(&bob_state), "decode bob state");
+
+    let mut wiped = bob_state.clone();
+    let output = must(force_leave(&mut wiped), "bob force leaves");
+
+    assert_eq!(output.group_id, "group-leave");
+    assert_eq!(output.epoch, 1);
+    assert!(wiped.iter().all(|byte| *byte == 0));
+    assert!(decode_group_state(&wiped).is_err());
+
+    let proposal: ProposalData = must(
+        deserialize_json(&output.proposal, "proposal"),
+        "decode proposal",
+    );
+    assert_eq!(
+        proposal.sender,
+        ProposalSenderData::Member { leaf_index: 1 }
+    );
+    assert_eq!(
+        proposal.operation,
+        ProposalOperationData::Remove { leaf_index: 1 }
+    );
+
+    let unsigned = UnsignedProposalData {
+        version: proposal.version,
+        group_id: proposal.group_id.clone(),
+        epoch: proposal.epoch,
+        sender: proposal.sender.clone(),
+        operation: proposal.operation.clone(),
+    };
+    let unsigned_bytes = must(serialize_json(&unsigned), "serialize unsigned proposal");
+    must(
+        verify_signature(
+            &bob_decoded.self_signing_public_key,
+            &unsigned_bytes,
+            &proposal.signature,
+        ),
+        "verify leave proposal signature",
+    );
+}
+
+#[test]
+fn force_leave_rejects_invalid_state() {
+    let mut garbage = b"not a group state".to_vec();
+    assert!(force_leave(&mut garbage).is_err());
+}
+
+#[test]
+fn commit_received_proposals_commits_only_selected_refs() {
+    let alice_state = create_single_member_state("group-moderate", "alice");
+    let (added_bob, bob_state) = add_and_join(&alice_state, "group-moderate", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added_bob.state, "group-moderate", "carol");
+    let bob_state = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob processes carol add",
+    )
+    .state;
+
+    let bob_decoded = must(decode_group_state(&bob_state), "decode bob state");
+    let remove_carol = must(
+        sign_member_proposal(
+            &bob_decoded,
+            ProposalOperationData::Remove { leaf_index: 2 },
+        ),
+        "bob proposes removing carol",
+    );
+    let remove_carol_bytes = must(serialize_json(&remove_carol), "encode proposal");
+    let mut bob_leaving = bob_state.clone();
+    let bob_leave = must(force_leave(&mut bob_leaving), "bob leaves");
+
+    let stored_carol = must(
+        store_proposal(&added_carol.state, &remove_carol_bytes),
+        "alice stores carol removal",
+    );
+    let stored_leave = must(
+        store_proposal(&stored_carol.state, &bob_leave.proposal),
+        "alice stores bob leave",
+    );
+    assert_eq!(must(pending_proposal_count(&added_carol.state), "none"), 0);
+    assert_eq!(must(pending_proposal_count(&stored_leave.state), "two"), 2);
+
+    let committed = must(
+        commit_received_proposals(
+            &stored_leave.state,
+            std::slice::from_ref(&stored_carol.proposal_ref),
+        ),
+        "alice commits carol removal",
+    );
+    assert_eq!(must(pending_proposal_count(&committed.state), "expired"), 0);
+    let metadata = must(group_state_metadata(&committed.state), "metadata");
+    let members: Vec<&str> = metadata
+        .members
+        .iter()
+        .map(|member| member.user_id.as_str())
+        .collect();
+    assert_eq!(members, ["alice", "bob"]);
+
+    let processed = must(
+        process_commit(&bob_state, &committed.commit),
+        "bob processes removal",
+    );
+    assert_eq!(processed.new_epoch, committed.new_epoch);
+    let bob_metadata = must(group_state_metadata(&processed.state), "bob metadata");
+    assert_eq!(bob_metadata.members.len(), 2);
+}
+
+#[test]
+fn commit_received_proposals_rejects_unknown_refs() {
+    let alice_state = create_single_member_state("group-moderate-2", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-moderate-2", "bob");
+
+    assert!(matches!(
+        commit_received_proposals(&added.state, &["missing".to_owned()]),
+        Err(MlsError::NotFound(_))
+    ));
+}
+
+#[test]
+fn commit_with_inline_external_proposals_is_processed_end_to_end() {
+    let alice_state = create_single_member_state("group-inline", "alice");
+    let (added_bob, bob_state) = add_and_join(&alice_state, "group-inline", "bob");
+    let (added_carol, _carol_state) = add_and_join(&added_bob.state, "group-inline", "carol");
+    let bob_state = must(
+        process_commit(&bob_state, &added_carol.commit),
+        "bob processes carol add",
+    )
+    .state;
+
+    let service_key = must(random_bytes::<32>(), "service key").to_vec();
+    let service_public_key = must(
+        signing_key_from_private(&service_key),
+        "service signing key",
+    )
+    .verifying_key()
+    .to_bytes()
+    .to_vec();
+    let context = must(
+        update_group_context(
+            &added_carol.state,
+            GroupContextData {
+                name: None,
+                external_senders: vec![ExternalSenderData {
+                    user_id: "delivery-service".to_owned(),
+                    signing_public_key: service_public_key,
+                }],
+                ratchet_tree_out_of_band: false,
+            },
+        ),
+        "alice trusts delivery service",
+    );
+    let bob_state = must(
+        process_commit(&bob_state, &context.commit),
+        "bob processes context",
+    )
+    .state;
+
+    let dave = must(generate_credential("dave"), "dave credential");
+    let dave_key_package = must(
+        generate_key_package(&dave.credential_bundle, &dave.private_key),
+        "dave key package",
+    );
+    let add_dave = external_proposal(
+        &service_key,
+        "group-inline",
+        context.new_epoch,
+        ProposalOperationData::Add {
+            key_package: dave_key_package.key_package.clone(),
+        },
+    );
+    let remove_carol = external_proposal(
+        &service_key,
+        "group-inline",
+        context.new_epoch,
+        ProposalOperationData::Remove { leaf_index: 2 },
+    );
+
+    let stored_add = must(store_proposal(&context.state, &add_dave), "store add");
+    let stored_remove = must(
+        store_proposal(&stored_add.state, &remove_carol),
+        "store remove",
+    );
+    let committed = must(
+        commit_received_proposals(
+            &stored_remove.state,
+            &[stored_add.proposal_ref, stored_remove.proposal_ref],
+        ),
+        "alice commits external proposals",
+    );
+    assert_eq!(committed.welcomes.len(), 1);
+    assert_eq!(
+        committed.welcomes[0].key_package_ref,
+        dave_key_package.key_package_ref
+    );
+
+    // Bob never stored the proposals; the commit carries them by value.
+    let preview = must(
+        preview_commit(&bob_state, &committed.commit),
+        "bob previews commit",
+    );
+    let added: Vec<&str> = preview
+        .added
+        .iter()
+        .map(|member| member.user_id.as_str())
+        .collect();
+    let removed: Vec<&str> = preview
+        .removed
+        .iter()
+        .map(|member| member.user_id.as_str())
+        .collect();
+    assert_eq!(added, ["dave"]);
+    assert_eq!(removed, ["carol"]);
+
+    let processed = must(
+        process_commit(&bob_state, &committed.commit),
+        "bob processes commit",
+    );
+    assert_eq!(processed.new_epoch, committed.new_epoch);
+
+    let dave_state = must(
+        join_group(
+            "group-inline",
+            &committed.welcomes[0].welcome,
+            &dave_key_package.key_package_ref,
+            &dave_key_package.private_key,
+            &dave.credential_bundle,
+            &dave.private_key,
+        ),
+        "dave joins",
+    )
+    .state;
+    let ciphertext = must(
+        encrypt_message(&processed.state, b"welcome"),
+        "bob encrypts",
+    );
+    let decrypted = must(
+        decrypt_message(&dave_state, &ciphertext, false),
+        "dave decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"welcome");
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -6,15 +6,16 @@ use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{
     crypto::{
-        generate_x25519_key_pair, random_bytes, require_key_bytes, sha256, sign_bytes,
-        signing_key_from_private, verify_signature,
+        generate_x25519_key_pair, random_bytes, require_key_bytes, signing_key_from_private,
+        x25519_public_key,
     },
     error::MlsError,
+    identity::{generate_credential_from_identity, user_id_identity},
     model::{
-        CredentialBundleData, EpochSecretData, GeneratedCredentialOutput,
-        GeneratedKeyPackageOutput, GroupMemberData, GroupMemberMetadataOutput, GroupStateData,
-        GroupStateMetadataOutput, ImportStateOutput, KeyPackageData, MLS_CIPHERSUITE_ID,
-        MLS_KEY_PACKAGE_VERSION, MLS_STATE_VERSION, UnsignedKeyPackageData,
+        CredentialBundleData, EpochSecretData, GeneratedCredentialOutput, GroupContextData,
+        GroupMemberData, GroupMemberMetadataOutput, GroupPolicyData, GroupStateData,
+        GroupStateMetadataOutput, ImportStateOutput, MLS_CIPHERSUITE_ID, MLS_CREDENTIAL_VERSION,
+        MLS_STATE_VERSION,
     },
 };
 
@@ -25,16 +26,6 @@ pub(crate) struct AeadMetadata {
     sender_leaf_index: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub(crate) struct WelcomeAeadMetadata {
-    pub(crate) group_id: String,
-    pub(crate) epoch: u64,
-    pub(crate) key_package_ref: String,
-    pub(crate) inviter_leaf_index: u32,
-    pub(crate) signer_leaf_index: u32,
-    pub(crate) ephemeral_public_key: Vec<u8>,
-}
-
 #[cfg(target_arch = "wasm32")]
 pub(crate) fn now_ms() -> Result<u64, MlsError> {
     Ok(js_sys::Date::now() as u64)
@@ -62,6 +53,27 @@ pub(crate) fn deserialize_json<T: DeserializeOwned>(
         .map_err(|error| MlsError::Serialization(format!("invalid {context} payload: {error}")))
 }
 
+/// Checks a payload's `version` before decoding the rest, so payloads from an
+/// older schema fail with a version error instead of a missing-field one.
+pub(crate) fn ensure_schema_version(
+    bytes: &[u8],
+    context: &str,
+    expected: u8,
+) -> Result<(), MlsError> {
+    #[derive(Deserialize)]
+    struct SchemaVersion {
+        version: u8,
+    }
+
+    let SchemaVersion { version } = deserialize_json(bytes, context)?;
+    if version != expected {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported {context} version {version}; expected {expected}"
+        )));
+    }
+    Ok(())
+}
+
 pub(crate) fn ensure_non_empty(value: &str, field: &str) -> Result<(), MlsError> {
     if value.trim().is_empty() {
         return Err(MlsError::InvalidInput(format!("{field} is required")));
@@ -80,6 +92,29 @@ fn validate_member(member: &GroupMemberData) -> Result<(), MlsError> {
     Ok(())
 }
 
+pub(crate) fn validate_group_context(group_context: &GroupContextData) -> Result<(), MlsError> {
+    if let Some(name) = &group_context.name {
+        ensure_non_empty(name, "group_context.name")?;
+    }
+
+    let mut sender_ids = HashSet::new();
+    for sender in &group_context.external_senders {
+        ensure_non_empty(&sender.user_id, "external_sender.user_id")?;
+        require_key_bytes::<32>(
+            &sender.signing_public_key,
+            "external_sender.signing_public_key",
+        )?;
+        if !sender_ids.insert(sender.user_id.as_str()) {
+            return Err(MlsError::InvalidInput(format!(
+                "duplicate external sender {}",
+                sender.user_id
+            )));
+        }
+    }
+
+    Ok(())
+}
+
 fn ensure_unique_members(members: &[GroupMemberData]) -> Result<(), MlsError> {
     if members.is_empty() {
         return Err(MlsError::InvalidState(
@@ -117,7 +152,7 @@ pub(crate) fn verify_credential(
     let credential: CredentialBundleData =
         deserialize_json(credential_bundle_bytes, "credential bundle")?;
 
-    if credential.version != MLS_KEY_PACKAGE_VERSION {
+    if credential.version != MLS_CREDENTIAL_VERSION {
         return Err(MlsError::InvalidInput(format!(
             "unsupported credential version {}",
             credential.version
@@ -136,41 +171,6 @@ pub(crate) fn verify_credential(
     Ok(credential)
 }
 
-pub(crate) fn decode_key_package(key_package_bytes: &[u8]) -> Result<KeyPackageData, MlsError> {
-    let key_package: KeyPackageData = deserialize_json(key_package_bytes, "key package")?;
-
-    if key_package.version != MLS_KEY_PACKAGE_VERSION {
-        return Err(MlsError::InvalidInput(format!(
-            "unsupported key package version {}",
-            key_package.version
-        )));
-    }
-
-    ensure_non_empty(&key_package.user_id, "key_package.user_id")?;
-    require_key_bytes::<32>(
-        &key_package.signing_public_key,
-        "key_package.signing_public_key",
-    )?;
-    require_key_bytes::<32>(&key_package.hpke_public_key, "key_package.hpke_public_key")?;
-
-    let unsigned = UnsignedKeyPackageData {
-        version: key_package.version,
-        user_id: key_package.user_id.clone(),
-        signing_public_key: key_package.signing_public_key.clone(),
-        hpke_public_key: key_package.hpke_public_key.clone(),
-        created_at_ms: key_package.created_at_ms,
-    };
-    let unsigned_bytes = serialize_json(&unsigned)?;
-
-    verify_signature(
-        &key_package.signing_public_key,
-        &unsigned_bytes,
-        &key_package.signature,
-    )?;
-
-    Ok(key_package)
-}
-
 fn normalize_state(mut state: GroupStateData) -> Result<GroupStateData, MlsError> {
     if state.version != MLS_STATE_VERSION {
         return Err(MlsError::InvalidState(format!(
@@ -189,23 +189,27 @@ fn normalize_state(mut state: GroupStateData) -> Result<GroupStateData, MlsError
         )));
     }
 
-    require_key_bytes::<32>(
-        &state.self_signing_private_key,
-        "group_state.self_signing_private_key",
-    )?;
     require_key_bytes::<32>(
         &state.self_signing_public_key,
         "group_state.self_signing_public_key",
     )?;
 
-    let signing_key = signing_key_from_private(&state.self_signing_private_key)?;
-    if signing_key.verifying_key().to_bytes().as_slice() != state.self_signing_public_key {
-        return Err(MlsError::InvalidState(
-            "group state self signing key pair does not match".to_owned(),
-        ));
+    // An empty private key was deleted on purpose; see `delete_signing_key`.
+    if !state.self_signing_private_key.is_empty() {
+        require_key_bytes::<32>(
+            &state.self_signing_private_key,
+            "group_state.self_signing_private_key",
+        )?;
+        let signing_key = signing_key_from_private(&state.self_signing_private_key)?;
+        if signing_key.verifying_key().to_bytes().as_slice() != state.self_signing_public_key {
+            return Err(MlsError::InvalidState(
+                "group state self signing key pair does not match".to_owned(),
+            ));
+        }
     }
 
     ensure_unique_members(&state.members)?;
+    validate_group_context(&state.group_context)?;
 
     let self_member = state
         .members
@@ -223,12 +227,41 @@ fn normalize_state(mut state: GroupStateData) -> Result<GroupStateData, MlsError
         ));
     }
 
+    // States saved before update paths existed have no leaf key. They still
+    // load so messaging keeps working; `self_update` rotates a key in, and
+    // until then opening an update path fails with `LegacyLeafKey`.
+    if !state.self_hpke_private_key.is_empty()
+        && x25519_public_key(&state.self_hpke_private_key)? != self_member.hpke_public_key
+    {
+        return Err(MlsError::InvalidState(
+            "group state self member HPKE key does not match local key".to_owned(),
+        ));
+    }
+
+    if state.solo && state.members.len() != 1 {
+        return Err(MlsError::InvalidState(
+            "solo group state must contain exactly one member".to_owned(),
+        ));
+    }
+
+    if state.last_update_epoch > state.epoch {
+        return Err(MlsError::InvalidState(format!(
+            "last update epoch {} is ahead of current epoch {}",
+            state.last_update_epoch, state.epoch
+        )));
+    }
+
     if state.epoch_secrets.is_empty() {
         return Err(MlsError::InvalidState(
             "group state must include at least one epoch secret".to_owned(),
         ));
     }
 
+    for (psk_id, secret) in &state.external_psks {
+        ensure_non_empty(psk_id, "external_psk.psk_id")?;
+        require_key_bytes::<32>(secret, "external_psk.secret")?;
+    }
+
     let mut secret_epochs = BTreeSet::new();
     for entry in &state.epoch_secrets {
         require_key_bytes::<32>(&entry.secret, "epoch_secret")?;
@@ -240,6 +273,15 @@ fn normalize_state(mut state: GroupStateData) -> Result<GroupStateData, MlsError
         }
     }
 
+    if let Some(latest) = secret_epochs.last()
+        && *latest > state.epoch
+    {
+        return Err(MlsError::InvalidState(format!(
+            "epoch secret for epoch {latest} is ahead of current epoch {}",
+            state.epoch
+        )));
+    }
+
     if !secret_epochs.contains(&state.epoch) {
         return Err(MlsError::InvalidState(format!(
             "missing epoch secret for current epoch {}",
@@ -305,98 +347,54 @@ pub(crate) fn add_epoch_secret(state: &mut GroupStateData, epoch: u64, secret: V
     }
 }
 
+pub(crate) fn member_metadata(
+    members: &[GroupMemberData],
+) -> Result<Vec<GroupMemberMetadataOutput>, MlsError> {
+    members
+        .iter()
+        .map(|member| {
+            Ok(GroupMemberMetadataOutput {
+                user_id: member.user_id.clone(),
+                identity: user_id_identity(&member.user_id)?,
+                leaf_index: member.leaf_index,
+            })
+        })
+        .collect()
+}
+
 pub(crate) fn group_state_metadata(
     group_state_bytes: &[u8],
 ) -> Result<GroupStateMetadataOutput, MlsError> {
     let state = decode_group_state(group_state_bytes)?;
-    let members = state
-        .members
-        .iter()
-        .map(|member| GroupMemberMetadataOutput {
-            user_id: member.user_id.clone(),
-            leaf_index: member.leaf_index,
-        })
-        .collect();
+    let members = member_metadata(&state.members)?;
 
     Ok(GroupStateMetadataOutput {
+        signing_key_deleted: state.self_signing_private_key.is_empty(),
         group_id: state.group_id,
         epoch: state.epoch,
         self_user_id: state.self_user_id,
         members,
+        group_context: state.group_context,
     })
 }
 
 pub(crate) fn generate_credential(user_id: &str) -> Result<GeneratedCredentialOutput, MlsError> {
     ensure_non_empty(user_id, "user_id")?;
-
-    let private_key = random_bytes::<32>()?.to_vec();
-    let signing_key = signing_key_from_private(&private_key)?;
-    let created_at_ms = now_ms()?;
-
-    let credential_bundle = CredentialBundleData {
-        version: MLS_KEY_PACKAGE_VERSION,
-        user_id: user_id.trim().to_owned(),
-        signing_public_key: signing_key.verifying_key().to_bytes().to_vec(),
-        created_at_ms,
-    };
-
-    Ok(GeneratedCredentialOutput {
-        credential_bundle: serialize_json(&credential_bundle)?,
-        private_key,
-        created_at_ms,
-    })
+    generate_credential_from_identity(user_id.trim().as_bytes())
 }
 
-pub(crate) fn generate_key_package(
-    credential_bundle_bytes: &[u8],
-    credential_private_key_bytes: &[u8],
-) -> Result<GeneratedKeyPackageOutput, MlsError> {
-    let credential = verify_credential(credential_bundle_bytes, credential_private_key_bytes)?;
-    let created_at_ms = now_ms()?;
-    let (hpke_private_key, hpke_public_key) = generate_x25519_key_pair()?;
-
-    let unsigned = UnsignedKeyPackageData {
-        version: MLS_KEY_PACKAGE_VERSION,
-        user_id: credential.user_id,
-        signing_public_key: credential.signing_public_key,
-        hpke_public_key,
-        created_at_ms,
-    };
-
-    let unsigned_bytes = serialize_json(&unsigned)?;
-    let signature = sign_bytes(credential_private_key_bytes, &unsigned_bytes)?;
-
-    let key_package = KeyPackageData {
-        version: unsigned.version,
-        user_id: unsigned.user_id,
-        signing_public_key: unsigned.signing_public_key,
-        hpke_public_key: unsigned.hpke_public_key,
-        created_at_ms: unsigned.created_at_ms,
-        signature,
-    };
-
-    let key_package_bytes = serialize_json(&key_package)?;
-    let key_package_ref = hex::encode(sha256(&key_package_bytes));
-
-    Ok(GeneratedKeyPackageOutput {
-        key_package: key_package_bytes,
-        key_package_ref,
-        private_key: hpke_private_key,
-        created_at_ms,
-    })
-}
-
-pub(crate) fn create_group(
+pub(crate) fn new_group_state(
     group_id: &str,
     credential_bundle_bytes: &[u8],
     credential_private_key_bytes: &[u8],
-) -> Result<Vec<u8>, MlsError> {
+) -> Result<GroupStateData, MlsError> {
     ensure_non_empty(group_id, "group_id")?;
     let credential = verify_credential(credential_bundle_bytes, credential_private_key_bytes)?;
 
     let epoch_secret = random_bytes::<32>()?.to_vec();
+    let (hpke_private_key, hpke_public_key) = generate_x25519_key_pair()?;
 
-    let state = GroupStateData {
+    Ok(GroupStateData {
         version: MLS_STATE_VERSION,
         group_id: group_id.trim().to_owned(),
         epoch: 0,
@@ -404,18 +402,41 @@ pub(crate) fn create_group(
         self_user_id: credential.user_id.clone(),
         self_signing_private_key: credential_private_key_bytes.to_vec(),
         self_signing_public_key: credential.signing_public_key.clone(),
+        self_hpke_private_key: hpke_private_key,
+        last_update_epoch: 0,
         members: vec![GroupMemberData {
             user_id: credential.user_id,
             leaf_index: 0,
             signing_public_key: credential.signing_public_key,
-            hpke_public_key: Vec::new(),
+            hpke_public_key,
+            key_package_ref: String::new(),
+            leaf_epoch: 0,
         }],
         epoch_secrets: vec![EpochSecretData {
             epoch: 0,
             secret: epoch_secret,
         }],
-    };
+        group_context: GroupContextData::default(),
+        confirmed_transcript_hash: Vec::new(),
+        local_metadata: BTreeMap::new(),
+        external_psks: BTreeMap::new(),
+        consumed_key_package_refs: BTreeSet::new(),
+        pending_proposals: BTreeMap::new(),
+        policy: GroupPolicyData::default(),
+        solo: false,
+    })
+}
 
+pub(crate) fn create_group(
+    group_id: &str,
+    credential_bundle_bytes: &[u8],
+    credential_private_key_bytes: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    let state = new_group_state(
+        group_id,
+        credential_bundle_bytes,
+        credential_private_key_bytes,
+    )?;
     encode_group_state(&state)
 }
 
@@ -456,7 +477,3 @@ pub(crate) fn metadata_bytes(
         sender_leaf_index,
     })
 }
-
-pub(crate) fn welcome_metadata_bytes(metadata: &WelcomeAeadMetadata) -> Result<Vec<u8>, MlsError> {
-    serialize_json(metadata)
-}
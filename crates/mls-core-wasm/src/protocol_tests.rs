@@ -1,20 +1,13 @@
 use crate::{
+    commit::process_commit,
+    key_package::generate_key_package,
     messaging::{decrypt_message, encrypt_message},
-    model::ImportStateOutput,
-    operations::{add_member, join_group, process_commit, remove_member},
-    protocol::{
-        create_group, export_group_state, generate_credential, generate_key_package,
-        import_group_state,
-    },
+    model::{ImportStateOutput, MLS_CIPHERSUITE_ID},
+    operations::{add_member, join_group, remove_member},
+    protocol::{create_group, export_group_state, generate_credential, import_group_state},
+    test_support::must,
 };
 
-fn must<T, E: core::fmt::Display>(result: Result<T, E>, context: &str) -> T {
-    match result {
-        Ok(value) => value,
-        Err(error) => panic!("{context}: {error}"),
-    }
-}
-
 #[test]
 fn key_package_generation_is_signed_and_stable() {
     let credential = must(generate_credential("alice"), "generate credential");
@@ -54,7 +47,7 @@ fn add_join_encrypt_and_decrypt_round_trip() {
         "add bob",
     );
 
-    let bob_state = must(
+    let joined = must(
         join_group(
             "group-1",
             &add_result.welcome,
@@ -65,12 +58,26 @@ fn add_join_encrypt_and_decrypt_round_trip() {
         ),
         "bob joins group",
     );
+    assert_eq!(joined.group_id, "group-1");
+    assert_eq!(joined.epoch, add_result.new_epoch);
+    assert_eq!(joined.ciphersuite, MLS_CIPHERSUITE_ID);
+    let mut joined_members: Vec<&str> = joined
+        .members
+        .iter()
+        .map(|member| member.user_id.as_str())
+        .collect();
+    joined_members.sort_unstable();
+    assert_eq!(joined_members, ["alice", "bob"]);
+    let bob_state = joined.state;
 
     let ciphertext = must(
         encrypt_message(&add_result.state, b"hello-from-alice"),
         "alice encrypts",
     );
-    let decrypted = must(decrypt_message(&bob_state, &ciphertext), "bob decrypts");
+    let decrypted = must(
+        decrypt_message(&bob_state, &ciphertext, false),
+        "bob decrypts",
+    );
 
     assert_eq!(decrypted.sender_id, "alice");
     assert_eq!(decrypted.plaintext, b"hello-from-alice".to_vec());
@@ -106,7 +113,7 @@ fn process_commit_and_remove_member_updates_state() {
         process_commit(&alice_state, &add_result.commit),
         "alice syncs add commit from previous epoch state",
     );
-    assert_eq!(alice_synced, add_result.state);
+    assert_eq!(alice_synced.state, add_result.state);
 
     let removed = must(remove_member(&add_result.state, 1), "remove bob");
     assert_eq!(removed.new_epoch, 2);
@@ -115,7 +122,7 @@ fn process_commit_and_remove_member_updates_state() {
         process_commit(&add_result.state, &removed.commit),
         "process remove commit from previous epoch state",
     );
-    assert_eq!(remove_applied, removed.state);
+    assert_eq!(remove_applied.state, removed.state);
 
     let bob_state = must(
         join_group(
@@ -127,7 +134,8 @@ fn process_commit_and_remove_member_updates_state() {
             &bob_credential.private_key,
         ),
         "bob joins group",
-    );
+    )
+    .state;
     let bob_remove = process_commit(&bob_state, &removed.commit);
     assert!(bob_remove.is_err());
 }
@@ -0,0 +1,115 @@
+use crate::{
+    commit::{advance_epoch_with_secret, sign_commit},
+    crypto::require_key_bytes,
+    error::MlsError,
+    model::{CommitOperationData, CommitOutput, GroupStateData, PskReferenceData},
+    protocol::{
+        decode_group_state, encode_group_state, ensure_non_empty, epoch_secret_for, serialize_json,
+    },
+};
+
+const MAX_EXTERNAL_PSKS: usize = 32;
+
+/// Returns the stored form of a PSK id; every lookup goes through it so
+/// surrounding whitespace never makes the same id miss.
+fn normalized_psk_id(psk_id: &str) -> Result<&str, MlsError> {
+    ensure_non_empty(psk_id, "psk_id")?;
+    Ok(psk_id.trim())
+}
+
+/// Stores an externally agreed PSK on the group state, replacing any with the same id.
+pub(crate) fn add_external_psk(
+    group_state_bytes: &[u8],
+    psk_id: &str,
+    secret: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    let psk_id = normalized_psk_id(psk_id)?.to_owned();
+    require_key_bytes::<32>(secret, "psk secret")?;
+    let mut state = decode_group_state(group_state_bytes)?;
+
+    if !state.external_psks.contains_key(&psk_id) && state.external_psks.len() >= MAX_EXTERNAL_PSKS
+    {
+        return Err(MlsError::InvalidInput(format!(
+            "external PSKs are limited to {MAX_EXTERNAL_PSKS} entries"
+        )));
+    }
+
+    state.external_psks.insert(psk_id, secret.to_vec());
+    encode_group_state(&state)
+}
+
+pub(crate) fn remove_external_psk(
+    group_state_bytes: &[u8],
+    psk_id: &str,
+) -> Result<Vec<u8>, MlsError> {
+    let psk_id = normalized_psk_id(psk_id)?;
+    let mut state = decode_group_state(group_state_bytes)?;
+    if state.external_psks.remove(psk_id).is_none() {
+        return Err(MlsError::NotFound(format!(
+            "external PSK {psk_id} not found"
+        )));
+    }
+    encode_group_state(&state)
+}
+
+/// Returns stored external PSK identifiers sorted; secrets are never exposed.
+pub(crate) fn list_external_psks(group_state_bytes: &[u8]) -> Result<Vec<String>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    Ok(state.external_psks.into_keys().collect())
+}
+
+pub(crate) fn resolve_psk(
+    state: &GroupStateData,
+    psk: &PskReferenceData,
+) -> Result<Vec<u8>, MlsError> {
+    match psk {
+        PskReferenceData::External { psk_id } => {
+            let psk_id = normalized_psk_id(psk_id)?;
+            state
+                .external_psks
+                .get(psk_id)
+                .cloned()
+                .ok_or_else(|| MlsError::NotFound(format!("external PSK {psk_id} not found")))
+        }
+        PskReferenceData::Resumption { epoch } => {
+            if *epoch > state.epoch {
+                return Err(MlsError::InvalidInput(format!(
+                    "resumption PSK epoch {epoch} is ahead of current epoch {}",
+                    state.epoch
+                )));
+            }
+            epoch_secret_for(&state.epoch_secrets, *epoch)
+        }
+    }
+}
+
+/// Commits a PSK so the next epoch secret depends on it.
+pub(crate) fn commit_psk(
+    group_state_bytes: &[u8],
+    psk: PskReferenceData,
+) -> Result<CommitOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    let secret = resolve_psk(&state, &psk)?;
+    // Commit the stored form of the id so receivers resolve the same entry.
+    let psk = match psk {
+        PskReferenceData::External { psk_id } => PskReferenceData::External {
+            psk_id: normalized_psk_id(&psk_id)?.to_owned(),
+        },
+        resumption => resumption,
+    };
+
+    let signed = sign_commit(&state, CommitOperationData::Psk { psk })?;
+    let new_epoch = signed.commit.new_epoch;
+    advance_epoch_with_secret(
+        &mut state,
+        new_epoch,
+        &signed.unsigned_commit_bytes,
+        &secret,
+    )?;
+
+    Ok(CommitOutput {
+        state: encode_group_state(&state)?,
+        commit: serialize_json(&signed.commit)?,
+        new_epoch,
+    })
+}
@@ -0,0 +1,94 @@
+use crate::{
+    commit::process_commit,
+    model::PskReferenceData,
+    protocol::{export_group_state, import_group_state},
+    psk::{add_external_psk, commit_psk, list_external_psks, remove_external_psk},
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+fn external(psk_id: &str) -> PskReferenceData {
+    PskReferenceData::External {
+        psk_id: psk_id.to_owned(),
+    }
+}
+
+#[test]
+fn external_psk_survives_export_and_drives_commit() {
+    let alice_state = create_single_member_state("group-psk", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-psk", "bob");
+
+    let alice_state = must(
+        add_external_psk(&added.state, "pairing", &[9; 32]),
+        "alice stores psk",
+    );
+    let bob_state = must(
+        add_external_psk(&bob_state, "pairing", &[9; 32]),
+        "bob stores psk",
+    );
+
+    let exported = must(export_group_state(&alice_state), "export");
+    let imported = must(import_group_state("group-psk", &exported), "import");
+    assert_eq!(
+        must(list_external_psks(&imported.state), "list psks"),
+        ["pairing"]
+    );
+
+    let committed = must(
+        commit_psk(&imported.state, external("pairing")),
+        "alice commits psk",
+    );
+    let processed = must(
+        process_commit(&bob_state, &committed.commit),
+        "bob processes psk commit",
+    );
+    assert_eq!(processed.new_epoch, committed.new_epoch);
+
+    let without_psk = must(remove_external_psk(&bob_state, "pairing"), "bob drops psk");
+    assert!(process_commit(&without_psk, &committed.commit).is_err());
+}
+
+#[test]
+fn resumption_psk_references_retained_epoch_secret() {
+    let alice_state = create_single_member_state("group-psk-2", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-psk-2", "bob");
+
+    let committed = must(
+        commit_psk(&added.state, PskReferenceData::Resumption { epoch: 1 }),
+        "commit resumption psk",
+    );
+    must(
+        process_commit(&bob_state, &committed.commit),
+        "bob processes resumption psk",
+    );
+
+    assert!(commit_psk(&added.state, PskReferenceData::Resumption { epoch: 5 }).is_err());
+    assert!(commit_psk(&added.state, external("missing")).is_err());
+    assert!(remove_external_psk(&added.state, "missing").is_err());
+}
+
+#[test]
+fn psk_ids_are_trimmed_for_every_lookup() {
+    let alice_state = create_single_member_state("group-psk-trim", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-psk-trim", "bob");
+    let alice_state = must(
+        add_external_psk(&added.state, " pairing ", &[7; 32]),
+        "alice stores padded psk",
+    );
+    let bob_state = must(
+        add_external_psk(&bob_state, "pairing", &[7; 32]),
+        "bob stores psk",
+    );
+
+    let committed = must(
+        commit_psk(&alice_state, external("pairing\n")),
+        "alice commits padded id",
+    );
+    must(
+        process_commit(&bob_state, &committed.commit),
+        "bob resolves trimmed id",
+    );
+
+    let removed = must(remove_external_psk(&alice_state, "pairing "), "remove");
+    assert!(must(list_external_psks(&removed), "list").is_empty());
+    assert!(commit_psk(&alice_state, external("  ")).is_err());
+}
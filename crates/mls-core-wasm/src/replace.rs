@@ -0,0 +1,90 @@
+use crate::{
+    commit::apply_operation,
+    error::MlsError,
+    key_package::{decode_key_package, ensure_known_extensions, key_package_ref},
+    model::{AddMemberOutput, CommitOperationData, GroupMemberData},
+    operations::{group_info_bytes, key_package_reused},
+    path_update::commit_with_path,
+    protocol::{decode_group_state, encode_group_state, self_leaf_index, serialize_json},
+    welcome::{WelcomeRecipient, build_welcome},
+};
+
+/// Removes `leaf_index` and adds `key_package` in one commit so the member is never out of the
+/// group, e.g. after a device compromise.
+///
+/// The commit always carries an update path so the replaced leaf cannot derive the new epoch.
+/// Unless `allow_identity_change` is set, the key package must belong to the removed member.
+pub(crate) fn replace_member(
+    group_state_bytes: &[u8],
+    leaf_index: u32,
+    key_package_bytes: &[u8],
+    allow_identity_change: bool,
+) -> Result<AddMemberOutput, MlsError> {
+    let mut state = decode_group_state(group_state_bytes)?;
+    let self_leaf = self_leaf_index(&state)?;
+    if leaf_index == self_leaf {
+        return Err(MlsError::InvalidInput(
+            "cannot replace local member; use a self update instead".to_owned(),
+        ));
+    }
+
+    let replaced = state
+        .members
+        .iter()
+        .find(|member| member.leaf_index == leaf_index)
+        .ok_or_else(|| MlsError::NotFound(format!("leaf index {leaf_index} not found in group")))?;
+
+    let key_package = decode_key_package(key_package_bytes)?;
+    ensure_known_extensions(&state.policy, &key_package)?;
+    if !allow_identity_change && key_package.user_id != replaced.user_id {
+        return Err(MlsError::InvalidInput(format!(
+            "replacement key package belongs to {}, not removed member {}",
+            key_package.user_id, replaced.user_id
+        )));
+    }
+
+    let key_package_ref = key_package_ref(key_package_bytes);
+    if key_package_reused(&state, &key_package, &key_package_ref, &[], &[]) {
+        return Err(MlsError::KeyPackageReused(format!(
+            "key package {key_package_ref} was already used"
+        )));
+    }
+
+    let next_leaf_index = state
+        .members
+        .iter()
+        .map(|member| member.leaf_index)
+        .max()
+        .map_or(0, |leaf| leaf.saturating_add(1));
+    let recipient = WelcomeRecipient {
+        key_package_ref: key_package_ref.clone(),
+        hpke_public_key: key_package.hpke_public_key.clone(),
+    };
+    let operation = CommitOperationData::Replace {
+        leaf_index,
+        member: GroupMemberData {
+            user_id: key_package.user_id,
+            leaf_index: next_leaf_index,
+            signing_public_key: key_package.signing_public_key,
+            hpke_public_key: key_package.hpke_public_key,
+            key_package_ref: key_package_ref.clone(),
+            leaf_epoch: state.epoch.saturating_add(1),
+        },
+        key_package_refs: if key_package.last_resort {
+            Vec::new()
+        } else {
+            vec![key_package_ref]
+        },
+    };
+
+    apply_operation(&mut state, operation.clone())?;
+    let signed = commit_with_path(&mut state, operation)?;
+
+    Ok(AddMemberOutput {
+        welcome: build_welcome(&state, self_leaf, &[recipient])?,
+        group_info: group_info_bytes(&state)?,
+        state: encode_group_state(&state)?,
+        commit: serialize_json(&signed.commit)?,
+        new_epoch: signed.commit.new_epoch,
+    })
+}
@@ -0,0 +1,87 @@
+use crate::{
+    commit::process_commit,
+    error::MlsError,
+    key_package::generate_key_package,
+    messaging::{decrypt_message, encrypt_message},
+    operations::join_group,
+    protocol::generate_credential,
+    replace::replace_member,
+    test_support::{add_and_join, create_single_member_state, must},
+};
+
+#[test]
+fn replaced_member_rejoins_while_old_leaf_is_locked_out() {
+    let alice_state = create_single_member_state("group-replace", "alice");
+    let (added, old_bob_state) = add_and_join(&alice_state, "group-replace", "bob");
+    let (added_carol, carol_state) = add_and_join(&added.state, "group-replace", "carol");
+
+    let credential = must(generate_credential("bob"), "new bob credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "new bob key package",
+    );
+    let replaced = must(
+        replace_member(&added_carol.state, 1, &key_package.key_package, false),
+        "replace bob",
+    );
+
+    let carol_state = must(
+        process_commit(&carol_state, &replaced.commit),
+        "carol processes replacement",
+    )
+    .state;
+    let new_bob_state = must(
+        join_group(
+            "group-replace",
+            &replaced.welcome,
+            &key_package.key_package_ref,
+            &key_package.private_key,
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "new bob joins",
+    )
+    .state;
+
+    let ciphertext = must(
+        encrypt_message(&carol_state, b"welcome back"),
+        "carol encrypts",
+    );
+    let decrypted = must(
+        decrypt_message(&new_bob_state, &ciphertext, false),
+        "new bob decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"welcome back");
+
+    let old_bob_state = must(
+        process_commit(&old_bob_state, &added_carol.commit),
+        "old bob processes carol add",
+    )
+    .state;
+    assert!(process_commit(&old_bob_state, &replaced.commit).is_err());
+}
+
+#[test]
+fn replacement_must_keep_identity_unless_overridden() {
+    let alice_state = create_single_member_state("group-replace-2", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-replace-2", "bob");
+
+    let credential = must(generate_credential("dave"), "dave credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "dave key package",
+    );
+    assert!(matches!(
+        replace_member(&added.state, 1, &key_package.key_package, false),
+        Err(MlsError::InvalidInput(_))
+    ));
+
+    let replaced = must(
+        replace_member(&added.state, 1, &key_package.key_package, true),
+        "replace bob with dave",
+    );
+    assert!(matches!(
+        replace_member(&replaced.state, 2, &key_package.key_package, false),
+        Err(MlsError::KeyPackageReused(_))
+    ));
+}
@@ -0,0 +1,19 @@
+use crate::{
+    error::MlsError,
+    protocol::{encode_group_state, new_group_state},
+};
+
+/// Creates a one-member group that never sends welcomes and can decrypt its own messages.
+pub(crate) fn create_solo_group(
+    group_id: &str,
+    credential_bundle_bytes: &[u8],
+    credential_private_key_bytes: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    let mut state = new_group_state(
+        group_id,
+        credential_bundle_bytes,
+        credential_private_key_bytes,
+    )?;
+    state.solo = true;
+    encode_group_state(&state)
+}
@@ -0,0 +1,52 @@
+use crate::{
+    key_package::generate_key_package,
+    messaging::{decrypt_message, encrypt_message},
+    model::DecryptStatus,
+    operations::add_member,
+    protocol::{export_group_state, generate_credential, import_group_state},
+    solo::create_solo_group,
+    test_support::must,
+};
+
+#[test]
+fn solo_group_decrypts_its_own_notes_after_reload() {
+    let credential = must(generate_credential("alice"), "generate credential");
+    let state = must(
+        create_solo_group(
+            "notes",
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "create solo group",
+    );
+
+    let ciphertext = must(
+        encrypt_message(&state, b"remember the milk"),
+        "encrypt note",
+    );
+    let exported = must(export_group_state(&state), "export");
+    let imported = must(import_group_state("notes", &exported), "import");
+
+    let decrypted = must(
+        decrypt_message(&imported.state, &ciphertext, false),
+        "decrypt note",
+    );
+    assert_eq!(decrypted.status, DecryptStatus::Decrypted);
+    assert_eq!(decrypted.plaintext, b"remember the milk");
+}
+
+#[test]
+fn solo_group_rejects_new_members() {
+    let alice = must(generate_credential("alice"), "alice credential");
+    let state = must(
+        create_solo_group("notes-2", &alice.credential_bundle, &alice.private_key),
+        "create solo group",
+    );
+    let bob = must(generate_credential("bob"), "bob credential");
+    let bob_key_package = must(
+        generate_key_package(&bob.credential_bundle, &bob.private_key),
+        "bob key package",
+    );
+
+    assert!(add_member(&state, &bob_key_package.key_package).is_err());
+}
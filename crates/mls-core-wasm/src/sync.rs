@@ -0,0 +1,113 @@
+use crate::{
+    crypto::sha256,
+    error::MlsError,
+    identity::user_id_identity,
+    model::{GroupStateReportOutput, PublicGroupStateOutput},
+    protocol::{decode_group_state, member_metadata, serialize_json},
+};
+
+/// Returns `remote_epoch - local_epoch`, negative when the local state is ahead.
+pub(crate) fn epochs_behind(group_state_bytes: &[u8], remote_epoch: u64) -> Result<i64, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let delta = i128::from(remote_epoch) - i128::from(state.epoch);
+    i64::try_from(delta)
+        .map_err(|_| MlsError::InvalidInput(format!("epoch distance {delta} is out of range")))
+}
+
+/// Digest over epoch, member tree and confirmed transcript; equal only for synced members.
+pub(crate) fn sync_digest(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let tree_hash = sha256(&serialize_json(&state.members)?);
+
+    let mut input = Vec::with_capacity(128);
+    input.extend_from_slice(b"tearleads-mls/sync-digest/v1:");
+    input.extend_from_slice(state.group_id.as_bytes());
+    input.extend_from_slice(&state.epoch.to_be_bytes());
+    input.extend_from_slice(&tree_hash);
+    input.extend_from_slice(&state.confirmed_transcript_hash);
+
+    Ok(sha256(&input).to_vec())
+}
+
+/// Digest over the sorted member identities only, so it ignores leaf order and key material.
+///
+/// Clients with the same membership derive the same value; unlike [`sync_digest`] it does not
+/// change on key rotation or epoch advance.
+pub(crate) fn members_digest(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let mut identities = state
+        .members
+        .iter()
+        .map(|member| user_id_identity(&member.user_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    identities.sort_unstable();
+
+    let mut input = b"tearleads-mls/members-digest/v1:".to_vec();
+    for identity in identities {
+        // Length prefixes keep adjacent identities from running together.
+        input.extend_from_slice(&(identity.len() as u64).to_be_bytes());
+        input.extend_from_slice(&identity);
+    }
+
+    Ok(sha256(&input).to_vec())
+}
+
+/// Returns what a directory may publish about a group: ids and membership, no keys.
+///
+/// Signing and HPKE public keys are left out too, so the snapshot cannot be used to
+/// address welcomes or verify messages on anyone's behalf.
+pub(crate) fn public_group_state(
+    group_state_bytes: &[u8],
+) -> Result<PublicGroupStateOutput, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let member_count = u32::try_from(state.members.len())
+        .map_err(|_| MlsError::InvalidState("member count is out of range".to_owned()))?;
+
+    Ok(PublicGroupStateOutput {
+        members: member_metadata(&state.members)?,
+        group_id: state.group_id,
+        epoch: state.epoch,
+        ciphersuite: state.ciphersuite,
+        member_count,
+    })
+}
+
+/// Reports whether `group_state_bytes` still loads as `group_id`, without failing, so one
+/// corrupted group can be set aside.
+///
+/// These are the checks every load already runs: the state decodes at the current version and
+/// ciphersuite, the local signing and HPKE keys match our own member entry when present, members
+/// are unique and epoch secrets are present; then the state's group id must match. A state saved
+/// before leaf keys existed is valid but flagged `upgrade_required`. The member tree hash is not
+/// recomputed, so a tree that decodes cleanly but diverged from other members passes; compare
+/// `sync_digest` across members for that.
+pub(crate) fn validate_group_state(
+    group_id: &str,
+    group_state_bytes: &[u8],
+) -> GroupStateReportOutput {
+    let failure = match decode_group_state(group_state_bytes) {
+        Ok(state) if state.group_id != group_id => Err((
+            state.epoch,
+            format!("state belongs to group {}, not {group_id}", state.group_id),
+        )),
+        Ok(state) => Ok(state),
+        Err(error) => Err((0, error.to_string())),
+    };
+
+    match failure {
+        Ok(state) => GroupStateReportOutput {
+            valid: true,
+            epoch: state.epoch,
+            reason: None,
+            resync_required: false,
+            upgrade_required: state.self_hpke_private_key.is_empty(),
+        },
+        Err((epoch, reason)) => GroupStateReportOutput {
+            valid: false,
+            epoch,
+            reason: Some(reason),
+            resync_required: true,
+            upgrade_required: false,
+        },
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{
+    commit::process_commit,
+    error::MlsError,
+    group_context::update_group_context,
+    messaging::{decrypt_message, encrypt_message},
+    model::{GroupContextData, MLS_CIPHERSUITE_ID, MLS_STATE_VERSION},
+    path_update::self_update,
+    protocol::{decode_group_state, import_group_state},
+    sync::{epochs_behind, members_digest, public_group_state, sync_digest, validate_group_state},
+    test_support::{add_and_join, create_single_member_state, legacy_payload, must},
+};
+
+#[test]
+fn epochs_behind_reports_signed_distance_to_remote_epoch() {
+    let alice_state = create_single_member_state("group-sync", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-sync", "bob");
+
+    assert_eq!(must(epochs_behind(&added.state, 1), "in sync"), 0);
+    assert_eq!(must(epochs_behind(&added.state, 4), "behind"), 3);
+    assert_eq!(must(epochs_behind(&added.state, 0), "ahead"), -1);
+    assert!(epochs_behind(&alice_state, u64::MAX).is_err());
+}
+
+#[test]
+fn sync_digest_matches_only_for_identical_group_views() {
+    let alice_state = create_single_member_state("group-digest", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-digest", "bob");
+    assert_eq!(
+        must(sync_digest(&added.state), "alice digest"),
+        must(sync_digest(&bob_state), "bob digest")
+    );
+
+    let alice_rename = must(
+        update_group_context(&added.state, named("Alice's name")),
+        "alice renames",
+    );
+    let bob_synced = must(
+        process_commit(&bob_state, &alice_rename.commit),
+        "bob follows rename",
+    );
+    assert_eq!(
+        must(sync_digest(&alice_rename.state), "alice digest"),
+        must(sync_digest(&bob_synced.state), "bob digest")
+    );
+
+    let bob_diverged = must(
+        update_group_context(&bob_state, named("Bob's name")),
+        "bob renames concurrently",
+    );
+    assert_eq!(bob_diverged.new_epoch, alice_rename.new_epoch);
+    assert_ne!(
+        must(sync_digest(&alice_rename.state), "alice digest"),
+        must(sync_digest(&bob_diverged.state), "diverged digest")
+    );
+}
+
+fn named(name: &str) -> GroupContextData {
+    GroupContextData {
+        name: Some(name.to_owned()),
+        external_senders: Vec::new(),
+        ratchet_tree_out_of_band: false,
+    }
+}
+
+#[test]
+fn members_digest_depends_only_on_membership() {
+    let alice_state = create_single_member_state("group-members-digest", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-members-digest", "bob");
+
+    let alice_digest = must(members_digest(&added.state), "alice digest");
+    assert_eq!(alice_digest, must(members_digest(&bob_state), "bob digest"));
+    assert_ne!(
+        alice_digest,
+        must(members_digest(&alice_state), "solo digest")
+    );
+
+    let update = must(self_update(&bob_state), "bob self-updates");
+    assert_eq!(
+        alice_digest,
+        must(members_digest(&update.state), "digest after update")
+    );
+
+    let other_state = create_single_member_state("group-members-digest-2", "bob");
+    let (other_added, _alice_state) = add_and_join(&other_state, "group-members-digest-2", "alice");
+    assert_eq!(
+        alice_digest,
+        must(
+            members_digest(&other_added.state),
+            "digest with swapped leaves"
+        )
+    );
+}
+
+#[test]
+fn validate_group_state_flags_corrupted_states_for_resync() {
+    let alice_state = create_single_member_state("group-integrity", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-integrity", "bob");
+
+    let intact = validate_group_state("group-integrity", &added.state);
+    assert!(intact.valid);
+    assert_eq!(intact.epoch, 1);
+    assert_eq!(intact.reason, None);
+    assert!(!intact.resync_required);
+
+    let foreign = validate_group_state("group-other", &added.state);
+    assert!(!foreign.valid);
+    assert_eq!(foreign.epoch, 1);
+    assert!(foreign.resync_required);
+
+    let mut state: serde_json::Value = must(serde_json::from_slice(&added.state), "parse state");
+    state["epoch"] = serde_json::json!(0);
+    let rolled_back = must(serde_json::to_vec(&state), "encode state");
+    let future_secret = validate_group_state("group-integrity", &rolled_back);
+    assert!(!future_secret.valid);
+    assert!(
+        future_secret
+            .reason
+            .is_some_and(|reason| reason.contains("ahead of current epoch"))
+    );
+
+    let garbage = validate_group_state("group-integrity", b"not a group state");
+    assert!(!garbage.valid);
+    assert_eq!(garbage.epoch, 0);
+    assert!(garbage.resync_required);
+}
+
+#[test]
+fn public_group_state_lists_membership_without_key_material() {
+    let alice_state = create_single_member_state("group-directory", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-directory", "bob");
+
+    let snapshot = must(public_group_state(&added.state), "public snapshot");
+    assert_eq!(snapshot.group_id, "group-directory");
+    assert_eq!(snapshot.epoch, 1);
+    assert_eq!(snapshot.ciphersuite, MLS_CIPHERSUITE_ID);
+    assert_eq!(snapshot.member_count, 2);
+    let members: Vec<(&str, u32)> = snapshot
+        .members
+        .iter()
+        .map(|member| (member.user_id.as_str(), member.leaf_index))
+        .collect();
+    assert_eq!(members, [("alice", 0), ("bob", 1)]);
+
+    let encoded = must(serde_json::to_vec(&snapshot), "encode snapshot");
+    let state = must(decode_group_state(&added.state), "decode state");
+    let secrets = state
+        .epoch_secrets
+        .iter()
+        .map(|entry| entry.secret.clone())
+        .chain([
+            state.self_signing_private_key.clone(),
+            state.self_hpke_private_key.clone(),
+        ])
+        .chain(state.members.iter().flat_map(|member| {
+            [
+                member.signing_public_key.clone(),
+                member.hpke_public_key.clone(),
+            ]
+        }));
+    for secret in secrets {
+        let needle = must(serde_json::to_vec(&secret), "encode key");
+        assert!(
+            !encoded
+                .windows(needle.len())
+                .any(|window| window == needle.as_slice())
+        );
+    }
+}
+
+/// Reduces `state_bytes` to the fields the first released state schema had.
+fn baseline_state(state_bytes: &[u8]) -> Vec<u8> {
+    let stripped = legacy_payload(
+        state_bytes,
+        MLS_STATE_VERSION,
+        &[
+            "self_hpke_private_key",
+            "last_update_epoch",
+            "group_context",
+            "confirmed_transcript_hash",
+            "local_metadata",
+            "policy",
+            "solo",
+            "external_psks",
+            "consumed_key_package_refs",
+            "pending_proposals",
+        ],
+    );
+    let mut payload: serde_json::Value = must(serde_json::from_slice(&stripped), "decode state");
+    if let Some(members) = payload["members"].as_array_mut() {
+        for member in members
+            .iter_mut()
+            .filter_map(serde_json::Value::as_object_mut)
+        {
+            member.remove("key_package_ref");
+            member.remove("leaf_epoch");
+        }
+    }
+    must(serde_json::to_vec(&payload), "encode baseline state")
+}
+
+#[test]
+fn states_saved_before_update_paths_load_and_upgrade_by_self_update() {
+    let alice_state = create_single_member_state("group-legacy", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-legacy", "bob");
+    let legacy = baseline_state(&bob_state);
+
+    let report = validate_group_state("group-legacy", &legacy);
+    assert!(report.valid);
+    assert!(!report.resync_required);
+    assert!(report.upgrade_required);
+    must(
+        import_group_state("group-legacy", &legacy),
+        "import legacy state",
+    );
+    let ciphertext = must(encrypt_message(&added.state, b"hi"), "alice encrypts");
+    let decrypted = must(
+        decrypt_message(&legacy, &ciphertext, false),
+        "legacy state decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"hi");
+
+    let alice_update = must(self_update(&added.state), "alice updates");
+    let Err(error) = process_commit(&legacy, &alice_update.commit) else {
+        panic!("expected the legacy state to refuse an update path");
+    };
+    assert!(matches!(error.kind(), MlsError::LegacyLeafKey(_)));
+
+    let upgraded = must(self_update(&legacy), "bob upgrades");
+    assert!(!validate_group_state("group-legacy", &upgraded.state).upgrade_required);
+    let alice_state = must(
+        process_commit(&added.state, &upgraded.commit),
+        "alice processes bob's upgrade",
+    )
+    .state;
+    let alice_update = must(self_update(&alice_state), "alice updates again");
+    must(
+        process_commit(&upgraded.state, &alice_update.commit),
+        "upgraded bob opens the update path",
+    );
+}
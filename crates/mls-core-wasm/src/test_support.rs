@@ -0,0 +1,69 @@
+use crate::{
+    key_package::generate_key_package,
+    model::AddMemberOutput,
+    operations::{add_member, join_group},
+    protocol::{create_group, generate_credential},
+};
+
+pub(crate) fn must<T, E: core::fmt::Display>(result: Result<T, E>, context: &str) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("{context}: {error}"),
+    }
+}
+
+/// Creates a fresh group owned by `user_id` with no other members; unlike a solo group,
+/// it accepts adds.
+pub(crate) fn create_single_member_state(group_id: &str, user_id: &str) -> Vec<u8> {
+    let credential = must(generate_credential(user_id), "generate credential");
+    must(
+        create_group(
+            group_id,
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "create group",
+    )
+}
+
+/// Adds `user_id` through `inviter_state` and returns the add output plus the joiner state.
+pub(crate) fn add_and_join(
+    inviter_state: &[u8],
+    group_id: &str,
+    user_id: &str,
+) -> (AddMemberOutput, Vec<u8>) {
+    let credential = must(generate_credential(user_id), "generate joiner credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate joiner key package",
+    );
+    let added = must(
+        add_member(inviter_state, &key_package.key_package),
+        "add joiner",
+    );
+    let joined = must(
+        join_group(
+            group_id,
+            &added.welcome,
+            &key_package.key_package_ref,
+            &key_package.private_key,
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "joiner joins group",
+    );
+
+    (added, joined.state)
+}
+
+/// Re-encodes a JSON payload as an older schema `version` without `removed` fields.
+pub(crate) fn legacy_payload(bytes: &[u8], version: u8, removed: &[&str]) -> Vec<u8> {
+    let mut payload: serde_json::Value = must(serde_json::from_slice(bytes), "decode payload");
+    if let Some(fields) = payload.as_object_mut() {
+        for field in removed {
+            fields.remove(*field);
+        }
+        fields.insert("version".to_owned(), serde_json::Value::from(version));
+    }
+    must(serde_json::to_vec(&payload), "encode legacy payload")
+}
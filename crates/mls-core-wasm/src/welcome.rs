@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    crypto::{
+        decrypt_chacha20, derive_welcome_group_key, derive_welcome_key, encrypt_chacha20,
+        generate_x25519_key_pair, random_bytes, random_nonce, sha256, sign_bytes, verify_signature,
+        x25519_shared_secret,
+    },
+    error::MlsError,
+    model::{
+        GroupMemberData, GroupStateData, MLS_CIPHERSUITE_ID, MLS_WELCOME_VERSION,
+        UnsignedWelcomeData, WelcomeData, WelcomeEncryptedData, WelcomeInfoOutput,
+        WelcomeJoinStatus, WelcomeSecretData,
+    },
+    policy::self_signing_key,
+    protocol::{
+        current_epoch_secret, decode_group_state, deserialize_json, ensure_schema_version,
+        metadata_bytes, self_leaf_index, serialize_json,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct WelcomeAeadMetadata {
+    group_id: String,
+    epoch: u64,
+    key_package_ref: String,
+    inviter_leaf_index: u32,
+    signer_leaf_index: u32,
+    ephemeral_public_key: Vec<u8>,
+}
+
+/// Key package a welcome is addressed to.
+pub(crate) struct WelcomeRecipient {
+    pub(crate) key_package_ref: String,
+    pub(crate) hpke_public_key: Vec<u8>,
+}
+
+fn secret_aad(
+    welcome: &UnsignedWelcomeData,
+    secret: &WelcomeSecretData,
+) -> Result<Vec<u8>, MlsError> {
+    serialize_json(&WelcomeAeadMetadata {
+        group_id: welcome.group_id.clone(),
+        epoch: welcome.epoch,
+        key_package_ref: secret.key_package_ref.clone(),
+        inviter_leaf_index: welcome.inviter_leaf_index,
+        signer_leaf_index: welcome.signer_leaf_index,
+        ephemeral_public_key: secret.ephemeral_public_key.clone(),
+    })
+}
+
+/// Decodes a welcome after checking its schema version.
+fn decode_welcome(welcome_bytes: &[u8]) -> Result<WelcomeData, MlsError> {
+    ensure_schema_version(welcome_bytes, "welcome", MLS_WELCOME_VERSION)?;
+    deserialize_json(welcome_bytes, "welcome")
+}
+
+fn unsigned_welcome(welcome: &WelcomeData) -> UnsignedWelcomeData {
+    UnsignedWelcomeData {
+        version: welcome.version,
+        group_id: welcome.group_id.clone(),
+        epoch: welcome.epoch,
+        ciphersuite: welcome.ciphersuite,
+        inviter_leaf_index: welcome.inviter_leaf_index,
+        signer_leaf_index: welcome.signer_leaf_index,
+        secrets: welcome.secrets.clone(),
+        nonce: welcome.nonce.clone(),
+        ciphertext: welcome.ciphertext.clone(),
+        ratchet_tree_omitted: welcome.ratchet_tree_omitted,
+    }
+}
+
+fn ratchet_tree_hash(members: &[GroupMemberData]) -> Result<Vec<u8>, MlsError> {
+    Ok(sha256(&serialize_json(&members)?).to_vec())
+}
+
+/// Builds one signed welcome for `state`'s current epoch that every recipient can open.
+pub(crate) fn build_welcome(
+    state: &GroupStateData,
+    signer_leaf_index: u32,
+    recipients: &[WelcomeRecipient],
+) -> Result<Vec<u8>, MlsError> {
+    let joiner_secret = random_bytes::<32>()?;
+    let ratchet_tree_omitted = state.group_context.ratchet_tree_out_of_band;
+    let payload = WelcomeEncryptedData {
+        group_id: state.group_id.clone(),
+        epoch: state.epoch,
+        epoch_secret: current_epoch_secret(state)?,
+        members: if ratchet_tree_omitted {
+            Vec::new()
+        } else {
+            state.members.clone()
+        },
+        ratchet_tree_hash: ratchet_tree_hash(&state.members)?,
+        ciphersuite: state.ciphersuite,
+        group_context: state.group_context.clone(),
+        confirmed_transcript_hash: state.confirmed_transcript_hash.clone(),
+    };
+
+    let group_key = derive_welcome_group_key(&joiner_secret, &state.group_id, state.epoch)?;
+    let nonce = random_nonce()?.to_vec();
+    let ciphertext = encrypt_chacha20(
+        &group_key,
+        &nonce,
+        &serialize_json(&payload)?,
+        &metadata_bytes(&state.group_id, state.epoch, signer_leaf_index)?,
+    )?;
+
+    let mut unsigned = UnsignedWelcomeData {
+        version: MLS_WELCOME_VERSION,
+        group_id: state.group_id.clone(),
+        epoch: state.epoch,
+        ciphersuite: state.ciphersuite,
+        inviter_leaf_index: signer_leaf_index,
+        signer_leaf_index,
+        secrets: Vec::with_capacity(recipients.len()),
+        nonce,
+        ciphertext,
+        ratchet_tree_omitted,
+    };
+
+    for recipient in recipients {
+        let (ephemeral_private_key, ephemeral_public_key) = generate_x25519_key_pair()?;
+        let shared_secret =
+            x25519_shared_secret(&ephemeral_private_key, &recipient.hpke_public_key)?;
+        let welcome_key = derive_welcome_key(
+            &shared_secret,
+            &state.group_id,
+            state.epoch,
+            &recipient.key_package_ref,
+        )?;
+
+        let mut secret = WelcomeSecretData {
+            key_package_ref: recipient.key_package_ref.clone(),
+            ephemeral_public_key,
+            nonce: random_nonce()?.to_vec(),
+            ciphertext: Vec::new(),
+        };
+        let aad = secret_aad(&unsigned, &secret)?;
+        secret.ciphertext = encrypt_chacha20(&welcome_key, &secret.nonce, &joiner_secret, &aad)?;
+        unsigned.secrets.push(secret);
+    }
+
+    let signature = sign_bytes(self_signing_key(state)?, &serialize_json(&unsigned)?)?;
+    serialize_json(&WelcomeData {
+        version: unsigned.version,
+        group_id: unsigned.group_id,
+        epoch: unsigned.epoch,
+        ciphersuite: unsigned.ciphersuite,
+        inviter_leaf_index: unsigned.inviter_leaf_index,
+        signer_leaf_index: unsigned.signer_leaf_index,
+        secrets: unsigned.secrets,
+        nonce: unsigned.nonce,
+        ciphertext: unsigned.ciphertext,
+        ratchet_tree_omitted: unsigned.ratchet_tree_omitted,
+        signature,
+        ratchet_tree: Vec::new(),
+    })
+}
+
+/// Serializes the member tree that joiners need when welcomes omit it.
+pub(crate) fn export_ratchet_tree(group_state_bytes: &[u8]) -> Result<Vec<u8>, MlsError> {
+    serialize_json(&decode_group_state(group_state_bytes)?.members)
+}
+
+/// Attaches an out-of-band member tree to a welcome before joining from it.
+pub(crate) fn attach_ratchet_tree(
+    welcome_bytes: &[u8],
+    ratchet_tree_bytes: &[u8],
+) -> Result<Vec<u8>, MlsError> {
+    let mut welcome = decode_welcome(welcome_bytes)?;
+    welcome.ratchet_tree = deserialize_json(ratchet_tree_bytes, "ratchet tree")?;
+    if welcome.ratchet_tree.is_empty() {
+        return Err(MlsError::InvalidInput("ratchet tree is empty".to_owned()));
+    }
+    serialize_json(&welcome)
+}
+
+/// Rebuilds a welcome at the current epoch for a member whose original welcome was lost.
+///
+/// Only leaves still holding their key package's init key can be re-welcomed; once a
+/// leaf rotates its key through an update path it has joined and must be re-added.
+pub(crate) fn resend_welcome(
+    group_state_bytes: &[u8],
+    leaf_index: u32,
+) -> Result<Vec<u8>, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let self_leaf = self_leaf_index(&state)?;
+    let member = state
+        .members
+        .iter()
+        .find(|member| member.leaf_index == leaf_index)
+        .ok_or_else(|| MlsError::NotFound(format!("leaf index {leaf_index} not found in group")))?;
+    if member.key_package_ref.is_empty() {
+        return Err(MlsError::InvalidInput(format!(
+            "leaf {leaf_index} no longer holds its key package init key"
+        )));
+    }
+
+    let recipient = WelcomeRecipient {
+        key_package_ref: member.key_package_ref.clone(),
+        hpke_public_key: member.hpke_public_key.clone(),
+    };
+    build_welcome(&state, self_leaf, &[recipient])
+}
+
+/// Returns the first of `stored_refs` that `welcome_bytes` carries a joiner secret for.
+pub(crate) fn match_welcome_key_package(
+    welcome_bytes: &[u8],
+    stored_refs: &[String],
+) -> Result<Option<String>, MlsError> {
+    let welcome = decode_welcome(welcome_bytes)?;
+    Ok(stored_refs
+        .iter()
+        .find(|stored| {
+            welcome
+                .secrets
+                .iter()
+                .any(|secret| &secret.key_package_ref == *stored)
+        })
+        .cloned())
+}
+
+/// Reads a welcome's public header without opening it.
+///
+/// Needs no key package private key, so delivery services can route and
+/// deduplicate welcomes by group and epoch without being members.
+pub(crate) fn inspect_welcome(welcome_bytes: &[u8]) -> Result<WelcomeInfoOutput, MlsError> {
+    let welcome = decode_welcome(welcome_bytes)?;
+
+    Ok(WelcomeInfoOutput {
+        group_id: welcome.group_id,
+        epoch: welcome.epoch,
+        ciphersuite: welcome.ciphersuite,
+        key_package_refs: welcome
+            .secrets
+            .into_iter()
+            .map(|secret| secret.key_package_ref)
+            .collect(),
+        has_ratchet_tree: !welcome.ratchet_tree_omitted || !welcome.ratchet_tree.is_empty(),
+        encrypted_group_info_present: !welcome.ciphertext.is_empty(),
+    })
+}
+
+/// Compares a welcome with the state already held for its group so replayed
+/// welcomes cannot roll the group back to an older epoch.
+pub(crate) fn welcome_join_status(
+    group_state_bytes: &[u8],
+    welcome_bytes: &[u8],
+) -> Result<WelcomeJoinStatus, MlsError> {
+    let state = decode_group_state(group_state_bytes)?;
+    let welcome = decode_welcome(welcome_bytes)?;
+    if welcome.group_id != state.group_id {
+        return Err(MlsError::InvalidInput(format!(
+            "welcome group mismatch: expected {}, got {}",
+            state.group_id, welcome.group_id
+        )));
+    }
+
+    Ok(if welcome.epoch > state.epoch {
+        WelcomeJoinStatus::Join
+    } else {
+        WelcomeJoinStatus::AlreadyJoined
+    })
+}
+
+/// Opens the recipient's entry of a welcome and returns the authenticated group payload.
+pub(crate) fn open_welcome(
+    welcome_bytes: &[u8],
+    group_id: &str,
+    key_package_ref: &str,
+    key_package_private_key: &[u8],
+) -> Result<WelcomeEncryptedData, MlsError> {
+    let welcome = decode_welcome(welcome_bytes)?;
+
+    if welcome.group_id != group_id {
+        return Err(MlsError::InvalidInput(format!(
+            "welcome group mismatch: expected {group_id}, got {}",
+            welcome.group_id
+        )));
+    }
+
+    if welcome.ciphersuite != MLS_CIPHERSUITE_ID {
+        return Err(MlsError::InvalidInput(format!(
+            "unsupported welcome ciphersuite {}",
+            welcome.ciphersuite
+        )));
+    }
+
+    let secret = welcome
+        .secrets
+        .iter()
+        .find(|secret| secret.key_package_ref == key_package_ref)
+        .ok_or_else(|| {
+            MlsError::InvalidInput("welcome key package reference mismatch".to_owned())
+        })?;
+
+    let unsigned = unsigned_welcome(&welcome);
+    let shared_secret =
+        x25519_shared_secret(key_package_private_key, &secret.ephemeral_public_key)?;
+    let welcome_key = derive_welcome_key(
+        &shared_secret,
+        &welcome.group_id,
+        welcome.epoch,
+        &secret.key_package_ref,
+    )?;
+    let joiner_secret = decrypt_chacha20(
+        &welcome_key,
+        &secret.nonce,
+        &secret.ciphertext,
+        &secret_aad(&unsigned, secret)?,
+    )?;
+
+    let group_key = derive_welcome_group_key(&joiner_secret, &welcome.group_id, welcome.epoch)?;
+    let decrypted = decrypt_chacha20(
+        &group_key,
+        &welcome.nonce,
+        &welcome.ciphertext,
+        &metadata_bytes(&welcome.group_id, welcome.epoch, welcome.signer_leaf_index)?,
+    )?;
+    let mut payload: WelcomeEncryptedData =
+        deserialize_json(&decrypted, "welcome encrypted payload")?;
+    if welcome.ratchet_tree_omitted {
+        if welcome.ratchet_tree.is_empty() {
+            return Err(MlsError::InvalidInput(
+                "welcome omits the ratchet tree; supply it out of band".to_owned(),
+            ));
+        }
+        payload.members = welcome.ratchet_tree.clone();
+    }
+    if ratchet_tree_hash(&payload.members)? != payload.ratchet_tree_hash {
+        return Err(MlsError::InvalidInput(
+            "ratchet tree does not match the welcome".to_owned(),
+        ));
+    }
+
+    if payload.group_id != welcome.group_id {
+        return Err(MlsError::InvalidInput(
+            "welcome encrypted payload group mismatch".to_owned(),
+        ));
+    }
+
+    if payload.epoch != welcome.epoch {
+        return Err(MlsError::InvalidInput(
+            "welcome encrypted payload epoch mismatch".to_owned(),
+        ));
+    }
+
+    if payload.ciphersuite != welcome.ciphersuite {
+        return Err(MlsError::InvalidInput(
+            "welcome encrypted payload ciphersuite mismatch".to_owned(),
+        ));
+    }
+
+    let signer = payload
+        .members
+        .iter()
+        .find(|member| member.leaf_index == welcome.signer_leaf_index)
+        .ok_or_else(|| MlsError::NotFound("welcome signer leaf index not found".to_owned()))?;
+
+    verify_signature(
+        &signer.signing_public_key,
+        &serialize_json(&unsigned)?,
+        &welcome.signature,
+    )?;
+
+    Ok(payload)
+}
@@ -0,0 +1,318 @@
+use crate::{
+    commit::process_commit,
+    error::MlsError,
+    group_context::update_group_context,
+    key_package::generate_key_package,
+    messaging::{decrypt_message, encrypt_message},
+    model::{DecryptStatus, GroupContextData, MLS_CIPHERSUITE_ID, WelcomeJoinStatus},
+    operations::{add_member, join_group, remove_member},
+    path_update::self_update,
+    protocol::generate_credential,
+    test_support::{add_and_join, create_single_member_state, legacy_payload, must},
+    welcome::{
+        attach_ratchet_tree, export_ratchet_tree, inspect_welcome, match_welcome_key_package,
+        resend_welcome, welcome_join_status,
+    },
+};
+
+#[test]
+fn welcome_matches_only_the_targeted_stored_package() {
+    let alice_state = create_single_member_state("group-match", "alice");
+    let credential = must(generate_credential("bob"), "generate credential");
+    let key_packages: Vec<_> = (0..3)
+        .map(|_| {
+            must(
+                generate_key_package(&credential.credential_bundle, &credential.private_key),
+                "generate key package",
+            )
+        })
+        .collect();
+    let stored_refs: Vec<String> = key_packages
+        .iter()
+        .map(|package| package.key_package_ref.clone())
+        .collect();
+
+    let added = must(
+        add_member(&alice_state, &key_packages[1].key_package),
+        "add bob",
+    );
+    assert_eq!(
+        must(
+            match_welcome_key_package(&added.welcome, &stored_refs),
+            "match welcome"
+        ),
+        Some(stored_refs[1].clone())
+    );
+    assert_eq!(
+        must(
+            match_welcome_key_package(&added.welcome, &stored_refs[2..]),
+            "match without target"
+        ),
+        None
+    );
+    assert!(match_welcome_key_package(b"not a welcome", &stored_refs).is_err());
+}
+
+#[test]
+fn replayed_welcome_reports_already_joined() {
+    let alice_state = create_single_member_state("group-replay", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-replay", "bob");
+
+    assert_eq!(
+        must(
+            welcome_join_status(&bob_state, &added.welcome),
+            "check replay at join epoch"
+        ),
+        WelcomeJoinStatus::AlreadyJoined
+    );
+
+    let updated = must(self_update(&bob_state), "bob moves to a later epoch");
+    assert_eq!(
+        must(
+            welcome_join_status(&updated.state, &added.welcome),
+            "check replay after update"
+        ),
+        WelcomeJoinStatus::AlreadyJoined
+    );
+
+    let other_group = create_single_member_state("group-other", "bob");
+    assert!(welcome_join_status(&other_group, &added.welcome).is_err());
+}
+
+#[test]
+fn inspect_welcome_reports_header_and_embedded_tree() {
+    let alice_state = create_single_member_state("group-inspect-welcome", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-inspect-welcome", "bob");
+
+    let info = must(inspect_welcome(&added.welcome), "inspect welcome");
+    assert_eq!(info.group_id, "group-inspect-welcome");
+    assert_eq!(info.epoch, added.new_epoch);
+    assert_eq!(info.ciphersuite, MLS_CIPHERSUITE_ID);
+    assert_eq!(info.key_package_refs.len(), 1);
+    assert!(info.has_ratchet_tree);
+    assert!(info.encrypted_group_info_present);
+
+    let mut stripped: serde_json::Value =
+        must(serde_json::from_slice(&added.welcome), "parse welcome");
+    stripped["ciphertext"] = serde_json::json!([]);
+    let stripped = must(serde_json::to_vec(&stripped), "encode welcome");
+    let info = must(inspect_welcome(&stripped), "inspect stripped welcome");
+    assert!(!info.encrypted_group_info_present);
+    assert_eq!(info.key_package_refs.len(), 1);
+
+    assert!(inspect_welcome(b"not a welcome").is_err());
+}
+
+#[test]
+fn resent_welcome_bootstraps_member_at_current_epoch() {
+    let alice_state = create_single_member_state("group-resend", "alice");
+    let credential = must(generate_credential("bob"), "generate credential");
+    let key_package = must(
+        generate_key_package(&credential.credential_bundle, &credential.private_key),
+        "generate key package",
+    );
+    let added_bob = must(
+        add_member(&alice_state, &key_package.key_package),
+        "add bob",
+    );
+    let (added_carol, _carol_state) = add_and_join(&added_bob.state, "group-resend", "carol");
+
+    let welcome = must(
+        resend_welcome(&added_carol.state, 1),
+        "resend bob's welcome",
+    );
+    assert_eq!(
+        must(inspect_welcome(&welcome), "inspect resent welcome").epoch,
+        added_carol.new_epoch
+    );
+    let bob_state = must(
+        join_group(
+            "group-resend",
+            &welcome,
+            &key_package.key_package_ref,
+            &key_package.private_key,
+            &credential.credential_bundle,
+            &credential.private_key,
+        ),
+        "bob joins from resent welcome",
+    )
+    .state;
+    let ciphertext = must(encrypt_message(&added_carol.state, b"steady"), "encrypt");
+    let decrypted = must(
+        decrypt_message(&bob_state, &ciphertext, false),
+        "bob decrypts",
+    );
+    assert_eq!(decrypted.plaintext, b"steady");
+
+    assert!(resend_welcome(&added_carol.state, 0).is_err());
+    assert!(matches!(
+        resend_welcome(&added_carol.state, 9),
+        Err(MlsError::NotFound(_))
+    ));
+
+    let update = must(self_update(&bob_state), "bob rotates his leaf");
+    let processed = must(
+        process_commit(&added_carol.state, &update.commit),
+        "alice processes update",
+    );
+    assert!(resend_welcome(&processed.state, 1).is_err());
+}
+
+#[test]
+fn removed_member_rejoins_over_stale_state_with_fresh_key_package() {
+    let alice_state = create_single_member_state("group-rejoin", "alice");
+    let bob = must(generate_credential("bob"), "generate credential");
+    let first_package = must(
+        generate_key_package(&bob.credential_bundle, &bob.private_key),
+        "first key package",
+    );
+    let added = must(
+        add_member(&alice_state, &first_package.key_package),
+        "add bob",
+    );
+    let stale_state = must(
+        join_group(
+            "group-rejoin",
+            &added.welcome,
+            &first_package.key_package_ref,
+            &first_package.private_key,
+            &bob.credential_bundle,
+            &bob.private_key,
+        ),
+        "bob joins",
+    )
+    .state;
+
+    let removed = must(remove_member(&added.state, 1), "remove bob");
+    let Err(error) = process_commit(&stale_state, &removed.commit) else {
+        panic!("expected removal to be detected");
+    };
+    assert!(error.to_string().contains("removed from group"));
+
+    let fresh_package = must(
+        generate_key_package(&bob.credential_bundle, &bob.private_key),
+        "fresh key package",
+    );
+    let readded = must(
+        add_member(&removed.state, &fresh_package.key_package),
+        "re-add bob",
+    );
+    assert_eq!(
+        must(
+            welcome_join_status(&stale_state, &readded.welcome),
+            "join status over stale state"
+        ),
+        WelcomeJoinStatus::Join
+    );
+
+    let rejoined = must(
+        join_group(
+            "group-rejoin",
+            &readded.welcome,
+            &fresh_package.key_package_ref,
+            &fresh_package.private_key,
+            &bob.credential_bundle,
+            &bob.private_key,
+        ),
+        "bob rejoins",
+    );
+    assert_eq!(rejoined.epoch, readded.new_epoch);
+    // The stale epoch-1 state must not be able to read the rejoined epoch.
+    let ciphertext = must(encrypt_message(&readded.state, b"welcome back"), "encrypt");
+    let stale = must(
+        decrypt_message(&stale_state, &ciphertext, false),
+        "stale state reads rejoined epoch",
+    );
+    assert_eq!(stale.status, DecryptStatus::FutureEpoch);
+    assert!(stale.plaintext.is_empty());
+    let decrypted = must(
+        decrypt_message(&rejoined.state, &ciphertext, false),
+        "bob decrypts after rejoin",
+    );
+    assert_eq!(decrypted.plaintext, b"welcome back");
+}
+
+#[test]
+fn out_of_band_ratchet_tree_must_be_supplied_to_join() {
+    let alice_state = create_single_member_state("group-oob-tree", "alice");
+    let (added, bob_state) = add_and_join(&alice_state, "group-oob-tree", "bob");
+    let switched = must(
+        update_group_context(
+            &added.state,
+            GroupContextData {
+                ratchet_tree_out_of_band: true,
+                ..GroupContextData::default()
+            },
+        ),
+        "alice drops the ratchet tree extension",
+    );
+    must(
+        process_commit(&bob_state, &switched.commit),
+        "bob follows the switch",
+    );
+
+    let carol = must(generate_credential("carol"), "generate credential");
+    let package = must(
+        generate_key_package(&carol.credential_bundle, &carol.private_key),
+        "key package",
+    );
+    let added_carol = must(
+        add_member(&switched.state, &package.key_package),
+        "add carol",
+    );
+    let inline = must(
+        add_member(&added.state, &package.key_package),
+        "add carol before the switch",
+    );
+    assert!(added_carol.welcome.len() < inline.welcome.len());
+    assert!(!must(inspect_welcome(&added_carol.welcome), "inspect").has_ratchet_tree);
+
+    let join = |welcome: &[u8]| {
+        join_group(
+            "group-oob-tree",
+            welcome,
+            &package.key_package_ref,
+            &package.private_key,
+            &carol.credential_bundle,
+            &carol.private_key,
+        )
+    };
+    let Err(error) = join(&added_carol.welcome) else {
+        panic!("join without the tree should fail");
+    };
+    assert!(error.to_string().contains("out of band"), "{error}");
+
+    let stale_tree = must(export_ratchet_tree(&switched.state), "tree without carol");
+    let mismatched = must(
+        attach_ratchet_tree(&added_carol.welcome, &stale_tree),
+        "attach stale tree",
+    );
+    let Err(error) = join(&mismatched) else {
+        panic!("join with a mismatched tree should fail");
+    };
+    assert!(error.to_string().contains("does not match"), "{error}");
+
+    let tree = must(export_ratchet_tree(&added_carol.state), "export tree");
+    let completed = must(
+        attach_ratchet_tree(&added_carol.welcome, &tree),
+        "attach tree",
+    );
+    assert!(must(inspect_welcome(&completed), "inspect").has_ratchet_tree);
+    let joined = must(join(&completed), "carol joins with the tree");
+    assert_eq!(joined.members.len(), 3);
+}
+
+#[test]
+fn version_one_welcomes_are_rejected_with_a_version_error() {
+    let alice_state = create_single_member_state("group-legacy-welcome", "alice");
+    let (added, _bob_state) = add_and_join(&alice_state, "group-legacy-welcome", "bob");
+    let legacy = legacy_payload(&added.welcome, 1, &["secrets"]);
+
+    let Err(error) = inspect_welcome(&legacy) else {
+        panic!("version 1 welcome was accepted");
+    };
+    assert_eq!(
+        error.to_string(),
+        "unsupported welcome version 1; expected 2"
+    );
+}
@@ -0,0 +1,47 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { base64ToBytes, bytesToBase64 } from './base64.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('base64', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('round-trips bytes across chunk boundaries', () => {
+    for (const length of [0, 1, 0x8000, 0x8001]) {
+      const bytes = Uint8Array.from({ length }, (_, index) => index % 256);
+      expect(base64ToBytes(bytesToBase64(bytes), 'Bytes')).toEqual(bytes);
+    }
+  });
+
+  it('encodes a commit bundle larger than 200 KB', async () => {
+    const bundle = Uint8Array.from(
+      { length: 256 * 1024 },
+      (_, index) => (index * 7) % 256
+    );
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_add_members_bundle: () => ({ state: [2], bundle, new_epoch: 2 })
+      })
+    );
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('mock-group');
+
+    const result = await client.addMembersBundle('mock-group', [
+      Uint8Array.from([5])
+    ]);
+
+    expect(base64ToBytes(result.bundle, 'Bundle')).toEqual(bundle);
+    client.close();
+  });
+});
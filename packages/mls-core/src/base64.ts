@@ -0,0 +1,22 @@
+/** Base64 helpers for the string forms exchanged with servers and peers. */
+
+/** Bytes passed to one `String.fromCharCode` call, below engine argument limits. */
+const CHUNK_SIZE = 0x8000;
+
+export function bytesToBase64(bytes: Uint8Array): string {
+  let binary = '';
+  for (let index = 0; index < bytes.length; index += CHUNK_SIZE) {
+    binary += String.fromCharCode(...bytes.subarray(index, index + CHUNK_SIZE));
+  }
+  return btoa(binary);
+}
+
+export function base64ToBytes(value: string, field: string): Uint8Array {
+  let decoded: string;
+  try {
+    decoded = atob(value);
+  } catch {
+    throw new Error(`${field} must be valid base64`);
+  }
+  return Uint8Array.from(decoded, (char) => char.charCodeAt(0));
+}
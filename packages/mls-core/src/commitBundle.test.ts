@@ -0,0 +1,45 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { unpackBundle } from './commitBundle.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import { READY_BACKEND_STATUS } from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('commitBundle', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+  });
+
+  it('unpacks a base64 bundle into its commit and welcomes', async () => {
+    const unpack = vi.fn((_bundle: Uint8Array) => ({
+      commit: [1],
+      welcomes: [{ key_package_ref: 'kp', welcome: [2] }]
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_unpack_commit_bundle: unpack })
+    );
+
+    await expect(unpackBundle('AQID')).resolves.toEqual({
+      commit: Uint8Array.from([1]),
+      welcomes: [{ keyPackageRef: 'kp', welcome: Uint8Array.from([2]) }]
+    });
+    expect(unpack).toHaveBeenCalledWith(Uint8Array.from([1, 2, 3]));
+  });
+
+  it('rejects a bundle that is not base64 before calling WASM', async () => {
+    const unpack = vi.fn((_bundle: Uint8Array) => ({
+      commit: [],
+      welcomes: []
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_unpack_commit_bundle: unpack })
+    );
+
+    await expect(unpackBundle('not base64!')).rejects.toThrow(
+      'bundle must be valid base64'
+    );
+    expect(unpack).not.toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,80 @@
+/**
+ * One-blob delivery of a membership commit and its welcomes.
+ *
+ * The bundle is base64 over this big-endian, length-prefixed framing:
+ *
+ * - `u8` version, currently 1
+ * - `u32` commit length, then the commit bytes
+ * - `u32` welcome count, then for each welcome:
+ *   - `u32` key package ref length, then the UTF-8 hex ref
+ *   - `u32` welcome length, then the welcome bytes
+ *
+ * No bytes follow the last welcome.
+ */
+
+import { base64ToBytes } from './base64.js';
+import {
+  loadMlsWasmPrimitiveBindings,
+  resolveMlsBackendStatus
+} from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  readNumber,
+  readObjectArray,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type { CommitBundle } from './types.js';
+
+interface AddMembersBundleResult {
+  state: Uint8Array;
+  bundle: Uint8Array;
+  newEpoch: number;
+}
+
+export async function wasmAddMembersBundle(
+  stateBytes: Uint8Array,
+  keyPackages: Uint8Array[],
+  forcePathUpdate: boolean
+): Promise<AddMembersBundleResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_add_members_bundle(
+    stateBytes,
+    keyPackages,
+    forcePathUpdate
+  );
+  if (!isRecordLike(value)) {
+    throw new Error('WASM add-members-bundle response must be an object');
+  }
+
+  return {
+    state: readUint8Array(value, 'state'),
+    bundle: readUint8Array(value, 'bundle'),
+    newEpoch: readNumber(value, 'new_epoch')
+  };
+}
+
+/** Splits a base64 bundle from `addMembersBundle`, e.g. on the server. */
+export async function unpackBundle(
+  bundleBase64: string
+): Promise<CommitBundle> {
+  const status = await resolveMlsBackendStatus();
+  if (!status.productionReady) {
+    throw new Error(`MLS backend not ready: ${status.reason}`);
+  }
+
+  const bundle = base64ToBytes(bundleBase64, 'bundle');
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_unpack_commit_bundle(bundle);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM unpack-bundle response must be an object');
+  }
+
+  return {
+    commit: readUint8Array(value, 'commit'),
+    welcomes: readObjectArray(value, 'welcomes').map((welcome) => ({
+      keyPackageRef: readString(welcome, 'key_package_ref'),
+      welcome: readUint8Array(welcome, 'welcome')
+    }))
+  };
+}
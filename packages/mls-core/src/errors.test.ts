@@ -0,0 +1,81 @@
+import { describe, expect, it } from 'vitest';
+import {
+  IdentityRejectedError,
+  MessageTooLargeError,
+  mlsErrorContext,
+  ResyncRequiredError
+} from './errors.js';
+
+describe('MessageTooLargeError', () => {
+  it('reports the rejected size and the limit', () => {
+    const error = new MessageTooLargeError(2048, 1024);
+
+    expect(error).toBeInstanceOf(Error);
+    expect(error.name).toBe('MessageTooLargeError');
+    expect(error.size).toBe(2048);
+    expect(error.maxSize).toBe(1024);
+    expect(error.message).toBe(
+      'Message of 2048 bytes exceeds the 1024-byte limit'
+    );
+  });
+});
+
+describe('ResyncRequiredError', () => {
+  it('reports the group and both epochs', () => {
+    const error = new ResyncRequiredError('group-1', 3, 7);
+
+    expect(error).toBeInstanceOf(Error);
+    expect(error.name).toBe('ResyncRequiredError');
+    expect(error.groupId).toBe('group-1');
+    expect(error.localEpoch).toBe(3);
+    expect(error.messageEpoch).toBe(7);
+    expect(error.message).toBe(
+      'Group group-1 is at epoch 3 but received a message from epoch 7; resync required'
+    );
+  });
+});
+
+describe('IdentityRejectedError', () => {
+  it('names the rejected identity', () => {
+    const identity = Uint8Array.from([98, 111, 98]);
+    const error = new IdentityRejectedError('bob', identity);
+
+    expect(error).toBeInstanceOf(Error);
+    expect(error.name).toBe('IdentityRejectedError');
+    expect(error.userId).toBe('bob');
+    expect(error.identity).toBe(identity);
+    expect(error.message).toBe('Identity rejected: bob');
+  });
+});
+
+describe('mlsErrorContext', () => {
+  it('reads the failing item attached by the WASM core', () => {
+    const error = Object.assign(new Error('reused'), {
+      error_context: { kind: 'key_package_ref', id: 'ab12' }
+    });
+    expect(mlsErrorContext(error)).toEqual({
+      kind: 'key_package_ref',
+      id: 'ab12'
+    });
+
+    const decryptError = Object.assign(new Error('sender leaf 3'), {
+      error_context: { kind: 'sender_leaf_index', id: 3 }
+    });
+    expect(mlsErrorContext(decryptError)).toEqual({
+      kind: 'sender_leaf_index',
+      id: 3
+    });
+  });
+
+  it('returns null for errors without a well-formed context', () => {
+    expect(mlsErrorContext(new Error('plain'))).toBeNull();
+    expect(mlsErrorContext('plain')).toBeNull();
+    expect(
+      mlsErrorContext(
+        Object.assign(new Error('bad'), {
+          error_context: { kind: 'leaf_index', id: '3' }
+        })
+      )
+    ).toBeNull();
+  });
+});
@@ -0,0 +1,100 @@
+import { isRecordLike } from './mlsWasmResponse.js';
+
+/**
+ * Error thrown when a plaintext exceeds the client's maximum message size.
+ * Callers that need to send larger payloads should split them into chunks.
+ */
+export class MessageTooLargeError extends Error {
+  readonly size: number;
+  readonly maxSize: number;
+
+  constructor(size: number, maxSize: number) {
+    super(`Message of ${size} bytes exceeds the ${maxSize}-byte limit`);
+    this.name = 'MessageTooLargeError';
+    this.size = size;
+    this.maxSize = maxSize;
+  }
+}
+
+/**
+ * Error thrown when a message is further ahead of the local epoch than the
+ * client tolerates. The local state has missed commits and must be resynced.
+ */
+export class ResyncRequiredError extends Error {
+  readonly groupId: string;
+  readonly localEpoch: number;
+  readonly messageEpoch: number;
+
+  constructor(groupId: string, localEpoch: number, messageEpoch: number) {
+    super(
+      `Group ${groupId} is at epoch ${localEpoch} but received a message from epoch ${messageEpoch}; resync required`
+    );
+    this.name = 'ResyncRequiredError';
+    this.groupId = groupId;
+    this.localEpoch = localEpoch;
+    this.messageEpoch = messageEpoch;
+  }
+}
+
+/**
+ * Error thrown when signing after `deleteSignatureKeys`. Group states lost
+ * their signing key too, so authoring in a group again needs a fresh join.
+ * `groupId` is null when the credential itself was needed.
+ */
+export class CredentialDeletedError extends Error {
+  readonly groupId: string | null;
+
+  constructor(groupId: string | null) {
+    super(
+      groupId === null
+        ? 'Signing credential was deleted'
+        : `Signing key for group ${groupId} was deleted`
+    );
+    this.name = 'CredentialDeletedError';
+    this.groupId = groupId;
+  }
+}
+
+/**
+ * Error thrown when the identity validator rejects a member being added or
+ * an existing member of a group being joined. The operation is aborted.
+ */
+export class IdentityRejectedError extends Error {
+  readonly userId: string;
+  readonly identity: Uint8Array;
+
+  constructor(userId: string, identity: Uint8Array) {
+    super(`Identity rejected: ${userId}`);
+    this.name = 'IdentityRejectedError';
+    this.userId = userId;
+    this.identity = identity;
+  }
+}
+
+/** Item of a multi-item operation that a WASM error is attributed to. */
+export type MlsErrorContext =
+  | { kind: 'key_package_ref'; id: string }
+  | { kind: 'leaf_index'; id: number }
+  | { kind: 'sender_leaf_index'; id: number };
+
+/**
+ * Returns the failing item attached to an error thrown by the WASM core:
+ * the key package ref for adds, the leaf index for removes, or the sender
+ * leaf index for decrypts. Returns null for errors without one.
+ */
+export function mlsErrorContext(error: unknown): MlsErrorContext | null {
+  if (!isRecordLike(error) || !isRecordLike(error['error_context'])) {
+    return null;
+  }
+  const { kind, id } = error['error_context'];
+  if (kind === 'key_package_ref' && typeof id === 'string') {
+    return { kind, id };
+  }
+  if (
+    (kind === 'leaf_index' || kind === 'sender_leaf_index') &&
+    typeof id === 'number'
+  ) {
+    return { kind, id };
+  }
+  return null;
+}
@@ -0,0 +1,97 @@
+/**
+ * Application trust policy consulted when members are added or a welcome is
+ * joined, e.g. to check identities against a directory.
+ */
+
+import { base64ToBytes } from './base64.js';
+import { IdentityRejectedError } from './errors.js';
+import { wasmInspectKeyPackage } from './keyPackageValidation.js';
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  type RecordLike,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+
+/** Accepts or rejects a credential identity and its signature key. */
+export type IdentityValidator = (
+  identity: Uint8Array,
+  signatureKey: Uint8Array
+) => boolean | Promise<boolean>;
+
+interface MemberIdentity {
+  userId: string;
+  identity: Uint8Array;
+  signatureKey: Uint8Array;
+}
+
+function readMemberIdentity(record: RecordLike): MemberIdentity {
+  return {
+    userId: readString(record, 'user_id'),
+    identity: readUint8Array(record, 'identity'),
+    signatureKey: readUint8Array(record, 'signing_public_key')
+  };
+}
+
+async function keyPackageIdentity(
+  keyPackage: Uint8Array
+): Promise<MemberIdentity> {
+  const info = await wasmInspectKeyPackage(keyPackage);
+  return {
+    userId: info.userId,
+    identity: base64ToBytes(info.identity, 'identity'),
+    signatureKey: base64ToBytes(info.signatureKey, 'signature key')
+  };
+}
+
+async function memberIdentities(
+  stateBytes: Uint8Array
+): Promise<MemberIdentity[]> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_member_credentials(stateBytes);
+  if (!Array.isArray(value)) {
+    throw new Error('WASM member-credentials response must be an array');
+  }
+  return value.map((entry: unknown) => {
+    if (!isRecordLike(entry)) {
+      throw new Error('WASM member-credentials entries must be objects');
+    }
+    return readMemberIdentity(entry);
+  });
+}
+
+async function assertAccepted(
+  validator: IdentityValidator,
+  identities: MemberIdentity[]
+): Promise<void> {
+  for (const entry of identities) {
+    if (!(await validator(entry.identity, entry.signatureKey))) {
+      throw new IdentityRejectedError(entry.userId, entry.identity);
+    }
+  }
+}
+
+/** Runs `validator` over the credential of every key package to be added. */
+export async function assertKeyPackagesAccepted(
+  validator: IdentityValidator,
+  keyPackages: Uint8Array[]
+): Promise<void> {
+  await assertAccepted(
+    validator,
+    await Promise.all(keyPackages.map(keyPackageIdentity))
+  );
+}
+
+/** Runs `validator` over every member of a joined state except `selfUserId`. */
+export async function assertMembersAccepted(
+  validator: IdentityValidator,
+  stateBytes: Uint8Array,
+  selfUserId: string
+): Promise<void> {
+  const members = await memberIdentities(stateBytes);
+  await assertAccepted(
+    validator,
+    members.filter((member) => member.userId !== selfUserId)
+  );
+}
@@ -1,3 +1,20 @@
+export { unpackBundle } from './commitBundle.js';
+export type { MlsErrorContext } from './errors.js';
+export {
+  CredentialDeletedError,
+  IdentityRejectedError,
+  MessageTooLargeError,
+  mlsErrorContext,
+  ResyncRequiredError
+} from './errors.js';
+export type { IdentityValidator } from './identityValidation.js';
+export {
+  inspectKeyPackage,
+  validateKeyPackage
+} from './keyPackageValidation.js';
+export type { EpochChangeListener } from './mlsClientBase.js';
+export { initMlsWasmWithOptions } from './mlsWasmBackend.js';
+export { inspectWelcomePublic } from './mlsWasmWelcome.js';
 export type { MlsBackendStatus } from './mls.js';
 export {
   MLS_CIPHERSUITE_ID,
@@ -11,7 +28,30 @@ export type {
 export { generateMlsOnboardingKeyMaterial } from './onboarding.js';
 export { MlsStorage } from './storage.js';
 export type {
+  AddMembersOptions,
+  AddMembersResult,
+  CatchUpResult,
+  CommitBundle,
+  CommitPreview,
+  CommitResult,
+  DecryptedContent,
+  DecryptedIntoContent,
+  ExportStateChunk,
+  GroupContextChange,
+  GroupPolicy,
+  GroupStateReport,
+  JoinGroupResult,
+  JoinGroupStatus,
+  KeyPackageInfo,
+  KeyPackageValidation,
+  KeyPackageWithRef,
   LocalKeyPackage,
   LocalMlsState,
-  MlsCredential
+  MessageCounters,
+  MlsCredential,
+  PskReference,
+  PublicGroupState,
+  SafetyNumber,
+  WelcomeInfo,
+  WelcomePublicInfo
 } from './types.js';
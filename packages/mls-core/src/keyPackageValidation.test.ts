@@ -0,0 +1,54 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { inspectKeyPackage } from './keyPackageValidation.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import { READY_BACKEND_STATUS } from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('keyPackageValidation', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+  });
+
+  it('inspects a base64 key package and keeps its keys base64', async () => {
+    const inspect = vi.fn((_keyPackage: Uint8Array) => ({
+      key_package_ref: 'ab12',
+      ciphersuite: 3,
+      user_id: 'alice',
+      identity: 'YWxpY2U=',
+      signing_public_key: 'AQI=',
+      init_key: 'AwQ=',
+      leaf_node_encryption_key: 'AwQ=',
+      lifetime: { not_before: 100, not_after: 200 },
+      last_resort: true
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_inspect_key_package: inspect })
+    );
+
+    await expect(inspectKeyPackage('CQk=')).resolves.toEqual({
+      ref: 'ab12',
+      ciphersuite: 3,
+      userId: 'alice',
+      identity: 'YWxpY2U=',
+      signatureKey: 'AQI=',
+      initKey: 'AwQ=',
+      leafNodeEncryptionKey: 'AwQ=',
+      lifetime: { notBefore: 100, notAfter: 200 },
+      lastResort: true
+    });
+    expect(inspect).toHaveBeenCalledWith(Uint8Array.from([9, 9]));
+  });
+
+  it('rejects a key package that is not base64', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+
+    await expect(inspectKeyPackage('not base64!')).rejects.toThrow(
+      'key package must be valid base64'
+    );
+  });
+});
@@ -0,0 +1,95 @@
+/**
+ * Group-free key package checks for delivery services accepting uploads.
+ */
+
+import { base64ToBytes } from './base64.js';
+import {
+  loadMlsWasmPrimitiveBindings,
+  resolveMlsBackendStatus
+} from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  readBoolean,
+  readNumber,
+  readOptionalString,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type { KeyPackageInfo, KeyPackageValidation } from './types.js';
+
+/** Parses a key package and verifies its signature; see `inspectKeyPackage`. */
+export async function wasmInspectKeyPackage(
+  keyPackage: Uint8Array
+): Promise<KeyPackageInfo> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_inspect_key_package(keyPackage);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM inspect-key-package response must be an object');
+  }
+  const lifetime = value['lifetime'];
+  if (!isRecordLike(lifetime)) {
+    throw new Error("WASM response field 'lifetime' must be an object");
+  }
+
+  return {
+    ref: readString(value, 'key_package_ref'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    userId: readString(value, 'user_id'),
+    identity: readString(value, 'identity'),
+    signatureKey: readString(value, 'signing_public_key'),
+    initKey: readString(value, 'init_key'),
+    leafNodeEncryptionKey: readString(value, 'leaf_node_encryption_key'),
+    lifetime: {
+      notBefore: readNumber(lifetime, 'not_before'),
+      notAfter: readNumber(lifetime, 'not_after')
+    },
+    lastResort: readBoolean(value, 'last_resort')
+  };
+}
+
+/**
+ * Returns a key package's public contents after verifying its signature, so
+ * servers can enforce policy on identities and keys without joining a group.
+ */
+export async function inspectKeyPackage(
+  keyPackageB64: string
+): Promise<KeyPackageInfo> {
+  const status = await resolveMlsBackendStatus();
+  if (!status.productionReady) {
+    throw new Error(`MLS backend not ready: ${status.reason}`);
+  }
+
+  return wasmInspectKeyPackage(base64ToBytes(keyPackageB64, 'key package'));
+}
+
+/**
+ * Verifies a key package's signature and lifetime at `nowSeconds` so servers
+ * can reject malformed or expired uploads with a precise reason.
+ */
+export async function validateKeyPackage(
+  keyPackage: Uint8Array,
+  nowSeconds: number
+): Promise<KeyPackageValidation> {
+  const status = await resolveMlsBackendStatus();
+  if (!status.productionReady) {
+    throw new Error(`MLS backend not ready: ${status.reason}`);
+  }
+
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_validate_key_package(
+    keyPackage,
+    BigInt(nowSeconds)
+  );
+  if (!isRecordLike(value)) {
+    throw new Error('WASM validate-key-package response must be an object');
+  }
+
+  const reason = readOptionalString(value, 'reason');
+  return {
+    valid: readBoolean(value, 'valid'),
+    ref: readString(value, 'key_package_ref'),
+    identity: readUint8Array(value, 'identity'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    ...(reason === undefined ? {} : { reason })
+  };
+}
@@ -18,6 +18,24 @@ describe('MlsClient', () => {
     client.close();
   });
 
+  it('accepts an observer option at construction', () => {
+    const client = new MlsClient('observer-1', undefined, { observer: true });
+    expect(client).toBeInstanceOf(MlsClient);
+    client.close();
+  });
+
+  it('reports no signature keys before a credential exists', () => {
+    const client = new MlsClient('user-123');
+    expect(client.hasSignatureKeys()).toBe(false);
+    client.close();
+  });
+
+  it('has no quarantined groups before init', () => {
+    const client = new MlsClient('user-123');
+    expect(client.listQuarantinedGroups()).toEqual([]);
+    client.close();
+  });
+
   it('starts in placeholder backend mode before init', () => {
     const client = new MlsClient('user-123');
     const status = client.getBackendStatus();
@@ -27,4 +45,71 @@ describe('MlsClient', () => {
 
     client.close();
   });
+
+  it('refuses chunked export before the backend is ready', async () => {
+    const client = new MlsClient('user-123');
+
+    expect(() => client.exportStateBegin()).toThrow(/not ready/);
+    await expect(client.exportStateNext(1)).rejects.toThrow(/not ready/);
+
+    client.close();
+  });
+
+  it('refuses to create a solo group before the backend is ready', async () => {
+    const client = new MlsClient('user-123');
+
+    await expect(client.createSoloGroup('notes')).rejects.toThrow(/not ready/);
+
+    client.close();
+  });
+
+  it('rejects invalid maximum message sizes', () => {
+    const client = new MlsClient('user-123');
+
+    expect(() => client.setMaxMessageSize(-1)).toThrow(
+      'Invalid maximum message size: -1'
+    );
+    expect(() => client.setMaxMessageSize(1.5)).toThrow(
+      'Invalid maximum message size: 1.5'
+    );
+    client.setMaxMessageSize(1024);
+    client.setMaxMessageSize(null);
+
+    client.close();
+  });
+
+  it('rejects invalid epoch tolerances', () => {
+    const client = new MlsClient('user-123');
+
+    expect(() => client.setEpochTolerance(-1)).toThrow(
+      'Invalid epoch tolerance: -1'
+    );
+    expect(() => client.setEpochTolerance(0.5)).toThrow(
+      'Invalid epoch tolerance: 0.5'
+    );
+    client.setEpochTolerance(0);
+    client.setEpochTolerance(5);
+
+    client.close();
+  });
+
+  it('rejects merging when no commit has been staged', async () => {
+    const client = new MlsClient('user-123');
+
+    await expect(client.mergeStagedCommit('group-1')).rejects.toThrow(
+      'No staged commit for group: group-1'
+    );
+
+    client.close();
+  });
+
+  it('has no pending commit epoch until a commit is staged', () => {
+    const client = new MlsClient('user-123');
+
+    expect(() => client.pendingCommitEpoch('group-1')).toThrow(
+      'No pending commit for group: group-1'
+    );
+
+    client.close();
+  });
 });
@@ -5,110 +5,87 @@
  * Rust/WASM backend. The TypeScript layer handles persistence and orchestration.
  */
 
+import { bytesToBase64 } from './base64.js';
+import { wasmAddMembersBundle } from './commitBundle.js';
+import { MlsPendingCommitClient } from './mlsClientPendingCommits.js';
+import type { MlsBackendStatus } from './mlsWasmBackend.js';
+import { toAddMembersResult, wasmAddMembers } from './mlsWasmCommits.js';
 import {
-  type MlsBackendStatus,
-  resolveMlsBackendStatus
-} from './mlsWasmBackend.js';
-import {
-  membersToLeafIndexMap,
   wasmAddMember,
   wasmCreateGroup,
-  wasmDecryptMessage,
-  wasmEncryptMessage,
-  wasmExportGroupState,
+  wasmCreateSoloGroup,
+  wasmForceLeave,
   wasmGenerateCredential,
-  wasmGenerateKeyPackage,
-  wasmGroupStateMetadata,
-  wasmImportGroupState,
+  wasmGenerateCredentialFromIdentity,
   wasmJoinGroup,
+  wasmPreviewCommit,
   wasmProcessCommit,
-  wasmRemoveMember
+  wasmRemoveMember,
+  wasmReplaceMember
 } from './mlsWasmBridge.js';
-import { MlsStorage } from './storage.js';
-import type { LocalKeyPackage, LocalMlsState, MlsCredential } from './types.js';
+import {
+  wasmAttachRatchetTree,
+  wasmExportRatchetTree,
+  wasmInspectWelcome,
+  wasmResendWelcome,
+  wasmWelcomeJoinStatus
+} from './mlsWasmWelcome.js';
+import type { MlsStorage } from './storage.js';
+import type {
+  AddMembersOptions,
+  AddMembersResult,
+  CommitPreview,
+  CommitResult,
+  GroupContextChange,
+  JoinGroupResult,
+  MlsCredential,
+  WelcomeInfo
+} from './types.js';
 
 // Standard ciphersuite: MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
 export const MLS_CIPHERSUITE_NAME =
   'MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519';
 export const MLS_CIPHERSUITE_ID = 0x0003;
 
-interface KeyPackageWithRef {
-  ref: string;
-  keyPackageBytes: Uint8Array;
-}
-
-interface CommitResult {
+interface StagedCommit {
   commit: Uint8Array;
-  welcome?: Uint8Array;
-  groupInfo?: Uint8Array;
-  newEpoch?: number;
-}
-
-interface DecryptedContent {
-  senderId: string;
-  plaintext: Uint8Array;
-  authenticatedData: Uint8Array;
-}
-
-interface GroupState {
-  groupId: string;
-  epoch: number;
-  members: Map<string, number>; // userId -> leafIndex
-  serialized: Uint8Array;
+  removesSelf: boolean;
 }
 
-class MlsClientImpl {
-  private storage: MlsStorage;
-  private groupStates: Map<string, GroupState> = new Map();
-  private credential: MlsCredential | null = null;
-  private userId: string;
-  private backendStatus: MlsBackendStatus = {
-    backend: 'placeholder',
-    wasmModuleLoaded: false,
-    backendName: 'typescript-placeholder',
-    backendVersion: null,
-    productionReady: false,
-    reason: 'MLS backend status not initialized yet.'
-  };
-
-  constructor(userId: string, storage?: MlsStorage) {
-    this.userId = userId;
-    this.storage = storage ?? new MlsStorage();
-  }
-
-  async init(): Promise<void> {
-    await this.storage.init();
-
-    this.backendStatus = await resolveMlsBackendStatus();
-
-    if (!this.backendStatus.productionReady) {
-      console.warn(`[mls-core] ${this.backendStatus.reason}`);
-    }
-
-    const storedCredential = await this.storage.getCredential(this.userId);
-    if (storedCredential) {
-      this.credential = storedCredential;
-    }
-
-    const groupStates = await this.storage.getAllGroupStates();
-    for (const state of groupStates) {
-      try {
-        await this.installSerializedGroupState(
-          state.groupId,
-          state.serializedState,
-          true
-        );
-      } catch {
-        await this.storage.deleteGroupState(state.groupId);
-      }
-    }
+class MlsClientImpl extends MlsPendingCommitClient {
+  private stagedCommits: Map<string, StagedCommit> = new Map();
+
+  /**
+   * Creates a client whose credential wraps raw identity bytes, e.g. a device
+   * public key, instead of a user id string. The client's `userId` is the one
+   * the WASM layer derives from `identity`, so group membership and storage
+   * keys match what other members see.
+   */
+  static async newWithCredential(
+    identity: Uint8Array,
+    storage?: MlsStorage,
+    options: { observer?: boolean } = {}
+  ): Promise<MlsClientImpl> {
+    const result = await wasmGenerateCredentialFromIdentity(identity);
+    const client = new MlsClientImpl(result.userId, storage, options);
+    await client.init();
+    client.assertBackendReady();
+    await client.storeCredential(result);
+    return client;
   }
 
   async generateCredential(): Promise<MlsCredential> {
     this.assertBackendReady();
 
     const result = await wasmGenerateCredential(this.userId);
+    return this.storeCredential(result);
+  }
 
+  private async storeCredential(result: {
+    credentialBundle: Uint8Array;
+    privateKey: Uint8Array;
+    createdAtMs: number;
+  }): Promise<MlsCredential> {
     const credential: MlsCredential = {
       credentialBundle: result.credentialBundle,
       privateKey: result.privateKey,
@@ -122,40 +99,30 @@ class MlsClientImpl {
     return credential;
   }
 
-  async generateKeyPackage(): Promise<KeyPackageWithRef> {
+  async createGroup(groupId: string): Promise<Uint8Array> {
     this.assertBackendReady();
+    const credential = this.requireCredential();
 
-    if (!this.credential) {
-      throw new Error(
-        'No credential available. Call generateCredential first.'
-      );
-    }
-
-    const result = await wasmGenerateKeyPackage(
-      this.credential.credentialBundle,
-      this.credential.privateKey
+    const state = await wasmCreateGroup(
+      groupId,
+      credential.credentialBundle,
+      credential.privateKey
     );
 
-    const localKeyPackage: LocalKeyPackage = {
-      ref: result.keyPackageRef,
-      keyPackage: result.keyPackage,
-      privateKey: result.privateKey,
-      createdAt: result.createdAtMs
-    };
-
-    await this.storage.saveKeyPackage(localKeyPackage);
-
-    return {
-      ref: result.keyPackageRef,
-      keyPackageBytes: result.keyPackage
-    };
+    await this.installSerializedGroupState(groupId, state, true);
+    return state;
   }
 
-  async createGroup(groupId: string): Promise<Uint8Array> {
+  /**
+   * Creates a notes-to-self group with only this client as a member. Unlike
+   * `createGroup`, messages we send to it decrypt locally, so other devices
+   * restored from the same state can read them.
+   */
+  async createSoloGroup(groupId: string): Promise<Uint8Array> {
     this.assertBackendReady();
     const credential = this.requireCredential();
 
-    const state = await wasmCreateGroup(
+    const state = await wasmCreateSoloGroup(
       groupId,
       credential.credentialBundle,
       credential.privateKey
@@ -165,30 +132,96 @@ class MlsClientImpl {
     return state;
   }
 
+  /**
+   * Joins from a welcome. A replayed welcome for a group we already hold at the
+   * same or a later epoch is ignored and reported as `already_joined`.
+   *
+   * After being removed, publish a fresh key package and have a member re-add
+   * it; the newer welcome replaces any stale state held for the group id.
+   *
+   * Groups that send the member tree out of band need `ratchetTree` from an
+   * existing member's `exportRatchetTree`; `inspectWelcome` reports which.
+   *
+   * The used key package is deleted unless it is a last-resort package.
+   */
   async joinGroup(
     groupId: string,
     welcomeBytes: Uint8Array,
-    keyPackageRef: string
-  ): Promise<void> {
+    keyPackageRef: string,
+    options: { ratchetTree?: Uint8Array } = {}
+  ): Promise<JoinGroupResult> {
     this.assertBackendReady();
     const credential = this.requireCredential();
 
+    const existing = this.groupStates.get(groupId);
+    if (
+      existing &&
+      (await wasmWelcomeJoinStatus(existing.serialized, welcomeBytes)) ===
+        'already_joined'
+    ) {
+      return {
+        status: 'already_joined',
+        groupId,
+        epoch: existing.epoch,
+        ciphersuite: MLS_CIPHERSUITE_ID,
+        members: new Map(existing.members)
+      };
+    }
+
     const localKeyPackage = await this.storage.getKeyPackage(keyPackageRef);
     if (!localKeyPackage) {
       throw new Error(`Key package not found: ${keyPackageRef}`);
     }
 
-    const state = await wasmJoinGroup(
+    const joined = await wasmJoinGroup(
       groupId,
-      welcomeBytes,
+      options.ratchetTree
+        ? await wasmAttachRatchetTree(welcomeBytes, options.ratchetTree)
+        : welcomeBytes,
       keyPackageRef,
       localKeyPackage.privateKey,
       credential.credentialBundle,
       credential.privateKey
     );
 
-    await this.installSerializedGroupState(groupId, state, true);
-    await this.storage.deleteKeyPackage(keyPackageRef);
+    await this.assertMembersAccepted(joined.state, credential.userId);
+    this.stagedCommits.delete(groupId);
+    this.discardPendingCommit(groupId);
+    await this.installSerializedGroupState(groupId, joined.state, true);
+    if (!localKeyPackage.lastResort) {
+      await this.storage.deleteKeyPackage(keyPackageRef);
+    }
+    return {
+      status: 'joined',
+      groupId: joined.groupId,
+      epoch: joined.epoch,
+      ciphersuite: joined.ciphersuite,
+      members: membersToLeafIndexMap(joined)
+    };
+  }
+
+  /** Reads a welcome's header, e.g. to check it embeds the member tree. */
+  async inspectWelcome(welcomeBytes: Uint8Array): Promise<WelcomeInfo> {
+    this.assertBackendReady();
+    return wasmInspectWelcome(welcomeBytes);
+  }
+
+  /**
+   * Rebuilds a welcome at the current epoch for a member who lost theirs,
+   * without a membership change. Fails once the member has updated its leaf.
+   */
+  async resendWelcome(groupId: string, leafIndex: number): Promise<Uint8Array> {
+    this.assertBackendReady();
+    return wasmResendWelcome(
+      this.requireSigningGroupState(groupId).serialized,
+      leafIndex
+    );
+  }
+
+  /** Exports the member tree that joiners need when welcomes omit it. */
+  async exportRatchetTree(groupId: string): Promise<Uint8Array> {
+    this.assertBackendReady();
+    return wasmExportRatchetTree(this.requireGroupState(groupId).serialized);
   }
 
   async addMember(
@@ -196,7 +229,8 @@ class MlsClientImpl {
     memberKeyPackageBytes: Uint8Array
   ): Promise<CommitResult> {
     this.assertBackendReady();
-    const groupState = this.requireGroupState(groupId);
+    const groupState = this.requireSigningGroupState(groupId);
+    await this.assertKeyPackagesAccepted([memberKeyPackageBytes]);
 
     const result = await wasmAddMember(
       groupState.serialized,
@@ -213,164 +247,201 @@ class MlsClientImpl {
     };
   }
 
-  async removeMember(
+  /**
+   * Adds members in one commit and merges it. Route each entry of `welcomes`
+   * to its key package's owner. See `AddMembersOptions` for `forcePathUpdate`.
+   */
+  async addMembers(
     groupId: string,
-    leafIndex: number
-  ): Promise<CommitResult> {
+    keyPackages: Uint8Array[],
+    options: AddMembersOptions = {}
+  ): Promise<AddMembersResult> {
     this.assertBackendReady();
-    const groupState = this.requireGroupState(groupId);
+    const groupState = this.requireSigningGroupState(groupId);
+    await this.assertKeyPackagesAccepted(keyPackages);
 
-    const result = await wasmRemoveMember(groupState.serialized, leafIndex);
+    const result = await wasmAddMembers(
+      groupState.serialized,
+      keyPackages,
+      options.forcePathUpdate ?? false
+    );
+    await this.installSerializedGroupState(groupId, result.state, true);
+
+    return toAddMembersResult(result);
+  }
 
+  /**
+   * Adds members in one commit and returns it with every welcome as a single
+   * base64 blob for the delivery service; see `unpackBundle` for the framing.
+   * See `AddMembersOptions` for `forcePathUpdate`.
+   */
+  async addMembersBundle(
+    groupId: string,
+    keyPackages: Uint8Array[],
+    options: AddMembersOptions = {}
+  ): Promise<{ bundle: string; newEpoch: number }> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+    await this.assertKeyPackagesAccepted(keyPackages);
+
+    const result = await wasmAddMembersBundle(
+      groupState.serialized,
+      keyPackages,
+      options.forcePathUpdate ?? false
+    );
     await this.installSerializedGroupState(groupId, result.state, true);
 
     return {
-      commit: result.commit,
+      bundle: bytesToBase64(result.bundle),
       newEpoch: result.newEpoch
     };
   }
 
-  async processCommit(groupId: string, commitBytes: Uint8Array): Promise<void> {
+  /**
+   * Swaps a member's leaf for a fresh key package in one commit, e.g. after a
+   * device compromise, so the user is never out of the group. The key package
+   * must belong to the same user unless `allowIdentityChange` is set.
+   */
+  async replaceMember(
+    groupId: string,
+    oldLeafIndex: number,
+    memberKeyPackageBytes: Uint8Array,
+    allowIdentityChange = false
+  ): Promise<CommitResult> {
     this.assertBackendReady();
-    const groupState = this.requireGroupState(groupId);
+    const groupState = this.requireSigningGroupState(groupId);
+    await this.assertKeyPackagesAccepted([memberKeyPackageBytes]);
 
-    const updatedState = await wasmProcessCommit(
+    const result = await wasmReplaceMember(
       groupState.serialized,
-      commitBytes
+      oldLeafIndex,
+      memberKeyPackageBytes,
+      allowIdentityChange
     );
-    await this.installSerializedGroupState(groupId, updatedState, true);
-  }
 
-  async encryptMessage(
-    groupId: string,
-    plaintext: Uint8Array
-  ): Promise<Uint8Array> {
-    this.assertBackendReady();
-    const groupState = this.requireGroupState(groupId);
+    await this.installSerializedGroupState(groupId, result.state, true);
 
-    return wasmEncryptMessage(groupState.serialized, plaintext);
+    return {
+      commit: result.commit,
+      welcome: result.welcome,
+      groupInfo: result.groupInfo,
+      newEpoch: result.newEpoch
+    };
   }
 
-  async decryptMessage(
+  async removeMember(
     groupId: string,
-    ciphertext: Uint8Array
-  ): Promise<DecryptedContent> {
+    leafIndex: number
+  ): Promise<CommitResult> {
     this.assertBackendReady();
-    const groupState = this.requireGroupState(groupId);
+    const groupState = this.requireSigningGroupState(groupId);
 
-    const result = await wasmDecryptMessage(groupState.serialized, ciphertext);
+    const result = await wasmRemoveMember(groupState.serialized, leafIndex);
+
+    await this.installSerializedGroupState(groupId, result.state, true);
 
     return {
-      senderId: result.senderId,
-      plaintext: result.plaintext,
-      authenticatedData: result.authenticatedData
+      commit: result.commit,
+      newEpoch: result.newEpoch
     };
   }
 
-  async exportGroupState(groupId: string): Promise<Uint8Array> {
+  /**
+   * Merges a commit and returns the new group context if it changed. Our own
+   * pending commit for the old epoch can no longer be merged and is dropped.
+   */
+  async processCommit(
+    groupId: string,
+    commitBytes: Uint8Array
+  ): Promise<GroupContextChange | null> {
     this.assertBackendReady();
     const groupState = this.requireGroupState(groupId);
-    return wasmExportGroupState(groupState.serialized);
+
+    const result = await wasmProcessCommit(groupState.serialized, commitBytes);
+    await this.installSerializedGroupState(groupId, result.state, true);
+    this.discardPendingCommit(groupId);
+    return result.groupContextChanged;
   }
 
-  async importGroupState(
+  /**
+   * Verifies and stages a commit without changing group state, so the UI can
+   * warn before an irreversible change. Staging again replaces the old commit.
+   */
+  async previewCommit(
     groupId: string,
-    serializedState: Uint8Array
-  ): Promise<void> {
+    commitBytes: Uint8Array
+  ): Promise<CommitPreview> {
     this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
 
-    const normalized = await wasmImportGroupState(groupId, serializedState);
-    await this.installSerializedGroupState(groupId, normalized.state, true);
+    const preview = await wasmPreviewCommit(groupState.serialized, commitBytes);
+    this.stagedCommits.set(groupId, {
+      commit: commitBytes,
+      removesSelf: preview.removesSelf
+    });
+
+    return {
+      newEpoch: preview.newEpoch,
+      addedUserIds: preview.added.map((member) => member.userId),
+      removedUserIds: preview.removed.map((member) => member.userId),
+      removesSelf: preview.removesSelf
+    };
   }
 
-  getGroupEpoch(groupId: string): number | undefined {
-    return this.groupStates.get(groupId)?.epoch;
+  /** Applies the staged commit; one that removes us drops the group locally. */
+  async mergeStagedCommit(groupId: string): Promise<void> {
+    const staged = this.requireStagedCommit(groupId);
+
+    this.stagedCommits.delete(groupId);
+    if (staged.removesSelf) {
+      await this.leaveGroup(groupId);
+      return;
+    }
+    await this.processCommit(groupId, staged.commit);
   }
 
-  getBackendStatus(): MlsBackendStatus {
-    return this.backendStatus;
+  discardStagedCommit(groupId: string): void {
+    this.stagedCommits.delete(groupId);
   }
 
   generateGroupIdMls(): string {
     const bytes = new Uint8Array(16);
     crypto.getRandomValues(bytes);
-    return this.bytesToBase64(bytes);
-  }
-
-  hasGroup(groupId: string): boolean {
-    return this.groupStates.has(groupId);
+    return bytesToBase64(bytes);
   }
 
   async leaveGroup(groupId: string): Promise<void> {
     this.groupStates.delete(groupId);
+    this.stagedCommits.delete(groupId);
+    this.discardPendingCommit(groupId);
+    this.quarantined.delete(groupId);
     await this.storage.deleteGroupState(groupId);
   }
 
-  private requireCredential(): MlsCredential {
-    if (!this.credential) {
-      throw new Error(
-        'No credential available. Call generateCredential first.'
-      );
-    }
-    return this.credential;
-  }
-
-  private assertBackendReady(): void {
-    if (!this.backendStatus.productionReady) {
-      throw new Error(
-        `MLS Rust/WASM backend is not ready: ${this.backendStatus.reason}`
-      );
-    }
-  }
+  /**
+   * Emits a self-remove proposal and drops the group locally right away.
+   * Remaining members keep us listed until one of them commits the proposal.
+   */
+  async forceLeave(groupId: string): Promise<Uint8Array> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
 
-  private requireGroupState(groupId: string): GroupState {
-    const groupState = this.groupStates.get(groupId);
-    if (!groupState) {
-      throw new Error(`Group not found: ${groupId}`);
-    }
-    return groupState;
+    const result = await wasmForceLeave(groupState.serialized);
+    await this.leaveGroup(groupId);
+    return result.proposal;
   }
 
-  private async installSerializedGroupState(
-    groupId: string,
-    serializedState: Uint8Array,
-    persist: boolean
-  ): Promise<void> {
-    const metadata = await wasmGroupStateMetadata(serializedState);
-    if (metadata.groupId !== groupId) {
-      throw new Error(
-        `Serialized state group mismatch: expected ${groupId}, got ${metadata.groupId}`
-      );
+  private requireStagedCommit(groupId: string): StagedCommit {
+    const staged = this.stagedCommits.get(groupId);
+    if (!staged) {
+      throw new Error(`No staged commit for group: ${groupId}`);
     }
-
-    const groupState: GroupState = {
-      groupId,
-      epoch: metadata.epoch,
-      members: membersToLeafIndexMap(metadata),
-      serialized: serializedState
-    };
-
-    this.groupStates.set(groupId, groupState);
-
-    if (persist) {
-      const localState: LocalMlsState = {
-        groupId,
-        serializedState,
-        epoch: metadata.epoch,
-        updatedAt: Date.now()
-      };
-      await this.storage.saveGroupState(localState);
-    }
-  }
-
-  private bytesToBase64(bytes: Uint8Array): string {
-    return btoa(String.fromCharCode.apply(null, Array.from(bytes)));
+    return staged;
   }
 
-  close(): void {
-    this.storage.close();
-    this.groupStates.clear();
-    this.credential = null;
+  override close(): void {
+    this.stagedCommits.clear();
+    super.close();
   }
 }
 
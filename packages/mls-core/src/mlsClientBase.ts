@@ -0,0 +1,424 @@
+import { CredentialDeletedError } from './errors.js';
+import {
+  assertKeyPackagesAccepted,
+  assertMembersAccepted,
+  type IdentityValidator
+} from './identityValidation.js';
+import {
+  type MlsBackendStatus,
+  resolveMlsBackendStatus
+} from './mlsWasmBackend.js';
+import {
+  wasmGroupStateMetadata,
+  wasmKeyPackageExpired,
+  wasmSignBlob,
+  wasmVerifyBlob
+} from './mlsWasmBridge.js';
+import {
+  wasmDeleteSigningKey,
+  wasmMarkReadOnly,
+  wasmPendingProposalCount,
+  wasmValidateGroupState
+} from './mlsWasmState.js';
+import { MlsStorage } from './storage.js';
+import type {
+  GroupStateReport,
+  LocalMlsState,
+  MessageCounters,
+  MlsCredential
+} from './types.js';
+
+export interface GroupState {
+  groupId: string;
+  epoch: number;
+  members: Map<string, number>; // userId -> leafIndex
+  serialized: Uint8Array;
+  counters: MessageCounters;
+  /** Set once `deleteSignatureKeys` erased the state's signing key. */
+  signingKeyDeleted: boolean;
+}
+
+export function membersToLeafIndexMap(metadata: {
+  members: { userId: string; leafIndex: number }[];
+}): Map<string, number> {
+  const map = new Map<string, number>();
+  for (const member of metadata.members) {
+    map.set(member.userId, member.leafIndex);
+  }
+  return map;
+}
+
+/** Receives the group id and new epoch after a loaded group advances. */
+export type EpochChangeListener = (groupId: string, epoch: number) => void;
+
+/** Holds loaded group states and credentials; `MlsClient` adds the operations. */
+export abstract class MlsClientBase {
+  protected storage: MlsStorage;
+  protected groupStates: Map<string, GroupState> = new Map();
+  /** States set aside because they failed to load; kept for inspection. */
+  protected quarantined: Map<string, Uint8Array> = new Map();
+  private epochChangeListener: EpochChangeListener | null = null;
+  private identityValidator: IdentityValidator | null = null;
+  protected credential: MlsCredential | null = null;
+  private signatureKeysDeleted = false;
+  protected userId: string;
+  protected readonly observer: boolean;
+  protected backendStatus: MlsBackendStatus = {
+    backend: 'placeholder',
+    wasmModuleLoaded: false,
+    backendName: 'typescript-placeholder',
+    backendVersion: null,
+    productionReady: false,
+    reason: 'MLS backend status not initialized yet.'
+  };
+
+  /**
+   * Pass `storage.withNamespace(...)` to let several identities share one
+   * database without their credentials or group states colliding.
+   *
+   * An `observer` joins groups as a regular member but the WASM layer refuses
+   * to let it encrypt or commit; decrypting and processing work as usual.
+   */
+  constructor(
+    userId: string,
+    storage?: MlsStorage,
+    options: { observer?: boolean } = {}
+  ) {
+    this.userId = userId;
+    this.storage = storage ?? new MlsStorage();
+    this.observer = options.observer ?? false;
+  }
+
+  /**
+   * Registers a listener for epoch transitions of loaded groups, so
+   * app-derived exporter keys can be re-derived as soon as they go stale.
+   * Fires once per installed state, after it is persisted, so a `catchUp`
+   * across several epochs fires once with the final epoch; loading groups at
+   * `init` or creating them does not fire. Pass `null` to stop.
+   * A listener that throws is logged and does not fail the operation.
+   */
+  onEpochChange(listener: EpochChangeListener | null): void {
+    this.epochChangeListener = listener;
+  }
+
+  /**
+   * Installs a trust check for each key package passed to `addMember`,
+   * `addMembers`, `addMembersStaged`, `addMembersBundle` or `replaceMember`,
+   * and for every existing member of a group being joined. A rejection
+   * aborts the operation with `IdentityRejectedError` before any state
+   * changes. Pass `null` to remove.
+   */
+  setIdentityValidator(validator: IdentityValidator | null): void {
+    this.identityValidator = validator;
+  }
+
+  async init(): Promise<void> {
+    await this.storage.init();
+
+    this.backendStatus = await resolveMlsBackendStatus();
+
+    if (!this.backendStatus.productionReady) {
+      console.warn(`[mls-core] ${this.backendStatus.reason}`);
+    }
+
+    const storedCredential = await this.storage.getCredential(this.userId);
+    if (storedCredential) {
+      this.credential = storedCredential;
+    }
+
+    const groupStates = await this.storage.getAllGroupStates();
+    for (const state of groupStates) {
+      if (state.quarantined) {
+        this.quarantined.set(state.groupId, state.serializedState);
+        continue;
+      }
+      try {
+        await this.installSerializedGroupState(
+          state.groupId,
+          state.serializedState,
+          true,
+          state.messageCounters
+        );
+      } catch {
+        this.quarantined.set(state.groupId, state.serializedState);
+        await this.storage.saveGroupState({ ...state, quarantined: true });
+      }
+    }
+  }
+
+  getGroupEpoch(groupId: string): number | undefined {
+    return this.groupStates.get(groupId)?.epoch;
+  }
+
+  getBackendStatus(): MlsBackendStatus {
+    return this.backendStatus;
+  }
+
+  hasGroup(groupId: string): boolean {
+    return this.groupStates.has(groupId);
+  }
+
+  /**
+   * Checks a loaded or quarantined group's state for corruption. An invalid
+   * report carries `resyncRequired`: quarantine the group and rejoin it
+   * through a fresh welcome from another member. A valid report may carry
+   * `upgradeRequired`; see the README's upgrade notes.
+   */
+  async validateGroupState(groupId: string): Promise<GroupStateReport> {
+    this.assertBackendReady();
+    const state =
+      this.groupStates.get(groupId)?.serialized ??
+      this.quarantined.get(groupId);
+    if (!state) {
+      throw new Error(`Group not found: ${groupId}`);
+    }
+    return wasmValidateGroupState(groupId, state);
+  }
+
+  /**
+   * Sets a group aside so its corrupted state no longer blocks other groups.
+   * The stored record is flagged rather than deleted, so the bytes survive a
+   * restart for `validateGroupState`; rejoining overwrites it and
+   * `leaveGroup` deletes it.
+   */
+  async quarantineGroup(groupId: string): Promise<void> {
+    const groupState = this.requireGroupState(groupId);
+    this.groupStates.delete(groupId);
+    this.quarantined.set(groupId, groupState.serialized);
+    await this.persistGroupState(groupState, true);
+  }
+
+  listQuarantinedGroups(): string[] {
+    return [...this.quarantined.keys()];
+  }
+
+  /** Counts stored proposals that expire unless committed this epoch. */
+  async pendingProposalCount(groupId: string): Promise<number> {
+    this.assertBackendReady();
+    return wasmPendingProposalCount(this.requireGroupState(groupId).serialized);
+  }
+
+  async commitNeeded(groupId: string): Promise<boolean> {
+    return (await this.pendingProposalCount(groupId)) > 0;
+  }
+
+  hasSignatureKeys(): boolean {
+    return this.credential !== null;
+  }
+
+  /**
+   * Zeroizes the credential private key and erases the signing key copy held
+   * by every group state, e.g. on logout. Groups stay readable, but anything
+   * that signs throws `CredentialDeletedError` until the group is rejoined
+   * with a new credential. Quarantined states may not decode, so they are
+   * deleted outright.
+   */
+  async deleteSignatureKeys(): Promise<void> {
+    this.credential?.privateKey.fill(0);
+    this.credential = null;
+    this.signatureKeysDeleted = true;
+    await this.storage.deleteCredential(this.userId);
+
+    for (const groupState of [...this.groupStates.values()]) {
+      const stripped = await wasmDeleteSigningKey(groupState.serialized);
+      groupState.serialized.fill(0);
+      await this.installSerializedGroupState(
+        groupState.groupId,
+        stripped,
+        true,
+        groupState.counters
+      );
+    }
+    for (const [groupId, state] of [...this.quarantined]) {
+      state.fill(0);
+      this.quarantined.delete(groupId);
+      await this.storage.deleteGroupState(groupId);
+    }
+  }
+
+  /** Signs non-MLS data with the credential key peers see as our leaf key. */
+  async signBlob(data: Uint8Array): Promise<Uint8Array> {
+    this.assertBackendReady();
+    return wasmSignBlob(this.requireCredential().privateKey, data);
+  }
+
+  async verifyBlob(
+    signingPublicKey: Uint8Array,
+    data: Uint8Array,
+    signature: Uint8Array
+  ): Promise<boolean> {
+    this.assertBackendReady();
+    return wasmVerifyBlob(signingPublicKey, data, signature);
+  }
+
+  /**
+   * Returns refs of the key packages this client can still join with, for
+   * reconciling against the server's published pool.
+   */
+  async listStoredKeyPackageRefs(): Promise<string[]> {
+    return this.storage.listKeyPackageRefs();
+  }
+
+  /**
+   * Deletes the private material of stored key packages whose lifetime ended
+   * before `nowSeconds` and returns how many were removed.
+   */
+  async pruneExpiredKeyPackages(nowSeconds: number): Promise<number> {
+    this.assertBackendReady();
+    let pruned = 0;
+    for (const ref of await this.storage.listKeyPackageRefs()) {
+      const stored = await this.storage.getKeyPackage(ref);
+      if (
+        stored &&
+        (await wasmKeyPackageExpired(stored.keyPackage, nowSeconds))
+      ) {
+        await this.storage.deleteKeyPackage(ref);
+        pruned += 1;
+      }
+    }
+    return pruned;
+  }
+
+  protected requireCredential(): MlsCredential {
+    if (!this.credential && this.signatureKeysDeleted) {
+      throw new CredentialDeletedError(null);
+    }
+    if (!this.credential) {
+      throw new Error(
+        'No credential available. Call generateCredential first.'
+      );
+    }
+    return this.credential;
+  }
+
+  protected assertBackendReady(): void {
+    if (!this.backendStatus.productionReady) {
+      throw new Error(
+        `MLS Rust/WASM backend is not ready: ${this.backendStatus.reason}`
+      );
+    }
+  }
+
+  protected requireGroupState(groupId: string): GroupState {
+    const groupState = this.groupStates.get(groupId);
+    if (!groupState) {
+      throw new Error(`Group not found: ${groupId}`);
+    }
+    return groupState;
+  }
+
+  /** Like `requireGroupState`, for operations that sign with the group key. */
+  protected requireSigningGroupState(groupId: string): GroupState {
+    const groupState = this.requireGroupState(groupId);
+    if (groupState.signingKeyDeleted) {
+      throw new CredentialDeletedError(groupId);
+    }
+    return groupState;
+  }
+
+  protected async assertKeyPackagesAccepted(
+    keyPackages: Uint8Array[]
+  ): Promise<void> {
+    if (this.identityValidator) {
+      await assertKeyPackagesAccepted(this.identityValidator, keyPackages);
+    }
+  }
+
+  protected async assertMembersAccepted(
+    stateBytes: Uint8Array,
+    selfUserId: string
+  ): Promise<void> {
+    if (this.identityValidator) {
+      await assertMembersAccepted(
+        this.identityValidator,
+        stateBytes,
+        selfUserId
+      );
+    }
+  }
+
+  protected async installSerializedGroupState(
+    groupId: string,
+    serializedState: Uint8Array,
+    persist: boolean,
+    storedCounters?: MessageCounters
+  ): Promise<void> {
+    const state = this.observer
+      ? await wasmMarkReadOnly(serializedState)
+      : serializedState;
+    const metadata = await wasmGroupStateMetadata(state);
+    if (metadata.groupId !== groupId) {
+      throw new Error(
+        `Serialized state group mismatch: expected ${groupId}, got ${metadata.groupId}`
+      );
+    }
+
+    const previousEpoch = this.groupStates.get(groupId)?.epoch;
+    // Counters are per epoch: carry them over only while the epoch is unchanged.
+    const previous = storedCounters ?? this.groupStates.get(groupId)?.counters;
+    const counters =
+      previous?.currentEpoch === metadata.epoch
+        ? { ...previous }
+        : { sent: 0, received: 0, currentEpoch: metadata.epoch };
+
+    const groupState: GroupState = {
+      groupId,
+      epoch: metadata.epoch,
+      members: membersToLeafIndexMap(metadata),
+      serialized: state,
+      counters,
+      signingKeyDeleted: metadata.signingKeyDeleted
+    };
+
+    this.groupStates.set(groupId, groupState);
+    this.quarantined.delete(groupId);
+
+    if (persist) {
+      await this.persistGroupState(groupState);
+    }
+    if (previousEpoch !== undefined && previousEpoch !== metadata.epoch) {
+      this.notifyEpochChange(groupId, metadata.epoch);
+    }
+  }
+
+  private notifyEpochChange(groupId: string, epoch: number): void {
+    try {
+      this.epochChangeListener?.(groupId, epoch);
+    } catch (error) {
+      console.warn(`[mls-core] Epoch change listener failed: ${error}`);
+    }
+  }
+
+  protected async recordMessage(
+    groupState: GroupState,
+    direction: 'sent' | 'received'
+  ): Promise<void> {
+    groupState.counters[direction] += 1;
+    await this.persistGroupState(groupState);
+  }
+
+  private async persistGroupState(
+    groupState: GroupState,
+    quarantined = false
+  ): Promise<void> {
+    const localState: LocalMlsState = {
+      groupId: groupState.groupId,
+      serializedState: groupState.serialized,
+      epoch: groupState.epoch,
+      updatedAt: Date.now(),
+      messageCounters: { ...groupState.counters }
+    };
+    await this.storage.saveGroupState(
+      quarantined ? { ...localState, quarantined } : localState
+    );
+  }
+
+  close(): void {
+    this.storage.close();
+    this.groupStates.clear();
+    this.quarantined.clear();
+    this.epochChangeListener = null;
+    this.identityValidator = null;
+    this.credential = null;
+  }
+}
@@ -0,0 +1,93 @@
+import { MlsGroupAdminClient } from './mlsClientGroupAdmin.js';
+import {
+  wasmCommitPsk,
+  wasmCommitReceivedProposals,
+  wasmSelfUpdate,
+  wasmShouldRekey,
+  toAddMembersResult,
+  wasmStoreProposal
+} from './mlsWasmCommits.js';
+import type {
+  AddMembersResult,
+  CommitResult,
+  PskReference
+} from './types.js';
+
+/** Commits that change no membership: key refreshes, PSKs and proposals. */
+export abstract class MlsCommitClient extends MlsGroupAdminClient {
+  /**
+   * Returns true once more than `maxEpochs` epochs passed since our last
+   * `selfUpdate`, so a background task can rekey for post-compromise security.
+   * The last-update epoch is persisted with the group state.
+   */
+  async shouldRekey(groupId: string, maxEpochs: number): Promise<boolean> {
+    this.assertBackendReady();
+    if (!Number.isInteger(maxEpochs) || maxEpochs < 0) {
+      throw new Error(`Invalid maximum epoch count: ${maxEpochs}`);
+    }
+    return wasmShouldRekey(
+      this.requireGroupState(groupId).serialized,
+      maxEpochs
+    );
+  }
+
+  /** Rotates our leaf key in a new epoch; send the commit to the group. */
+  async selfUpdate(groupId: string): Promise<CommitResult> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+
+    const result = await wasmSelfUpdate(groupState.serialized);
+    await this.installSerializedGroupState(groupId, result.state, true);
+
+    return { commit: result.commit, newEpoch: result.newEpoch };
+  }
+
+  /**
+   * Commits a PSK so the next epoch secret depends on it. Every member must
+   * hold the same external PSK, or `processCommit` fails for them.
+   */
+  async commitPsk(groupId: string, psk: PskReference): Promise<CommitResult> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+
+    const result = await wasmCommitPsk(groupState.serialized, psk);
+    await this.installSerializedGroupState(groupId, result.state, true);
+
+    return { commit: result.commit, newEpoch: result.newEpoch };
+  }
+
+  /**
+   * Verifies a proposal received from another member or an external sender
+   * and keeps it pending for the current epoch. Returns the ref to pass to
+   * `commitReceivedProposals`; pending proposals expire with the epoch.
+   */
+  async storeProposal(groupId: string, proposal: Uint8Array): Promise<string> {
+    this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
+
+    const result = await wasmStoreProposal(groupState.serialized, proposal);
+    await this.installSerializedGroupState(groupId, result.state, true);
+    return result.proposalRef;
+  }
+
+  /**
+   * Commits only the pending proposals named by `proposalRefs`. When they add
+   * members, route each entry of `welcomes` to its key package's owner;
+   * `pendingProposalCount` reports whether any proposals are still waiting.
+   */
+  async commitReceivedProposals(
+    groupId: string,
+    proposalRefs: string[]
+  ): Promise<AddMembersResult> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+
+    const result = await wasmCommitReceivedProposals(
+      groupState.serialized,
+      proposalRefs
+    );
+    await this.installSerializedGroupState(groupId, result.state, true);
+
+    return toAddMembersResult(result);
+  }
+}
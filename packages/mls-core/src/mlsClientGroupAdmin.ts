@@ -0,0 +1,130 @@
+import { MlsSecretsClient } from './mlsClientSecrets.js';
+import {
+  wasmGroupContext,
+  wasmUpdateGroupContext
+} from './mlsWasmGroupContext.js';
+import {
+  wasmEpochsBehind,
+  wasmGroupMetadata,
+  wasmGroupPolicy,
+  wasmSetGroupMetadata,
+  wasmSetGroupPolicy,
+  wasmSyncDigest
+} from './mlsWasmState.js';
+import type {
+  CommitResult,
+  GroupContextChange,
+  GroupPolicy
+} from './types.js';
+
+/**
+ * Group settings: the shared context committed to every member, local
+ * metadata kept only on this device, and checks against other members' views.
+ */
+export abstract class MlsGroupAdminClient extends MlsSecretsClient {
+  async getGroupContext(groupId: string): Promise<GroupContextChange> {
+    this.assertBackendReady();
+    return wasmGroupContext(this.requireGroupState(groupId).serialized);
+  }
+
+  /**
+   * Commits a new group context, e.g. `{ name }` to rename the group. Fields
+   * left out keep their current value; other members see the result from
+   * `processCommit`.
+   */
+  async updateGroupContext(
+    groupId: string,
+    change: Partial<GroupContextChange>
+  ): Promise<CommitResult> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+    const current = await wasmGroupContext(groupState.serialized);
+
+    const result = await wasmUpdateGroupContext(groupState.serialized, {
+      ...current,
+      ...change
+    });
+    await this.installSerializedGroupState(groupId, result.state, true);
+
+    return { commit: result.commit, newEpoch: result.newEpoch };
+  }
+
+  /**
+   * Stores a local-only note on the group, e.g. a draft or unread marker, or
+   * clears it when `value` is null. It is persisted with the group state but
+   * never sent to other members. Keys and values are bounded in size.
+   */
+  async setGroupMetadata(
+    groupId: string,
+    key: string,
+    value: string | null
+  ): Promise<void> {
+    this.assertBackendReady();
+    const state = await wasmSetGroupMetadata(
+      this.requireGroupState(groupId).serialized,
+      key,
+      value
+    );
+    await this.installSerializedGroupState(groupId, state, true);
+  }
+
+  async getGroupMetadata(groupId: string): Promise<Map<string, string>> {
+    this.assertBackendReady();
+    return wasmGroupMetadata(this.requireGroupState(groupId).serialized);
+  }
+
+  async getGroupPolicy(groupId: string): Promise<GroupPolicy> {
+    this.assertBackendReady();
+    return wasmGroupPolicy(this.requireGroupState(groupId).serialized);
+  }
+
+  /**
+   * Updates this device's commit policy for the group, e.g.
+   * `{ rekeyOnRemove: true }`. Fields left out keep their current value.
+   */
+  async setGroupPolicy(
+    groupId: string,
+    change: Partial<GroupPolicy>
+  ): Promise<void> {
+    this.assertBackendReady();
+    if (
+      change.minMembers !== undefined &&
+      (!Number.isSafeInteger(change.minMembers) || change.minMembers < 0)
+    ) {
+      throw new Error(`Invalid minimum member count: ${change.minMembers}`);
+    }
+    const groupState = this.requireGroupState(groupId);
+    const current = await wasmGroupPolicy(groupState.serialized);
+
+    const state = await wasmSetGroupPolicy(groupState.serialized, {
+      ...current,
+      ...change
+    });
+    await this.installSerializedGroupState(groupId, state, true);
+  }
+
+  /**
+   * Returns how many commits to fetch to reach `remoteEpoch`, e.g. the epoch
+   * of an incoming message after reconnecting; negative when we are ahead.
+   */
+  async epochsBehind(groupId: string, remoteEpoch: number): Promise<number> {
+    this.assertBackendReady();
+    if (!Number.isSafeInteger(remoteEpoch) || remoteEpoch < 0) {
+      throw new Error(`Invalid remote epoch: ${remoteEpoch}`);
+    }
+    return wasmEpochsBehind(
+      this.requireGroupState(groupId).serialized,
+      remoteEpoch
+    );
+  }
+
+  /**
+   * Digests the epoch, member tree and confirmed transcript. Two members in
+   * the same group state get equal digests; any divergence changes it, so
+   * comparing digests answers "are we actually in sync?" in one call.
+   */
+  async syncDigest(groupId: string): Promise<Uint8Array> {
+    this.assertBackendReady();
+    return wasmSyncDigest(this.requireGroupState(groupId).serialized);
+  }
+}
@@ -0,0 +1,45 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { MlsClient } from './mls.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('MlsClient.newWithCredential', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+  });
+
+  it('builds a client under the user id derived from the identity', async () => {
+    const fromIdentity = vi.fn((_identity: Uint8Array) => ({
+      user_id: 'hex:deadbeef',
+      credential_bundle: [1],
+      private_key: [2],
+      created_at_ms: 7
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_generate_credential_from_identity: fromIdentity
+      })
+    );
+    const storage = new MemoryMlsStorage();
+    const identity = Uint8Array.from([0xde, 0xad, 0xbe, 0xef]);
+
+    const client = await MlsClient.newWithCredential(identity, storage);
+
+    expect(fromIdentity).toHaveBeenCalledWith(identity);
+    expect(client.hasSignatureKeys()).toBe(true);
+    expect(storage.credentials.get('hex:deadbeef')).toEqual({
+      credentialBundle: Uint8Array.from([1]),
+      privateKey: Uint8Array.from([2]),
+      userId: 'hex:deadbeef',
+      createdAt: 7
+    });
+    client.close();
+  });
+});
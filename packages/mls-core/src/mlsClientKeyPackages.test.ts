@@ -0,0 +1,90 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { bytesToBase64 } from './base64.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('MlsClient key packages', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+  });
+
+  it('stores a last-resort key package flagged for reuse', async () => {
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+
+    const ordinary = await client.generateKeyPackage();
+    const lastResort = await client.generateLastResortKeyPackage();
+
+    expect(lastResort).toEqual({
+      ref: 'mock-last-resort-ref',
+      keyPackageBytes: Uint8Array.from([3])
+    });
+    expect(storage.keyPackages.get(lastResort.ref)?.lastResort).toBe(true);
+    expect(storage.keyPackages.get(ordinary.ref)?.lastResort).toBeUndefined();
+    client.close();
+  });
+
+  it('matches a welcome against the stored key package refs', async () => {
+    const match = vi.fn((_welcome: Uint8Array, refs: string[]) => refs[0]);
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_match_welcome_key_package: match })
+    );
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+    const welcome = Uint8Array.from([9, 9]);
+
+    await expect(
+      client.matchWelcomeToStoredPackage(bytesToBase64(welcome))
+    ).resolves.toBeNull();
+
+    const { ref } = await client.generateKeyPackage();
+    await expect(
+      client.matchWelcomeToStoredPackage(bytesToBase64(welcome))
+    ).resolves.toBe(ref);
+    expect(match).toHaveBeenLastCalledWith(welcome, [ref]);
+    client.close();
+  });
+
+  it('prunes only the expired key packages and counts them', async () => {
+    const expired = vi.fn(
+      (keyPackage: Uint8Array, _nowSeconds: bigint) => keyPackage[0] === 1
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_key_package_expired: expired })
+    );
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+    const packages: [string, number][] = [
+      ['expired-ref', 1],
+      ['valid-ref', 2]
+    ];
+    for (const [ref, byte] of packages) {
+      await storage.saveKeyPackage({
+        ref,
+        keyPackage: Uint8Array.from([byte]),
+        privateKey: Uint8Array.from([byte]),
+        createdAt: 0
+      });
+    }
+    const deleteKeyPackage = vi.spyOn(storage, 'deleteKeyPackage');
+
+    await expect(client.pruneExpiredKeyPackages(1_000)).resolves.toBe(1);
+
+    expect(deleteKeyPackage).toHaveBeenCalledTimes(1);
+    expect(deleteKeyPackage).toHaveBeenCalledWith('expired-ref');
+    expect([...storage.keyPackages.keys()]).toEqual(['valid-ref']);
+    expect(expired).toHaveBeenCalledWith(Uint8Array.from([1]), 1_000n);
+    client.close();
+  });
+});
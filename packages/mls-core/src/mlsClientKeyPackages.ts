@@ -0,0 +1,76 @@
+import { base64ToBytes } from './base64.js';
+import { MlsStateTransferClient } from './mlsClientStateTransfer.js';
+import {
+  type GeneratedKeyPackage,
+  wasmGenerateKeyPackage
+} from './mlsWasmBridge.js';
+import {
+  wasmGenerateLastResortKeyPackage,
+  wasmMatchWelcomeKeyPackage
+} from './mlsWasmKeyPackages.js';
+import type { KeyPackageWithRef, LocalKeyPackage } from './types.js';
+
+/** Key packages published so others can add this client to groups. */
+export abstract class MlsKeyPackageClient extends MlsStateTransferClient {
+  async generateKeyPackage(): Promise<KeyPackageWithRef> {
+    this.assertBackendReady();
+    const credential = this.requireCredential();
+
+    const result = await wasmGenerateKeyPackage(
+      credential.credentialBundle,
+      credential.privateKey
+    );
+    return this.storeKeyPackage(result, false);
+  }
+
+  /**
+   * Generates a key package that survives being used, to publish when the
+   * pool of ordinary ones may run dry. `joinGroup` keeps its private key, so
+   * it serves every add until it expires or is pruned.
+   */
+  async generateLastResortKeyPackage(): Promise<KeyPackageWithRef> {
+    this.assertBackendReady();
+    const credential = this.requireCredential();
+
+    const result = await wasmGenerateLastResortKeyPackage(
+      credential.credentialBundle,
+      credential.privateKey
+    );
+    return this.storeKeyPackage(result, true);
+  }
+
+  /**
+   * Returns the ref of the stored key package a base64 welcome was sealed
+   * to, or null when none is held, so the right `keyPackageRef` can be passed
+   * to `joinGroup` without trying each one.
+   */
+  async matchWelcomeToStoredPackage(
+    welcomeB64: string
+  ): Promise<string | null> {
+    this.assertBackendReady();
+    return wasmMatchWelcomeKeyPackage(
+      base64ToBytes(welcomeB64, 'Welcome'),
+      await this.storage.listKeyPackageRefs()
+    );
+  }
+
+  private async storeKeyPackage(
+    result: GeneratedKeyPackage,
+    lastResort: boolean
+  ): Promise<KeyPackageWithRef> {
+    const localKeyPackage: LocalKeyPackage = {
+      ref: result.keyPackageRef,
+      keyPackage: result.keyPackage,
+      privateKey: result.privateKey,
+      createdAt: result.createdAtMs,
+      ...(lastResort ? { lastResort } : {})
+    };
+
+    await this.storage.saveKeyPackage(localKeyPackage);
+
+    return {
+      ref: result.keyPackageRef,
+      keyPackageBytes: result.keyPackage
+    };
+  }
+}
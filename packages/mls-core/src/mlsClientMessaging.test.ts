@@ -0,0 +1,148 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { ResyncRequiredError } from './errors.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+// The mocked group sits at epoch 1; the first ciphertext byte is the
+// message epoch.
+function futureEpochMessage(_state: Uint8Array, ciphertext: Uint8Array) {
+  return {
+    status: 'future_epoch',
+    epoch: ciphertext[0] ?? 0,
+    sender_id: 'bob',
+    sender_signature_key: Uint8Array.from([]),
+    authenticated: false,
+    plaintext: Uint8Array.from([]),
+    authenticated_data: Uint8Array.from([]),
+    framing: Uint8Array.from([])
+  };
+}
+
+describe('MlsClient epoch tolerance', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_decrypt_message: futureEpochMessage
+      })
+    );
+  });
+
+  it('buffers a message at the tolerance and requires resync beyond it', async () => {
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('mock-group');
+
+    for (const tolerance of [0, 1, 3]) {
+      client.setEpochTolerance(tolerance);
+
+      const atLimit = await client.decryptMessage(
+        'mock-group',
+        Uint8Array.from([1 + tolerance])
+      );
+      expect(atLimit.status).toBe('future_epoch');
+      expect(atLimit.epoch).toBe(1 + tolerance);
+
+      const beyond = client.decryptMessage(
+        'mock-group',
+        Uint8Array.from([2 + tolerance])
+      );
+      await expect(beyond).rejects.toBeInstanceOf(ResyncRequiredError);
+      await expect(beyond).rejects.toMatchObject({
+        groupId: 'mock-group',
+        localEpoch: 1,
+        messageEpoch: 2 + tolerance
+      });
+    }
+    client.close();
+  });
+});
+
+describe('MlsClient receiving', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+  });
+
+  it('coalesces a multi-epoch catch-up into one epoch change', async () => {
+    // The first state byte stands in for the epoch.
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_catch_up: () => ({
+          state: [4],
+          epoch: 4,
+          messages: [],
+          pending: []
+        }),
+        mls_group_state_metadata: (state: Uint8Array) => ({
+          group_id: 'mock-group',
+          epoch: state[0] ?? 0,
+          self_user_id: 'alice',
+          members: [],
+          group_context: {
+            name: null,
+            external_senders: [],
+            ratchet_tree_out_of_band: false
+          }
+        })
+      })
+    );
+    const listener = vi.fn((_groupId: string, _epoch: number) => {});
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('mock-group');
+    client.onEpochChange(listener);
+
+    const commits = [2, 3, 4].map((epoch) => Uint8Array.from([epoch]));
+    const result = await client.catchUp('mock-group', commits);
+
+    expect(result.epoch).toBe(4);
+    expect(listener.mock.calls).toEqual([['mock-group', 4]]);
+    client.close();
+  });
+
+  it('counts decrypted messages but not ones from senders outside the group', async () => {
+    const decrypt = vi.fn((_state: Uint8Array, ciphertext: Uint8Array) => ({
+      status: ciphertext[0] === 0 ? 'sender_not_in_group' : 'decrypted',
+      epoch: 1,
+      sender_id: 'bob',
+      sender_signature_key: Uint8Array.from([]),
+      authenticated: ciphertext[0] !== 0,
+      plaintext: Uint8Array.from([]),
+      authenticated_data: Uint8Array.from([]),
+      framing: Uint8Array.from([])
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_decrypt_message: decrypt
+      })
+    );
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('mock-group');
+
+    const orphan = await client.decryptMessage(
+      'mock-group',
+      Uint8Array.from([0])
+    );
+    await client.decryptMessage('mock-group', Uint8Array.from([1]));
+
+    expect(orphan.status).toBe('sender_not_in_group');
+    expect(client.getMessageCounters('mock-group')).toEqual({
+      sent: 0,
+      received: 1,
+      currentEpoch: 1
+    });
+    client.close();
+  });
+});
@@ -0,0 +1,195 @@
+import { MessageTooLargeError, ResyncRequiredError } from './errors.js';
+import { type GroupState, MlsClientBase } from './mlsClientBase.js';
+import {
+  wasmCatchUp,
+  wasmDecryptMessage,
+  wasmEncryptMessage,
+  wasmReencryptHistory
+} from './mlsWasmBridge.js';
+import { wasmDecryptMessageInto } from './mlsWasmMessages.js';
+import type {
+  CatchUpResult,
+  DecryptedContent,
+  DecryptedIntoContent,
+  MessageCounters
+} from './types.js';
+
+/** Application-message operations layered over the loaded group states. */
+export abstract class MlsMessagingClient extends MlsClientBase {
+  private maxMessageSize: number | null = null;
+  private epochTolerance = 1;
+
+  /**
+   * Caps plaintext size for `encryptMessage` so untrusted input cannot bloat
+   * WASM memory. Pass `null` to remove the limit, which is the default.
+   */
+  setMaxMessageSize(bytes: number | null): void {
+    if (bytes !== null && (!Number.isInteger(bytes) || bytes < 0)) {
+      throw new Error(`Invalid maximum message size: ${bytes}`);
+    }
+    this.maxMessageSize = bytes;
+  }
+
+  /**
+   * Sets how many epochs ahead of the local state a message may be before
+   * `decryptMessage` throws `ResyncRequiredError` instead of reporting
+   * `future_epoch` for buffering. Defaults to 1, covering a commit in flight.
+   */
+  setEpochTolerance(epochs: number): void {
+    if (!Number.isInteger(epochs) || epochs < 0) {
+      throw new Error(`Invalid epoch tolerance: ${epochs}`);
+    }
+    this.epochTolerance = epochs;
+  }
+
+  async encryptMessage(
+    groupId: string,
+    plaintext: Uint8Array
+  ): Promise<Uint8Array> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+    this.assertWithinMaxMessageSize(plaintext);
+
+    const ciphertext = await wasmEncryptMessage(
+      groupState.serialized,
+      plaintext
+    );
+    await this.recordMessage(groupState, 'sent');
+    return ciphertext;
+  }
+
+  async decryptMessage(
+    groupId: string,
+    ciphertext: Uint8Array,
+    options: { includeFraming?: boolean } = {}
+  ): Promise<DecryptedContent> {
+    this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
+
+    const result = await wasmDecryptMessage(
+      groupState.serialized,
+      ciphertext,
+      options.includeFraming ?? false
+    );
+    await this.acceptDecryptResult(groupId, groupState, result);
+
+    return {
+      status: result.status,
+      epoch: result.epoch,
+      senderId: result.senderId,
+      senderSignatureKey: result.senderSignatureKey,
+      authenticated: result.authenticated,
+      plaintext: result.plaintext,
+      authenticatedData: result.authenticatedData,
+      framing: result.framing
+    };
+  }
+
+  /**
+   * Like `decryptMessage`, but writes the plaintext into `out` and returns its
+   * length, so replaying long histories can reuse one buffer. Fails with the
+   * required size when `out` is too small.
+   */
+  async decryptInto(
+    groupId: string,
+    ciphertext: Uint8Array,
+    out: Uint8Array
+  ): Promise<DecryptedIntoContent> {
+    this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
+
+    const result = await wasmDecryptMessageInto(
+      groupState.serialized,
+      ciphertext,
+      out
+    );
+    await this.acceptDecryptResult(groupId, groupState, result);
+    return result;
+  }
+
+  /**
+   * Replays missed commits and application messages in delivery order,
+   * retrying messages that arrived ahead of their commit once the group
+   * reaches their epoch, then installs the caught-up state. The epoch change
+   * listener fires once, with the final epoch, however many commits replayed.
+   */
+  async catchUp(groupId: string, items: Uint8Array[]): Promise<CatchUpResult> {
+    this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
+
+    const result = await wasmCatchUp(groupState.serialized, items);
+    await this.installSerializedGroupState(groupId, result.state, true);
+    const caughtUp = this.requireGroupState(groupId);
+    for (const message of result.messages) {
+      if (message.status === 'decrypted' && message.epoch === caughtUp.epoch) {
+        await this.recordMessage(caughtUp, 'received');
+      }
+    }
+
+    return {
+      epoch: result.epoch,
+      messages: result.messages,
+      pending: result.pending
+    };
+  }
+
+  /**
+   * Returns application messages sent and received in the current epoch.
+   * Counts reset whenever the group moves to a new epoch, survive reloads,
+   * and skip relay echoes of our own messages and `sender_not_in_group`
+   * messages.
+   */
+  getMessageCounters(groupId: string): MessageCounters {
+    return { ...this.requireGroupState(groupId).counters };
+  }
+
+  /**
+   * Re-encrypts chosen past plaintexts as current-epoch messages so members
+   * who joined later can read them. This deliberately exposes the selected
+   * history to everyone in the group now; nothing is shared automatically.
+   */
+  async reencryptHistory(
+    groupId: string,
+    plaintexts: Uint8Array[]
+  ): Promise<Uint8Array[]> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+    for (const plaintext of plaintexts) {
+      this.assertWithinMaxMessageSize(plaintext);
+    }
+
+    const messages = await wasmReencryptHistory(
+      groupState.serialized,
+      plaintexts
+    );
+    for (let index = 0; index < messages.length; index += 1) {
+      await this.recordMessage(groupState, 'sent');
+    }
+    return messages;
+  }
+
+  private async acceptDecryptResult(
+    groupId: string,
+    groupState: GroupState,
+    result: Pick<DecryptedContent, 'status' | 'epoch'>
+  ): Promise<void> {
+    if (
+      result.status === 'future_epoch' &&
+      result.epoch - groupState.epoch > this.epochTolerance
+    ) {
+      throw new ResyncRequiredError(groupId, groupState.epoch, result.epoch);
+    }
+    if (result.status === 'decrypted') {
+      await this.recordMessage(groupState, 'received');
+    }
+  }
+
+  private assertWithinMaxMessageSize(plaintext: Uint8Array): void {
+    if (
+      this.maxMessageSize !== null &&
+      plaintext.byteLength > this.maxMessageSize
+    ) {
+      throw new MessageTooLargeError(plaintext.byteLength, this.maxMessageSize);
+    }
+  }
+}
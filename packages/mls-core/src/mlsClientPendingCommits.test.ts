@@ -0,0 +1,218 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+function groupMetadata(state: Uint8Array) {
+  return {
+    group_id: 'group-1',
+    epoch: state[0] ?? 0,
+    self_user_id: 'alice',
+    members: [],
+    group_context: {
+      name: null,
+      external_senders: [],
+      ratchet_tree_out_of_band: false
+    }
+  };
+}
+
+describe('MlsClient pending commits', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+    // The first state byte stands in for the epoch.
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_add_members: () => ({
+          state: [2],
+          commit: [8],
+          welcomes: [
+            { key_package_ref: 'kp', epoch: 2, ciphersuite: 3, welcome: [9] }
+          ],
+          group_info: [7],
+          new_epoch: 2
+        }),
+        mls_group_state_metadata: groupMetadata
+      })
+    );
+  });
+
+  it('reports our staged add epoch and merges it on request', async () => {
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+    await client.createGroup('group-1');
+
+    const result = await client.addMembersStaged('group-1', [
+      Uint8Array.from([5])
+    ]);
+
+    expect(result).toEqual({
+      commit: Uint8Array.from([8]),
+      welcome: Uint8Array.from([9]),
+      welcomes: [
+        {
+          keyPackageRef: 'kp',
+          epoch: 2,
+          ciphersuite: 3,
+          welcome: Uint8Array.from([9])
+        }
+      ],
+      groupInfo: Uint8Array.from([7]),
+      newEpoch: 2
+    });
+    expect(client.pendingCommitEpoch('group-1')).toBe(2);
+    expect(client.getGroupEpoch('group-1')).toBe(1);
+    await expect(
+      client.addMembersStaged('group-1', [Uint8Array.from([6])])
+    ).rejects.toThrow('Commit already pending for group: group-1');
+
+    await client.mergePendingCommit('group-1');
+
+    expect(client.getGroupEpoch('group-1')).toBe(2);
+    expect(storage.groupStates.get('group-1')?.epoch).toBe(2);
+    expect(() => client.pendingCommitEpoch('group-1')).toThrow(
+      'No pending commit for group: group-1'
+    );
+    client.close();
+  });
+
+  it('keeps the current state when the pending commit is discarded', async () => {
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('group-1');
+    await client.addMembersStaged('group-1', [Uint8Array.from([5])]);
+
+    client.discardPendingCommit('group-1');
+
+    expect(client.getGroupEpoch('group-1')).toBe(1);
+    expect(() => client.pendingCommitEpoch('group-1')).toThrow(
+      'No pending commit for group: group-1'
+    );
+    client.close();
+  });
+
+  it('merges a direct add at once with a welcome per recipient', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_add_members: () => ({
+          state: [2],
+          commit: [8],
+          welcomes: [
+            { key_package_ref: 'kp-1', epoch: 2, ciphersuite: 3, welcome: [9] },
+            { key_package_ref: 'kp-2', epoch: 2, ciphersuite: 3, welcome: [9] }
+          ],
+          group_info: [7],
+          new_epoch: 2
+        }),
+        mls_group_state_metadata: groupMetadata
+      })
+    );
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+    await client.createGroup('group-1');
+
+    const result = await client.addMembers('group-1', [
+      Uint8Array.from([5]),
+      Uint8Array.from([6])
+    ]);
+
+    expect(result.welcomes.map((entry) => entry.keyPackageRef)).toEqual([
+      'kp-1',
+      'kp-2'
+    ]);
+    expect(result.welcome).toEqual(Uint8Array.from([9]));
+    expect(result.newEpoch).toBe(2);
+    expect(client.getGroupEpoch('group-1')).toBe(2);
+    expect(storage.groupStates.get('group-1')?.epoch).toBe(2);
+    expect(() => client.pendingCommitEpoch('group-1')).toThrow(
+      'No pending commit for group: group-1'
+    );
+    client.close();
+  });
+
+  it('forwards forcePathUpdate to every add path, defaulting to off', async () => {
+    const addMembers = vi.fn(
+      (_state: Uint8Array, _keyPackages: Uint8Array[], _force: boolean) => ({
+        state: [2],
+        commit: [8],
+        welcomes: [],
+        group_info: [7],
+        new_epoch: 2
+      })
+    );
+    const addMembersBundle = vi.fn(
+      (state: Uint8Array, _keyPackages: Uint8Array[], _force: boolean) => ({
+        state: [(state[0] ?? 0) + 1],
+        bundle: [6],
+        new_epoch: (state[0] ?? 0) + 1
+      })
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_add_members: addMembers,
+        mls_add_members_bundle: addMembersBundle,
+        mls_group_state_metadata: groupMetadata
+      })
+    );
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('group-1');
+    const keyPackages = [Uint8Array.from([5])];
+
+    await client.addMembersStaged('group-1', keyPackages);
+    client.discardPendingCommit('group-1');
+    await client.addMembersStaged('group-1', keyPackages, {
+      forcePathUpdate: true
+    });
+    client.discardPendingCommit('group-1');
+    await client.addMembers('group-1', keyPackages);
+    await client.addMembers('group-1', keyPackages, { forcePathUpdate: true });
+    await client.addMembersBundle('group-1', keyPackages);
+    await client.addMembersBundle('group-1', keyPackages, {
+      forcePathUpdate: true
+    });
+
+    expect(addMembers.mock.calls.map((call) => call[2])).toEqual([
+      false,
+      true,
+      false,
+      true
+    ]);
+    expect(addMembersBundle.mock.calls.map((call) => call[2])).toEqual([
+      false,
+      true
+    ]);
+    client.close();
+  });
+
+  it('merges the commit even when the epoch listener throws', async () => {
+    const warn = vi.spyOn(console, 'warn').mockImplementation(() => {});
+    const listener = vi.fn((_groupId: string, _epoch: number) => {
+      throw new Error('listener bug');
+    });
+    const client = await createClientWithCredential(new MemoryMlsStorage());
+    await client.createGroup('group-1');
+    client.onEpochChange(listener);
+    await client.addMembersStaged('group-1', [Uint8Array.from([5])]);
+
+    await client.mergePendingCommit('group-1');
+
+    expect(listener).toHaveBeenCalledWith('group-1', 2);
+    expect(client.getGroupEpoch('group-1')).toBe(2);
+    expect(() => client.pendingCommitEpoch('group-1')).toThrow(
+      'No pending commit for group: group-1'
+    );
+    expect(warn).toHaveBeenCalledWith(
+      '[mls-core] Epoch change listener failed: Error: listener bug'
+    );
+    client.close();
+  });
+});
@@ -0,0 +1,88 @@
+import { MlsKeyPackageClient } from './mlsClientKeyPackages.js';
+import { toAddMembersResult, wasmAddMembers } from './mlsWasmCommits.js';
+import type { AddMembersOptions, AddMembersResult } from './types.js';
+
+interface PendingCommit {
+  state: Uint8Array;
+  newEpoch: number;
+}
+
+/**
+ * Commits we produced but have not merged yet, e.g. until the delivery
+ * service accepts them. The group keeps its current state meanwhile.
+ */
+export abstract class MlsPendingCommitClient extends MlsKeyPackageClient {
+  private pendingCommits: Map<string, PendingCommit> = new Map();
+
+  /**
+   * Adds members in one commit without merging it. Send the commit and
+   * welcome, then call `mergePendingCommit` once the delivery service accepts
+   * it, or `discardPendingCommit` if another commit won the epoch.
+   */
+  async addMembersStaged(
+    groupId: string,
+    keyPackages: Uint8Array[],
+    options: AddMembersOptions = {}
+  ): Promise<AddMembersResult> {
+    this.assertBackendReady();
+    const groupState = this.requireSigningGroupState(groupId);
+    if (this.pendingCommits.has(groupId)) {
+      throw new Error(`Commit already pending for group: ${groupId}`);
+    }
+    await this.assertKeyPackagesAccepted(keyPackages);
+
+    const result = await wasmAddMembers(
+      groupState.serialized,
+      keyPackages,
+      options.forcePathUpdate ?? false
+    );
+    this.pendingCommits.set(groupId, {
+      state: result.state,
+      newEpoch: result.newEpoch
+    });
+
+    return toAddMembersResult(result);
+  }
+
+  /**
+   * Returns the epoch our pending commit will move the group to, so outgoing
+   * messages can be tagged before it is merged.
+   */
+  pendingCommitEpoch(groupId: string): number {
+    return this.requirePendingCommit(groupId).newEpoch;
+  }
+
+  async mergePendingCommit(groupId: string): Promise<void> {
+    this.assertBackendReady();
+    const pending = this.requirePendingCommit(groupId);
+
+    await this.installSerializedGroupState(groupId, pending.state, true);
+    this.pendingCommits.delete(groupId);
+  }
+
+  discardPendingCommit(groupId: string): void {
+    this.pendingCommits.delete(groupId);
+  }
+
+  private requirePendingCommit(groupId: string): PendingCommit {
+    const pending = this.pendingCommits.get(groupId);
+    if (!pending) {
+      throw new Error(`No pending commit for group: ${groupId}`);
+    }
+    return pending;
+  }
+
+  /** Also drops pending commits, whose staged states still hold the key. */
+  override async deleteSignatureKeys(): Promise<void> {
+    for (const pending of this.pendingCommits.values()) {
+      pending.state.fill(0);
+    }
+    this.pendingCommits.clear();
+    await super.deleteSignatureKeys();
+  }
+
+  override close(): void {
+    this.pendingCommits.clear();
+    super.close();
+  }
+}
@@ -0,0 +1,94 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { MlsClient } from './mls.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+// A state whose first byte is 0 stands in for a corrupted one.
+const groupMetadata = vi.fn((state: Uint8Array) => {
+  if (state[0] === 0) {
+    throw new Error('corrupted state');
+  }
+  return {
+    group_id: 'group-1',
+    epoch: state[0] ?? 0,
+    self_user_id: 'alice',
+    members: [],
+    group_context: {
+      name: null,
+      external_senders: [],
+      ratchet_tree_out_of_band: false
+    }
+  };
+});
+
+function storedState(groupId: string, byte: number) {
+  return {
+    groupId,
+    serializedState: Uint8Array.from([byte]),
+    epoch: byte,
+    updatedAt: 1
+  };
+}
+
+describe('MlsClient quarantine', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    groupMetadata.mockClear();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_group_state_metadata: groupMetadata })
+    );
+  });
+
+  it('keeps a state that fails to load flagged in storage', async () => {
+    const storage = new MemoryMlsStorage();
+    await storage.saveGroupState(storedState('broken', 0));
+    await storage.saveGroupState(storedState('group-1', 1));
+
+    const client = await createClientWithCredential(storage);
+
+    expect(client.listQuarantinedGroups()).toEqual(['broken']);
+    expect(client.hasGroup('group-1')).toBe(true);
+    expect(storage.groupStates.get('broken')).toEqual({
+      ...storedState('broken', 0),
+      quarantined: true
+    });
+    client.close();
+
+    groupMetadata.mockClear();
+    const restarted = new MlsClient('alice', storage);
+    await restarted.init();
+
+    expect(restarted.listQuarantinedGroups()).toEqual(['broken']);
+    expect(groupMetadata).not.toHaveBeenCalledWith(Uint8Array.from([0]));
+    restarted.close();
+  });
+
+  it('flags a quarantined group until it is left', async () => {
+    const storage = new MemoryMlsStorage();
+    await storage.saveGroupState(storedState('group-1', 1));
+    const client = await createClientWithCredential(storage);
+
+    await client.quarantineGroup('group-1');
+
+    expect(client.hasGroup('group-1')).toBe(false);
+    expect(client.listQuarantinedGroups()).toEqual(['group-1']);
+    expect(storage.groupStates.get('group-1')?.quarantined).toBe(true);
+    expect(storage.groupStates.get('group-1')?.serializedState).toEqual(
+      Uint8Array.from([1])
+    );
+
+    await client.leaveGroup('group-1');
+
+    expect(client.listQuarantinedGroups()).toEqual([]);
+    expect(storage.groupStates.has('group-1')).toBe(false);
+    client.close();
+  });
+});
@@ -0,0 +1,105 @@
+import { base64ToBytes, bytesToBase64 } from './base64.js';
+import { MlsMessagingClient } from './mlsClientMessaging.js';
+import {
+  wasmAddExternalPsk,
+  wasmCompareSafetyNumber,
+  wasmDeriveAttachmentKey,
+  wasmDeriveBackupKey,
+  wasmEpochAuthenticator,
+  wasmListExternalPsks,
+  wasmRemoveExternalPsk
+} from './mlsWasmSecrets.js';
+import type { SafetyNumber } from './types.js';
+
+/** Values derived from epoch secrets, and the external PSKs mixed into them. */
+export abstract class MlsSecretsClient extends MlsMessagingClient {
+  /**
+   * Returns the current epoch authenticator as base64. Members in the same
+   * group state hold the same value; it changes with every epoch.
+   */
+  async getEpochAuthenticator(groupId: string): Promise<string> {
+    this.assertBackendReady();
+    return bytesToBase64(
+      await wasmEpochAuthenticator(this.requireGroupState(groupId).serialized)
+    );
+  }
+
+  /**
+   * Returns the shared key for encrypted history backups as base64. It
+   * changes with every epoch, so record the epoch a backup was sealed under
+   * and derive the key before the group moves past it.
+   */
+  async deriveBackupKey(groupId: string): Promise<string> {
+    this.assertBackendReady();
+    return bytesToBase64(
+      await wasmDeriveBackupKey(this.requireGroupState(groupId).serialized)
+    );
+  }
+
+  /**
+   * Derives the key for an attachment encrypted out of band, as base64. Pick a
+   * fresh random `nonce` per attachment and send it in the MLS message; any
+   * member at the same epoch re-derives the key from it.
+   */
+  async deriveAttachmentKey(
+    groupId: string,
+    nonce: Uint8Array
+  ): Promise<string> {
+    this.assertBackendReady();
+    return bytesToBase64(
+      await wasmDeriveAttachmentKey(
+        this.requireGroupState(groupId).serialized,
+        nonce
+      )
+    );
+  }
+
+  /**
+   * Checks another member's base64 epoch authenticator against ours, e.g. one
+   * scanned from their screen, and returns the safety number to display.
+   */
+  async compareSafetyNumber(
+    groupId: string,
+    otherAuthenticatorB64: string
+  ): Promise<SafetyNumber> {
+    this.assertBackendReady();
+    return wasmCompareSafetyNumber(
+      this.requireGroupState(groupId).serialized,
+      base64ToBytes(otherAuthenticatorB64, 'Epoch authenticator')
+    );
+  }
+
+  /**
+   * Stores an externally agreed 32-byte PSK, e.g. from a pairing protocol,
+   * under `pskId` so `commitPsk` can reference it. It is persisted with the
+   * group state; an existing PSK with the same id is replaced.
+   */
+  async addExternalPsk(
+    groupId: string,
+    pskId: string,
+    secret: Uint8Array
+  ): Promise<void> {
+    this.assertBackendReady();
+    const state = await wasmAddExternalPsk(
+      this.requireGroupState(groupId).serialized,
+      pskId,
+      secret
+    );
+    await this.installSerializedGroupState(groupId, state, true);
+  }
+
+  async removeExternalPsk(groupId: string, pskId: string): Promise<void> {
+    this.assertBackendReady();
+    const state = await wasmRemoveExternalPsk(
+      this.requireGroupState(groupId).serialized,
+      pskId
+    );
+    await this.installSerializedGroupState(groupId, state, true);
+  }
+
+  /** Lists stored external PSK ids in sorted order; secrets stay in WASM. */
+  async listExternalPsks(groupId: string): Promise<string[]> {
+    this.assertBackendReady();
+    return wasmListExternalPsks(this.requireGroupState(groupId).serialized);
+  }
+}
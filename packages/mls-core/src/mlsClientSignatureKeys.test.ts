@@ -0,0 +1,75 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { CredentialDeletedError } from './errors.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  createClientWithCredential,
+  MemoryMlsStorage,
+  READY_BACKEND_STATUS
+} from './test/memoryStorage.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+// A state whose first byte is 0 stands in for one with its key erased.
+function groupMetadata(state: Uint8Array) {
+  return {
+    group_id: 'group-1',
+    epoch: 1,
+    self_user_id: 'alice',
+    members: [],
+    group_context: {
+      name: null,
+      external_senders: [],
+      ratchet_tree_out_of_band: false
+    },
+    ...(state[0] === 0 && { signing_key_deleted: true })
+  };
+}
+
+describe('MlsClient.deleteSignatureKeys', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+    vi.spyOn(mlsWasmBackend, 'resolveMlsBackendStatus').mockResolvedValue(
+      READY_BACKEND_STATUS
+    );
+  });
+
+  it('erases every signing key and refuses to sign afterwards', async () => {
+    const deleteSigningKey = vi.fn((_state: Uint8Array) => Uint8Array.from([0]));
+    const encrypt = vi.fn((_state: Uint8Array, _plaintext: Uint8Array) =>
+      Uint8Array.from([3])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_create_group: () => Uint8Array.from([1]),
+        mls_group_state_metadata: groupMetadata,
+        mls_delete_signing_key: deleteSigningKey,
+        mls_encrypt_message: encrypt
+      })
+    );
+    const storage = new MemoryMlsStorage();
+    const client = await createClientWithCredential(storage);
+    await client.createGroup('group-1');
+    const credentialKey = storage.credentials.get('alice')?.privateKey;
+
+    await client.deleteSignatureKeys();
+
+    expect(deleteSigningKey).toHaveBeenCalledTimes(1);
+    expect(credentialKey).toEqual(Uint8Array.from([0]));
+    expect(storage.credentials.has('alice')).toBe(false);
+    expect(storage.groupStates.get('group-1')?.serializedState).toEqual(
+      Uint8Array.from([0])
+    );
+    await expect(
+      client.encryptMessage('group-1', Uint8Array.from([1]))
+    ).rejects.toBeInstanceOf(CredentialDeletedError);
+    await expect(client.selfUpdate('group-1')).rejects.toMatchObject({
+      name: 'CredentialDeletedError',
+      groupId: 'group-1'
+    });
+    await expect(client.signBlob(Uint8Array.from([1]))).rejects.toMatchObject({
+      name: 'CredentialDeletedError',
+      groupId: null
+    });
+    expect(encrypt).not.toHaveBeenCalled();
+    client.close();
+  });
+});
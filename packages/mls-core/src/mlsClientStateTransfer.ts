@@ -0,0 +1,93 @@
+import { MlsCommitClient } from './mlsClientCommits.js';
+import {
+  wasmExportGroupState,
+  wasmExportPublicGroupState,
+  wasmImportGroupState,
+  wasmMembersDigest
+} from './mlsWasmState.js';
+import type { ExportStateChunk, PublicGroupState } from './types.js';
+
+/** Exports and imports of group state, whole or one group at a time. */
+export abstract class MlsStateTransferClient extends MlsCommitClient {
+  private exportCursors: Map<number, string[]> = new Map();
+  private nextExportHandle = 1;
+
+  /**
+   * Hashes the sorted member identities. Clients with the same membership get
+   * the same digest regardless of leaf order, key rotation, or epoch.
+   */
+  async membersDigest(groupId: string): Promise<Uint8Array> {
+    this.assertBackendReady();
+    return wasmMembersDigest(this.requireGroupState(groupId).serialized);
+  }
+
+  /**
+   * Returns what a directory service may publish about a group: ids,
+   * ciphersuite and member identities. Unlike `exportGroupState` it holds no
+   * secrets or keys, so it cannot decrypt messages or impersonate members.
+   */
+  async exportPublicGroupState(groupId: string): Promise<PublicGroupState> {
+    this.assertBackendReady();
+    return wasmExportPublicGroupState(
+      this.requireGroupState(groupId).serialized
+    );
+  }
+
+  async exportGroupState(groupId: string): Promise<Uint8Array> {
+    this.assertBackendReady();
+    const groupState = this.requireGroupState(groupId);
+    return wasmExportGroupState(groupState.serialized);
+  }
+
+  /**
+   * Starts a chunked export over the groups present now. Call `exportStateNext`
+   * until it reports `done`, yielding to the event loop between calls.
+   */
+  exportStateBegin(): number {
+    this.assertBackendReady();
+    const handle = this.nextExportHandle;
+    this.nextExportHandle += 1;
+    this.exportCursors.set(handle, Array.from(this.groupStates.keys()));
+    return handle;
+  }
+
+  /** Exports one group per call, then the credential once every group is done. */
+  async exportStateNext(handle: number): Promise<ExportStateChunk> {
+    this.assertBackendReady();
+    const remaining = this.exportCursors.get(handle);
+    if (!remaining) {
+      throw new Error(`Unknown export handle: ${handle}`);
+    }
+
+    let groupId = remaining.shift();
+    while (groupId !== undefined && !this.groupStates.has(groupId)) {
+      groupId = remaining.shift();
+    }
+
+    if (groupId === undefined) {
+      this.exportCursors.delete(handle);
+      return { done: true, credential: this.credential };
+    }
+
+    return {
+      done: false,
+      groupId,
+      state: await this.exportGroupState(groupId)
+    };
+  }
+
+  async importGroupState(
+    groupId: string,
+    serializedState: Uint8Array
+  ): Promise<void> {
+    this.assertBackendReady();
+
+    const normalized = await wasmImportGroupState(groupId, serializedState);
+    await this.installSerializedGroupState(groupId, normalized.state, true);
+  }
+
+  override close(): void {
+    this.exportCursors.clear();
+    super.close();
+  }
+}
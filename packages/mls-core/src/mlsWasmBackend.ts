@@ -9,16 +9,50 @@ interface MlsWasmBackendBindings {
 }
 
 export interface MlsWasmPrimitiveBindings extends MlsWasmBackendBindings {
+  mls_init_with_options: (verbose: boolean) => void;
   mls_generate_credential: (userId: string) => unknown;
+  mls_generate_credential_from_identity: (identity: Uint8Array) => unknown;
   mls_generate_key_package: (
     credentialBundle: Uint8Array,
     credentialPrivateKey: Uint8Array
   ) => unknown;
+  mls_generate_last_resort_key_package: (
+    credentialBundle: Uint8Array,
+    credentialPrivateKey: Uint8Array
+  ) => unknown;
+  mls_match_welcome_key_package: (
+    welcome: Uint8Array,
+    storedRefs: string[]
+  ) => string | undefined;
+  mls_key_package_expired: (
+    keyPackage: Uint8Array,
+    nowSeconds: bigint
+  ) => boolean;
+  mls_validate_key_package: (
+    keyPackage: Uint8Array,
+    nowSeconds: bigint
+  ) => unknown;
+  mls_inspect_key_package: (keyPackage: Uint8Array) => unknown;
+  mls_member_credentials: (groupState: Uint8Array) => unknown;
+  mls_sign_blob: (
+    credentialPrivateKey: Uint8Array,
+    data: Uint8Array
+  ) => Uint8Array;
+  mls_verify_blob: (
+    signingPublicKey: Uint8Array,
+    data: Uint8Array,
+    signature: Uint8Array
+  ) => boolean;
   mls_create_group: (
     groupId: string,
     credentialBundle: Uint8Array,
     credentialPrivateKey: Uint8Array
   ) => Uint8Array;
+  mls_create_solo_group: (
+    groupId: string,
+    credentialBundle: Uint8Array,
+    credentialPrivateKey: Uint8Array
+  ) => Uint8Array;
   mls_join_group: (
     groupId: string,
     welcomeBytes: Uint8Array,
@@ -26,27 +60,118 @@ export interface MlsWasmPrimitiveBindings extends MlsWasmBackendBindings {
     keyPackagePrivateKey: Uint8Array,
     credentialBundle: Uint8Array,
     credentialPrivateKey: Uint8Array
-  ) => Uint8Array;
+  ) => unknown;
   mls_add_member: (
     groupState: Uint8Array,
     memberKeyPackage: Uint8Array
   ) => unknown;
+  mls_add_members: (
+    groupState: Uint8Array,
+    memberKeyPackages: Uint8Array[],
+    forcePathUpdate: boolean
+  ) => unknown;
+  mls_add_members_bundle: (
+    groupState: Uint8Array,
+    memberKeyPackages: Uint8Array[],
+    forcePathUpdate: boolean
+  ) => unknown;
+  mls_unpack_commit_bundle: (bundle: Uint8Array) => unknown;
   mls_remove_member: (groupState: Uint8Array, leafIndex: number) => unknown;
+  mls_replace_member: (
+    groupState: Uint8Array,
+    leafIndex: number,
+    memberKeyPackage: Uint8Array,
+    allowIdentityChange: boolean
+  ) => unknown;
   mls_process_commit: (
     groupState: Uint8Array,
     commitBytes: Uint8Array
-  ) => Uint8Array;
+  ) => unknown;
+  mls_preview_commit: (
+    groupState: Uint8Array,
+    commitBytes: Uint8Array
+  ) => unknown;
+  mls_update_group_context: (
+    groupState: Uint8Array,
+    groupContext: unknown
+  ) => unknown;
+  mls_self_update: (groupState: Uint8Array) => unknown;
+  mls_should_rekey: (groupState: Uint8Array, maxEpochs: bigint) => boolean;
   mls_encrypt_message: (
     groupState: Uint8Array,
     plaintext: Uint8Array
   ) => Uint8Array;
+  mls_reencrypt_history: (
+    groupState: Uint8Array,
+    plaintexts: Uint8Array[]
+  ) => unknown;
   mls_decrypt_message: (
     groupState: Uint8Array,
-    ciphertext: Uint8Array
+    ciphertext: Uint8Array,
+    includeFraming: boolean
   ) => unknown;
+  mls_decrypt_message_into: (
+    groupState: Uint8Array,
+    ciphertext: Uint8Array,
+    out: Uint8Array
+  ) => unknown;
+  mls_catch_up: (groupState: Uint8Array, items: Uint8Array[]) => unknown;
   mls_group_state_metadata: (groupState: Uint8Array) => unknown;
+  mls_members_digest: (groupState: Uint8Array) => Uint8Array;
+  mls_pending_proposal_count: (groupState: Uint8Array) => number;
+  mls_epochs_behind: (groupState: Uint8Array, remoteEpoch: bigint) => bigint;
+  mls_sync_digest: (groupState: Uint8Array) => Uint8Array;
+  mls_group_policy: (groupState: Uint8Array) => unknown;
+  mls_set_group_policy: (groupState: Uint8Array, policy: unknown) => Uint8Array;
+  mls_delete_signing_key: (groupState: Uint8Array) => Uint8Array;
+  mls_set_group_metadata: (
+    groupState: Uint8Array,
+    key: string,
+    value: string | undefined
+  ) => Uint8Array;
+  mls_group_metadata: (groupState: Uint8Array) => unknown;
+  mls_add_external_psk: (
+    groupState: Uint8Array,
+    pskId: string,
+    secret: Uint8Array
+  ) => Uint8Array;
+  mls_remove_external_psk: (groupState: Uint8Array, pskId: string) => Uint8Array;
+  mls_list_external_psks: (groupState: Uint8Array) => unknown;
+  mls_commit_psk: (groupState: Uint8Array, psk: unknown) => unknown;
+  mls_store_proposal: (groupState: Uint8Array, proposal: Uint8Array) => unknown;
+  mls_commit_received_proposals: (
+    groupState: Uint8Array,
+    proposalRefs: string[]
+  ) => unknown;
+  mls_epoch_authenticator: (groupState: Uint8Array) => Uint8Array;
+  mls_derive_backup_key: (groupState: Uint8Array) => Uint8Array;
+  mls_derive_attachment_key: (
+    groupState: Uint8Array,
+    nonce: Uint8Array
+  ) => Uint8Array;
+  mls_compare_safety_number: (
+    groupState: Uint8Array,
+    otherAuthenticator: Uint8Array
+  ) => unknown;
   mls_export_group_state: (groupState: Uint8Array) => Uint8Array;
   mls_import_group_state: (groupId: string, groupState: Uint8Array) => unknown;
+  mls_export_public_group_state: (groupState: Uint8Array) => unknown;
+  mls_validate_group_state: (
+    groupId: string,
+    groupState: Uint8Array
+  ) => unknown;
+  mls_force_leave: (groupState: Uint8Array) => unknown;
+  mls_inspect_welcome: (welcomeBytes: Uint8Array) => unknown;
+  mls_welcome_join_status: (
+    groupState: Uint8Array,
+    welcomeBytes: Uint8Array
+  ) => unknown;
+  mls_resend_welcome: (groupState: Uint8Array, leafIndex: number) => Uint8Array;
+  mls_export_ratchet_tree: (groupState: Uint8Array) => Uint8Array;
+  mls_attach_ratchet_tree: (
+    welcomeBytes: Uint8Array,
+    ratchetTree: Uint8Array
+  ) => Uint8Array;
 }
 
 export interface MlsBackendStatus {
@@ -110,18 +235,67 @@ function assertPrimitiveBindings(
 ): asserts module is MlsWasmPrimitiveBindings {
   assertBackendBindings(module);
   assertFunctions(module, [
+    'mls_init_with_options',
     'mls_generate_credential',
+    'mls_generate_credential_from_identity',
     'mls_generate_key_package',
+    'mls_generate_last_resort_key_package',
+    'mls_match_welcome_key_package',
+    'mls_key_package_expired',
+    'mls_validate_key_package',
+    'mls_inspect_key_package',
+    'mls_member_credentials',
+    'mls_sign_blob',
+    'mls_verify_blob',
     'mls_create_group',
+    'mls_create_solo_group',
     'mls_join_group',
     'mls_add_member',
+    'mls_add_members',
+    'mls_add_members_bundle',
+    'mls_unpack_commit_bundle',
     'mls_remove_member',
+    'mls_replace_member',
     'mls_process_commit',
+    'mls_preview_commit',
+    'mls_update_group_context',
+    'mls_self_update',
+    'mls_should_rekey',
     'mls_encrypt_message',
+    'mls_reencrypt_history',
     'mls_decrypt_message',
+    'mls_decrypt_message_into',
+    'mls_catch_up',
     'mls_group_state_metadata',
+    'mls_members_digest',
+    'mls_pending_proposal_count',
+    'mls_epochs_behind',
+    'mls_sync_digest',
+    'mls_group_policy',
+    'mls_set_group_policy',
+    'mls_delete_signing_key',
+    'mls_set_group_metadata',
+    'mls_group_metadata',
+    'mls_add_external_psk',
+    'mls_remove_external_psk',
+    'mls_list_external_psks',
+    'mls_commit_psk',
+    'mls_store_proposal',
+    'mls_commit_received_proposals',
+    'mls_epoch_authenticator',
+    'mls_derive_backup_key',
+    'mls_derive_attachment_key',
+    'mls_compare_safety_number',
     'mls_export_group_state',
-    'mls_import_group_state'
+    'mls_import_group_state',
+    'mls_export_public_group_state',
+    'mls_validate_group_state',
+    'mls_force_leave',
+    'mls_inspect_welcome',
+    'mls_welcome_join_status',
+    'mls_resend_welcome',
+    'mls_export_ratchet_tree',
+    'mls_attach_ratchet_tree'
   ]);
 }
 
@@ -155,6 +329,18 @@ export async function loadMlsWasmPrimitiveBindings(): Promise<MlsWasmPrimitiveBi
   return module;
 }
 
+/**
+ * Configures the loaded WASM backend. `verbose` routes protocol diagnostics
+ * (group ids, epochs, leaf indexes) to `console.debug`; it stays off by
+ * default and never logs key material or plaintext.
+ */
+export async function initMlsWasmWithOptions(
+  options: { verbose?: boolean } = {}
+): Promise<void> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  bindings.mls_init_with_options(options.verbose ?? false);
+}
+
 export async function resolveMlsBackendStatus(): Promise<MlsBackendStatus> {
   try {
     const bindings = await getBackendBindings();
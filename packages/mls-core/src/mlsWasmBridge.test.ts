@@ -1,75 +1,34 @@
 import { beforeAll, beforeEach, describe, expect, it, vi } from 'vitest';
-import type { MlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
 import * as mlsWasmBackend from './mlsWasmBackend.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
 
 type WasmGenerateCredential =
   typeof import('./mlsWasmBridge').wasmGenerateCredential;
 type WasmGenerateKeyPackage =
   typeof import('./mlsWasmBridge').wasmGenerateKeyPackage;
+type WasmInspectWelcome = typeof import('./mlsWasmWelcome').wasmInspectWelcome;
+type WasmJoinGroup = typeof import('./mlsWasmBridge').wasmJoinGroup;
+type WasmPreviewCommit = typeof import('./mlsWasmBridge').wasmPreviewCommit;
+type WasmWelcomeJoinStatus =
+  typeof import('./mlsWasmWelcome').wasmWelcomeJoinStatus;
 
 let wasmGenerateCredential: WasmGenerateCredential;
 let wasmGenerateKeyPackage: WasmGenerateKeyPackage;
-
-function createPrimitiveBindings(
-  overrides: Partial<MlsWasmPrimitiveBindings> = {}
-): MlsWasmPrimitiveBindings {
-  return {
-    mls_backend_name: () => 'mock-mls',
-    mls_backend_version: () => '0.0.0-test',
-    mls_backend_ready: () => true,
-    mls_backend_notice: () => 'ready',
-    mls_generate_credential: () => ({
-      credential_bundle: [1],
-      private_key: [2],
-      created_at_ms: 1
-    }),
-    mls_generate_key_package: () => ({
-      key_package: [1],
-      key_package_ref: 'mock-ref',
-      private_key: [2],
-      created_at_ms: 1
-    }),
-    mls_create_group: () => Uint8Array.from([]),
-    mls_join_group: () => Uint8Array.from([]),
-    mls_add_member: () => ({
-      state: Uint8Array.from([]),
-      commit: Uint8Array.from([]),
-      welcome: Uint8Array.from([]),
-      group_info: Uint8Array.from([]),
-      new_epoch: 1
-    }),
-    mls_remove_member: () => ({
-      state: Uint8Array.from([]),
-      commit: Uint8Array.from([]),
-      new_epoch: 1
-    }),
-    mls_process_commit: () => Uint8Array.from([]),
-    mls_encrypt_message: () => Uint8Array.from([]),
-    mls_decrypt_message: () => ({
-      sender_id: 'mock-user',
-      plaintext: Uint8Array.from([]),
-      authenticated_data: Uint8Array.from([])
-    }),
-    mls_group_state_metadata: () => ({
-      group_id: 'mock-group',
-      epoch: 1,
-      self_user_id: 'mock-user',
-      members: []
-    }),
-    mls_export_group_state: () => Uint8Array.from([]),
-    mls_import_group_state: () => ({
-      state: Uint8Array.from([]),
-      epoch: 1
-    }),
-    ...overrides
-  };
-}
+let wasmInspectWelcome: WasmInspectWelcome;
+let wasmJoinGroup: WasmJoinGroup;
+let wasmPreviewCommit: WasmPreviewCommit;
+let wasmWelcomeJoinStatus: WasmWelcomeJoinStatus;
 
 describe('mlsWasmBridge', () => {
   beforeAll(async () => {
     const module = await import('./mlsWasmBridge');
+    const welcomeModule = await import('./mlsWasmWelcome');
     wasmGenerateCredential = module.wasmGenerateCredential;
     wasmGenerateKeyPackage = module.wasmGenerateKeyPackage;
+    wasmInspectWelcome = welcomeModule.wasmInspectWelcome;
+    wasmJoinGroup = module.wasmJoinGroup;
+    wasmPreviewCommit = module.wasmPreviewCommit;
+    wasmWelcomeJoinStatus = welcomeModule.wasmWelcomeJoinStatus;
   });
 
   beforeEach(() => {
@@ -80,6 +39,7 @@ describe('mlsWasmBridge', () => {
     vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
       createPrimitiveBindings({
         mls_generate_credential: () => ({
+          user_id: 'baseline-shape-user',
           credential_bundle: [123, 34, 118, 101],
           private_key: [5, 10, 255],
           created_at_ms: 1234
@@ -89,6 +49,7 @@ describe('mlsWasmBridge', () => {
 
     const output = await wasmGenerateCredential('baseline-shape-user');
 
+    expect(output.userId).toBe('baseline-shape-user');
     expect(output.credentialBundle).toBeInstanceOf(Uint8Array);
     expect(output.privateKey).toBeInstanceOf(Uint8Array);
     expect(Array.from(output.credentialBundle)).toEqual([123, 34, 118, 101]);
@@ -125,6 +86,7 @@ describe('mlsWasmBridge', () => {
     vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
       createPrimitiveBindings({
         mls_generate_credential: () => ({
+          user_id: 'alice',
           credential_bundle: [1, -1],
           private_key: [2],
           created_at_ms: 1234
@@ -136,4 +98,79 @@ describe('mlsWasmBridge', () => {
       "WASM response field 'credential_bundle' array entries must be integers between 0 and 255"
     );
   });
+
+  it('parses joined group info from the join response', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+
+    const bytes = Uint8Array.from([1]);
+    await expect(
+      wasmJoinGroup('group-1', bytes, 'mock-ref', bytes, bytes, bytes)
+    ).resolves.toEqual({
+      state: Uint8Array.from([]),
+      groupId: 'group-1',
+      epoch: 1,
+      ciphersuite: 3,
+      members: [{ userId: 'alice', leafIndex: 0 }]
+    });
+  });
+
+  it('parses commit previews and requires a boolean self-removal flag', async () => {
+    const spy = vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings');
+    spy.mockResolvedValue(createPrimitiveBindings());
+
+    await expect(
+      wasmPreviewCommit(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).resolves.toEqual({
+      newEpoch: 2,
+      added: [],
+      removed: [{ userId: 'mock-user', leafIndex: 1 }],
+      removesSelf: true
+    });
+
+    spy.mockResolvedValue(
+      createPrimitiveBindings({
+        mls_preview_commit: () => ({
+          new_epoch: 2,
+          added: [],
+          removed: [],
+          removes_self: 'yes'
+        })
+      })
+    );
+    await expect(
+      wasmPreviewCommit(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).rejects.toThrow("WASM response field 'removes_self' must be a boolean");
+  });
+
+  it('parses welcome headers including the embedded tree flag', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+
+    await expect(wasmInspectWelcome(Uint8Array.from([1]))).resolves.toEqual({
+      groupId: 'mock-group',
+      epoch: 1,
+      ciphersuite: 3,
+      keyPackageRefs: ['mock-ref'],
+      hasRatchetTree: true
+    });
+  });
+
+  it('passes through welcome join statuses and rejects unknown ones', async () => {
+    const spy = vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings');
+    spy.mockResolvedValue(createPrimitiveBindings());
+
+    await expect(
+      wasmWelcomeJoinStatus(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).resolves.toBe('already_joined');
+
+    spy.mockResolvedValue(
+      createPrimitiveBindings({ mls_welcome_join_status: () => 'rejoin' })
+    );
+    await expect(
+      wasmWelcomeJoinStatus(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).rejects.toThrow("WASM welcome join status is unknown: 'rejoin'");
+  });
 });
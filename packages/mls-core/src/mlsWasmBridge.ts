@@ -2,10 +2,18 @@ import {
   loadMlsWasmPrimitiveBindings,
   type MlsWasmPrimitiveBindings
 } from './mlsWasmBackend.js';
-
-interface RecordLike {
-  [key: string]: unknown;
-}
+import { readGroupContext } from './mlsWasmGroupContext.js';
+import {
+  isRecordLike,
+  type RecordLike,
+  readBoolean,
+  readNumber,
+  readObjectArray,
+  readString,
+  readUint8Array,
+  readUint8ArrayList
+} from './mlsWasmResponse.js';
+import type { GroupContextChange } from './types.js';
 
 interface GroupMemberMetadata {
   userId: string;
@@ -17,15 +25,25 @@ interface GroupStateMetadata {
   epoch: number;
   selfUserId: string;
   members: GroupMemberMetadata[];
+  signingKeyDeleted: boolean;
+}
+
+interface JoinGroupResult {
+  state: Uint8Array;
+  groupId: string;
+  epoch: number;
+  ciphersuite: number;
+  members: GroupMemberMetadata[];
 }
 
 interface GeneratedCredential {
+  userId: string;
   credentialBundle: Uint8Array;
   privateKey: Uint8Array;
   createdAtMs: number;
 }
 
-interface GeneratedKeyPackage {
+export interface GeneratedKeyPackage {
   keyPackage: Uint8Array;
   keyPackageRef: string;
   privateKey: Uint8Array;
@@ -46,104 +64,76 @@ interface RemoveMemberResult {
   newEpoch: number;
 }
 
+interface CommitPreviewResult {
+  newEpoch: number;
+  added: GroupMemberMetadata[];
+  removed: GroupMemberMetadata[];
+  removesSelf: boolean;
+}
+
+interface ProcessCommitResult {
+  state: Uint8Array;
+  newEpoch: number;
+  groupContextChanged: GroupContextChange | null;
+}
+
+export type DecryptStatus =
+  | 'decrypted'
+  | 'own_message'
+  | 'sender_not_in_group'
+  | 'future_epoch';
+
 interface DecryptResult {
+  status: DecryptStatus;
+  epoch: number;
   senderId: string;
+  senderSignatureKey: Uint8Array;
+  authenticated: boolean;
   plaintext: Uint8Array;
   authenticatedData: Uint8Array;
+  framing: Uint8Array;
 }
 
-interface ImportStateResult {
+interface CatchUpResult {
   state: Uint8Array;
   epoch: number;
+  messages: DecryptResult[];
+  pending: Uint8Array[];
 }
 
-function isRecordLike(value: unknown): value is RecordLike {
-  return typeof value === 'object' && value !== null;
-}
-
-function readString(record: RecordLike, field: string): string {
-  const value = record[field];
-  if (typeof value !== 'string') {
-    throw new Error(`WASM response field '${field}' must be a string`);
-  }
-  return value;
-}
-
-function readNumber(record: RecordLike, field: string): number {
-  const value = record[field];
-  if (typeof value !== 'number' || !Number.isFinite(value)) {
-    throw new Error(`WASM response field '${field}' must be a finite number`);
+interface ForceLeaveResult {
+  groupId: string;
+  epoch: number;
+  proposal: Uint8Array;
+}
+
+export function readDecryptStatus(record: RecordLike): DecryptStatus {
+  const value = readString(record, 'status');
+  if (
+    value !== 'decrypted' &&
+    value !== 'own_message' &&
+    value !== 'sender_not_in_group' &&
+    value !== 'future_epoch'
+  ) {
+    throw new Error(`WASM decrypt response has unknown status '${value}'`);
   }
   return value;
 }
 
-function readUint8Array(record: RecordLike, field: string): Uint8Array {
-  const value = record[field];
-
-  if (value instanceof Uint8Array) {
-    return value;
-  }
-
-  if (Array.isArray(value)) {
-    for (const entry of value) {
-      const isByte =
-        typeof entry === 'number' &&
-        Number.isInteger(entry) &&
-        entry >= 0 &&
-        entry <= 255;
-
-      if (!isByte) {
-        throw new Error(
-          `WASM response field '${field}' array entries must be integers between 0 and 255`
-        );
-      }
-    }
-
-    return Uint8Array.from(value);
-  }
-
-  if (value instanceof ArrayBuffer) {
-    return new Uint8Array(value);
-  }
-
-  if (ArrayBuffer.isView(value)) {
-    return new Uint8Array(value.buffer, value.byteOffset, value.byteLength);
-  }
-
-  throw new Error(
-    `WASM response field '${field}' must be Uint8Array-compatible binary data`
-  );
-}
-
-function readObjectArray(record: RecordLike, field: string): RecordLike[] {
-  const value = record[field];
-  if (!Array.isArray(value)) {
-    throw new Error(`WASM response field '${field}' must be an array`);
-  }
-
-  const output: RecordLike[] = [];
-  for (const item of value) {
-    if (!isRecordLike(item)) {
-      throw new Error(`WASM response field '${field}' entries must be objects`);
-    }
-    output.push(item);
-  }
-  return output;
-}
-
 function parseGeneratedCredential(value: unknown): GeneratedCredential {
   if (!isRecordLike(value)) {
     throw new Error('WASM credential response must be an object');
   }
 
   return {
+    userId: readString(value, 'user_id'),
     credentialBundle: readUint8Array(value, 'credential_bundle'),
     privateKey: readUint8Array(value, 'private_key'),
     createdAtMs: readNumber(value, 'created_at_ms')
   };
 }
 
-function parseGeneratedKeyPackage(value: unknown): GeneratedKeyPackage {
+export function parseGeneratedKeyPackage(value: unknown): GeneratedKeyPackage {
   if (!isRecordLike(value)) {
     throw new Error('WASM key package response must be an object');
   }
@@ -156,22 +146,42 @@ function parseGeneratedKeyPackage(value: unknown): GeneratedKeyPackage {
   };
 }
 
+function readGroupMembers(
+  record: RecordLike,
+  field = 'members'
+): GroupMemberMetadata[] {
+  return readObjectArray(record, field).map((member) => ({
+    userId: readString(member, 'user_id'),
+    leafIndex: readNumber(member, 'leaf_index')
+  }));
+}
+
 function parseGroupStateMetadata(value: unknown): GroupStateMetadata {
   if (!isRecordLike(value)) {
     throw new Error('WASM metadata response must be an object');
   }
 
-  const memberRecords = readObjectArray(value, 'members');
-  const members: GroupMemberMetadata[] = memberRecords.map((member) => ({
-    userId: readString(member, 'user_id'),
-    leafIndex: readNumber(member, 'leaf_index')
-  }));
-
   return {
     groupId: readString(value, 'group_id'),
     epoch: readNumber(value, 'epoch'),
     selfUserId: readString(value, 'self_user_id'),
-    members
+    members: readGroupMembers(value),
+    // Omitted unless the key was deleted.
+    signingKeyDeleted: value['signing_key_deleted'] === true
+  };
+}
+
+function parseJoinGroupResult(value: unknown): JoinGroupResult {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM join-group response must be an object');
+  }
+
+  return {
+    state: readUint8Array(value, 'state'),
+    groupId: readString(value, 'group_id'),
+    epoch: readNumber(value, 'epoch'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    members: readGroupMembers(value)
   };
 }
 
@@ -201,26 +211,74 @@ function parseRemoveMemberResult(value: unknown): RemoveMemberResult {
   };
 }
 
+function parseProcessCommitResult(value: unknown): ProcessCommitResult {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM process-commit response must be an object');
+  }
+
+  const changed = value['group_context_changed'];
+  return {
+    state: readUint8Array(value, 'state'),
+    newEpoch: readNumber(value, 'new_epoch'),
+    groupContextChanged:
+      changed === null || changed === undefined
+        ? null
+        : readGroupContext(changed)
+  };
+}
+
+function parseCommitPreviewResult(value: unknown): CommitPreviewResult {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM preview-commit response must be an object');
+  }
+
+  return {
+    newEpoch: readNumber(value, 'new_epoch'),
+    added: readGroupMembers(value, 'added'),
+    removed: readGroupMembers(value, 'removed'),
+    removesSelf: readBoolean(value, 'removes_self')
+  };
+}
+
 function parseDecryptResult(value: unknown): DecryptResult {
   if (!isRecordLike(value)) {
     throw new Error('WASM decrypt response must be an object');
   }
 
   return {
+    status: readDecryptStatus(value),
+    epoch: readNumber(value, 'epoch'),
     senderId: readString(value, 'sender_id'),
+    senderSignatureKey: readUint8Array(value, 'sender_signature_key'),
+    authenticated: readBoolean(value, 'authenticated'),
     plaintext: readUint8Array(value, 'plaintext'),
-    authenticatedData: readUint8Array(value, 'authenticated_data')
+    authenticatedData: readUint8Array(value, 'authenticated_data'),
+    framing: readUint8Array(value, 'framing')
   };
 }
 
-function parseImportStateResult(value: unknown): ImportStateResult {
+function parseCatchUpResult(value: unknown): CatchUpResult {
   if (!isRecordLike(value)) {
-    throw new Error('WASM import-state response must be an object');
+    throw new Error('WASM catch-up response must be an object');
   }
 
   return {
     state: readUint8Array(value, 'state'),
-    epoch: readNumber(value, 'epoch')
+    epoch: readNumber(value, 'epoch'),
+    messages: readObjectArray(value, 'messages').map(parseDecryptResult),
+    pending: readUint8ArrayList(value['pending'], 'catch-up pending')
+  };
+}
+
+function parseForceLeaveResult(value: unknown): ForceLeaveResult {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM force-leave response must be an object');
+  }
+
+  return {
+    groupId: readString(value, 'group_id'),
+    epoch: readNumber(value, 'epoch'),
+    proposal: readUint8Array(value, 'proposal')
   };
 }
 
@@ -232,6 +290,16 @@ export async function wasmGenerateCredential(
   return parseGeneratedCredential(bindings.mls_generate_credential(userId));
 }
 
+/** Generates a credential for raw identity bytes, e.g. a device public key. */
+export async function wasmGenerateCredentialFromIdentity(
+  identity: Uint8Array
+): Promise<GeneratedCredential> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseGeneratedCredential(
+    bindings.mls_generate_credential_from_identity(identity)
+  );
+}
+
 export async function wasmGenerateKeyPackage(
   credentialBundle: Uint8Array,
   credentialPrivateKey: Uint8Array
@@ -242,6 +310,31 @@ export async function wasmGenerateKeyPackage(
   );
 }
 
+export async function wasmKeyPackageExpired(
+  keyPackage: Uint8Array,
+  nowSeconds: number
+): Promise<boolean> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_key_package_expired(keyPackage, BigInt(nowSeconds));
+}
+
+export async function wasmSignBlob(
+  credentialPrivateKey: Uint8Array,
+  data: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_sign_blob(credentialPrivateKey, data);
+}
+
+export async function wasmVerifyBlob(
+  signingPublicKey: Uint8Array,
+  data: Uint8Array,
+  signature: Uint8Array
+): Promise<boolean> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_verify_blob(signingPublicKey, data, signature);
+}
+
 export async function wasmCreateGroup(
   groupId: string,
   credentialBundle: Uint8Array,
@@ -255,25 +348,40 @@ export async function wasmCreateGroup(
   );
 }
 
-export async function wasmJoinGroup(
+export async function wasmCreateSoloGroup(
   groupId: string,
-  welcomeBytes: Uint8Array,
-  keyPackageRef: string,
-  keyPackagePrivateKey: Uint8Array,
   credentialBundle: Uint8Array,
   credentialPrivateKey: Uint8Array
 ): Promise<Uint8Array> {
   const bindings = await loadMlsWasmPrimitiveBindings();
-  return bindings.mls_join_group(
+  return bindings.mls_create_solo_group(
     groupId,
-    welcomeBytes,
-    keyPackageRef,
-    keyPackagePrivateKey,
     credentialBundle,
     credentialPrivateKey
   );
 }
 
+export async function wasmJoinGroup(
+  groupId: string,
+  welcomeBytes: Uint8Array,
+  keyPackageRef: string,
+  keyPackagePrivateKey: Uint8Array,
+  credentialBundle: Uint8Array,
+  credentialPrivateKey: Uint8Array
+): Promise<JoinGroupResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseJoinGroupResult(
+    bindings.mls_join_group(
+      groupId,
+      welcomeBytes,
+      keyPackageRef,
+      keyPackagePrivateKey,
+      credentialBundle,
+      credentialPrivateKey
+    )
+  );
+}
+
 export async function wasmGroupStateMetadata(
   stateBytes: Uint8Array
 ): Promise<GroupStateMetadata> {
@@ -291,6 +399,23 @@ export async function wasmAddMember(
   );
 }
 
+export async function wasmReplaceMember(
+  stateBytes: Uint8Array,
+  leafIndex: number,
+  memberKeyPackageBytes: Uint8Array,
+  allowIdentityChange: boolean
+): Promise<AddMemberResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseAddMemberResult(
+    bindings.mls_replace_member(
+      stateBytes,
+      leafIndex,
+      memberKeyPackageBytes,
+      allowIdentityChange
+    )
+  );
+}
+
 export async function wasmRemoveMember(
   stateBytes: Uint8Array,
   leafIndex: number
@@ -304,9 +429,21 @@ export async function wasmRemoveMember(
 export async function wasmProcessCommit(
   stateBytes: Uint8Array,
   commitBytes: Uint8Array
-): Promise<Uint8Array> {
+): Promise<ProcessCommitResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseProcessCommitResult(
+    bindings.mls_process_commit(stateBytes, commitBytes)
+  );
+}
+
+export async function wasmPreviewCommit(
+  stateBytes: Uint8Array,
+  commitBytes: Uint8Array
+): Promise<CommitPreviewResult> {
   const bindings = await loadMlsWasmPrimitiveBindings();
-  return bindings.mls_process_commit(stateBytes, commitBytes);
+  return parseCommitPreviewResult(
+    bindings.mls_preview_commit(stateBytes, commitBytes)
+  );
 }
 
 export async function wasmEncryptMessage(
@@ -317,39 +454,39 @@ export async function wasmEncryptMessage(
   return bindings.mls_encrypt_message(stateBytes, plaintext);
 }
 
+export async function wasmReencryptHistory(
+  stateBytes: Uint8Array,
+  plaintexts: Uint8Array[]
+): Promise<Uint8Array[]> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return readUint8ArrayList(
+    bindings.mls_reencrypt_history(stateBytes, plaintexts),
+    'reencrypt-history'
+  );
+}
+
 export async function wasmDecryptMessage(
   stateBytes: Uint8Array,
-  ciphertext: Uint8Array
+  ciphertext: Uint8Array,
+  includeFraming: boolean
 ): Promise<DecryptResult> {
   const bindings = await loadMlsWasmPrimitiveBindings();
   return parseDecryptResult(
-    bindings.mls_decrypt_message(stateBytes, ciphertext)
+    bindings.mls_decrypt_message(stateBytes, ciphertext, includeFraming)
   );
 }
 
-export async function wasmExportGroupState(
-  stateBytes: Uint8Array
-): Promise<Uint8Array> {
+export async function wasmCatchUp(
+  stateBytes: Uint8Array,
+  items: Uint8Array[]
+): Promise<CatchUpResult> {
   const bindings = await loadMlsWasmPrimitiveBindings();
-  return bindings.mls_export_group_state(stateBytes);
+  return parseCatchUpResult(bindings.mls_catch_up(stateBytes, items));
 }
 
-export async function wasmImportGroupState(
-  groupId: string,
+export async function wasmForceLeave(
   stateBytes: Uint8Array
-): Promise<ImportStateResult> {
+): Promise<ForceLeaveResult> {
   const bindings = await loadMlsWasmPrimitiveBindings();
-  return parseImportStateResult(
-    bindings.mls_import_group_state(groupId, stateBytes)
-  );
-}
-
-export function membersToLeafIndexMap(
-  metadata: GroupStateMetadata
-): Map<string, number> {
-  const map = new Map<string, number>();
-  for (const member of metadata.members) {
-    map.set(member.userId, member.leafIndex);
-  }
-  return map;
+  return parseForceLeaveResult(bindings.mls_force_leave(stateBytes));
 }
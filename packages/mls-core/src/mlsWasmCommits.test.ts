@@ -0,0 +1,137 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  toAddMembersResult,
+  wasmCommitPsk,
+  wasmCommitReceivedProposals,
+  wasmSelfUpdate,
+  wasmShouldRekey,
+  wasmStoreProposal
+} from './mlsWasmCommits.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('mlsWasmCommits', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('parses the self-update commit and new state', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_self_update: () => ({
+          state: [4],
+          commit: [5, 6],
+          new_epoch: 7
+        })
+      })
+    );
+
+    await expect(wasmSelfUpdate(Uint8Array.from([1]))).resolves.toEqual({
+      state: Uint8Array.from([4]),
+      commit: Uint8Array.from([5, 6]),
+      newEpoch: 7
+    });
+  });
+
+  it('passes the rekey threshold as a 64-bit epoch count', async () => {
+    const shouldRekey = vi.fn(
+      (_state: Uint8Array, maxEpochs: bigint) => maxEpochs < 3n
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_should_rekey: shouldRekey })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(wasmShouldRekey(state, 2)).resolves.toBe(true);
+    await expect(wasmShouldRekey(state, 5)).resolves.toBe(false);
+    expect(shouldRekey).toHaveBeenCalledWith(state, 2n);
+  });
+
+  it('sends PSK references in their tagged wire form', async () => {
+    const commitPsk = vi.fn((_state: Uint8Array, _psk: unknown) => ({
+      state: [1],
+      commit: [2],
+      new_epoch: 3
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_commit_psk: commitPsk })
+    );
+
+    const state = Uint8Array.from([1]);
+    await wasmCommitPsk(state, { type: 'external', pskId: 'pairing' });
+    await wasmCommitPsk(state, { type: 'resumption', epoch: 4 });
+
+    expect(commitPsk).toHaveBeenNthCalledWith(1, state, {
+      type: 'external',
+      psk_id: 'pairing'
+    });
+    expect(commitPsk).toHaveBeenNthCalledWith(2, state, {
+      type: 'resumption',
+      epoch: 4
+    });
+  });
+
+  it('returns the ref of a stored proposal', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_store_proposal: () => ({ state: [3], proposal_ref: 'ab12' })
+      })
+    );
+
+    await expect(
+      wasmStoreProposal(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).resolves.toEqual({ state: Uint8Array.from([3]), proposalRef: 'ab12' });
+  });
+
+  it('commits the chosen proposals with a welcome per recipient', async () => {
+    const commit = vi.fn((_state: Uint8Array, _refs: string[]) => ({
+      state: [4],
+      commit: [5],
+      welcomes: [
+        { key_package_ref: 'kp-1', epoch: 3, ciphersuite: 3, welcome: [6] },
+        { key_package_ref: 'kp-2', epoch: 3, ciphersuite: 1, welcome: [6] }
+      ],
+      group_info: [7],
+      new_epoch: 3
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_commit_received_proposals: commit })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(
+      wasmCommitReceivedProposals(state, ['ab12', 'cd34'])
+    ).resolves.toEqual({
+      state: Uint8Array.from([4]),
+      commit: Uint8Array.from([5]),
+      newEpoch: 3,
+      welcomes: [
+        {
+          keyPackageRef: 'kp-1',
+          epoch: 3,
+          ciphersuite: 3,
+          welcome: Uint8Array.from([6])
+        },
+        {
+          keyPackageRef: 'kp-2',
+          epoch: 3,
+          ciphersuite: 1,
+          welcome: Uint8Array.from([6])
+        }
+      ],
+      groupInfo: Uint8Array.from([7])
+    });
+    expect(commit).toHaveBeenCalledWith(state, ['ab12', 'cd34']);
+  });
+
+  it('reports no welcome when the proposals add nobody', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+
+    const result = await wasmCommitReceivedProposals(Uint8Array.from([1]), []);
+
+    expect(result.welcomes).toEqual([]);
+    expect(toAddMembersResult(result)).not.toHaveProperty('welcome');
+  });
+});
@@ -0,0 +1,146 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  readNumber,
+  readObjectArray,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type {
+  AddMembersResult,
+  PskReference,
+  RecipientWelcome
+} from './types.js';
+
+/** New local state plus the commit to send for a commit-only operation. */
+export interface CommitOutputResult {
+  state: Uint8Array;
+  commit: Uint8Array;
+  newEpoch: number;
+}
+
+/** Commit that may add members, with a welcome entry per added key package. */
+export interface ProposalCommitResult extends CommitOutputResult {
+  welcomes: RecipientWelcome[];
+  groupInfo: Uint8Array;
+}
+
+export function parseCommitOutput(
+  value: unknown,
+  operation: string
+): CommitOutputResult {
+  if (!isRecordLike(value)) {
+    throw new Error(`WASM ${operation} response must be an object`);
+  }
+
+  return {
+    state: readUint8Array(value, 'state'),
+    commit: readUint8Array(value, 'commit'),
+    newEpoch: readNumber(value, 'new_epoch')
+  };
+}
+
+export async function wasmSelfUpdate(
+  stateBytes: Uint8Array
+): Promise<CommitOutputResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseCommitOutput(bindings.mls_self_update(stateBytes), 'self-update');
+}
+
+export async function wasmShouldRekey(
+  stateBytes: Uint8Array,
+  maxEpochs: number
+): Promise<boolean> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_should_rekey(stateBytes, BigInt(maxEpochs));
+}
+
+export async function wasmCommitPsk(
+  stateBytes: Uint8Array,
+  psk: PskReference
+): Promise<CommitOutputResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const wirePsk =
+    psk.type === 'external'
+      ? { type: 'external', psk_id: psk.pskId }
+      : { type: 'resumption', epoch: psk.epoch };
+  return parseCommitOutput(
+    bindings.mls_commit_psk(stateBytes, wirePsk),
+    'commit-psk'
+  );
+}
+
+/** Verifies a received proposal and keeps it pending for the current epoch. */
+export async function wasmStoreProposal(
+  stateBytes: Uint8Array,
+  proposal: Uint8Array
+): Promise<{ state: Uint8Array; proposalRef: string }> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_store_proposal(stateBytes, proposal);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM store-proposal response must be an object');
+  }
+
+  return {
+    state: readUint8Array(value, 'state'),
+    proposalRef: readString(value, 'proposal_ref')
+  };
+}
+
+function parseWelcomeCommitOutput(
+  value: unknown,
+  operation: string
+): ProposalCommitResult {
+  if (!isRecordLike(value)) {
+    throw new Error(`WASM ${operation} response must be an object`);
+  }
+
+  return {
+    ...parseCommitOutput(value, operation),
+    welcomes: readObjectArray(value, 'welcomes').map((entry) => ({
+      keyPackageRef: readString(entry, 'key_package_ref'),
+      epoch: readNumber(entry, 'epoch'),
+      ciphersuite: readNumber(entry, 'ciphersuite'),
+      welcome: readUint8Array(entry, 'welcome')
+    })),
+    groupInfo: readUint8Array(value, 'group_info')
+  };
+}
+
+/** Shapes a multi-add commit for callers; the local state is left out. */
+export function toAddMembersResult(
+  result: ProposalCommitResult
+): AddMembersResult {
+  // Every recipient shares one combined welcome.
+  const [first] = result.welcomes;
+  return {
+    commit: result.commit,
+    ...(first === undefined ? {} : { welcome: first.welcome }),
+    welcomes: result.welcomes,
+    groupInfo: result.groupInfo,
+    newEpoch: result.newEpoch
+  };
+}
+
+export async function wasmCommitReceivedProposals(
+  stateBytes: Uint8Array,
+  proposalRefs: string[]
+): Promise<ProposalCommitResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseWelcomeCommitOutput(
+    bindings.mls_commit_received_proposals(stateBytes, proposalRefs),
+    'commit-received-proposals'
+  );
+}
+
+export async function wasmAddMembers(
+  stateBytes: Uint8Array,
+  keyPackages: Uint8Array[],
+  forcePathUpdate: boolean
+): Promise<ProposalCommitResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseWelcomeCommitOutput(
+    bindings.mls_add_members(stateBytes, keyPackages, forcePathUpdate),
+    'add-members'
+  );
+}
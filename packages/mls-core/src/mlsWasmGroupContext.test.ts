@@ -0,0 +1,96 @@
+import { beforeAll, beforeEach, describe, expect, it, vi } from 'vitest';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+type WasmProcessCommit = typeof import('./mlsWasmBridge').wasmProcessCommit;
+type WasmUpdateGroupContext =
+  typeof import('./mlsWasmGroupContext').wasmUpdateGroupContext;
+
+let wasmProcessCommit: WasmProcessCommit;
+let wasmUpdateGroupContext: WasmUpdateGroupContext;
+
+describe('mlsWasmGroupContext', () => {
+  beforeAll(async () => {
+    wasmProcessCommit = (await import('./mlsWasmBridge')).wasmProcessCommit;
+    wasmUpdateGroupContext = (await import('./mlsWasmGroupContext'))
+      .wasmUpdateGroupContext;
+  });
+
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('carries the new name from a rename commit', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_process_commit: () => ({
+          state: Uint8Array.from([7]),
+          new_epoch: 3,
+          group_context_changed: {
+            name: 'Renamed',
+            external_senders: [{ user_id: 'bot', signing_public_key: [1, 2] }],
+            ratchet_tree_out_of_band: false
+          }
+        })
+      })
+    );
+
+    const result = await wasmProcessCommit(
+      Uint8Array.from([1]),
+      Uint8Array.from([2])
+    );
+
+    expect(result.newEpoch).toBe(3);
+    expect(result.groupContextChanged).toEqual({
+      name: 'Renamed',
+      externalSenders: [
+        { userId: 'bot', signingPublicKey: Uint8Array.from([1, 2]) }
+      ],
+      ratchetTreeOutOfBand: false
+    });
+  });
+
+  it('reports no context change for membership-only commits', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings()
+    );
+
+    const result = await wasmProcessCommit(
+      Uint8Array.from([1]),
+      Uint8Array.from([2])
+    );
+
+    expect(result.groupContextChanged).toBeNull();
+  });
+
+  it('sends the replacement context in wire field names', async () => {
+    const update = vi.fn((_state: Uint8Array, _context: unknown) => ({
+      state: Uint8Array.from([9]),
+      commit: Uint8Array.from([8]),
+      new_epoch: 4
+    }));
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_update_group_context: update })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(
+      wasmUpdateGroupContext(state, {
+        name: 'Team',
+        externalSenders: [
+          { userId: 'bot', signingPublicKey: Uint8Array.from([3]) }
+        ],
+        ratchetTreeOutOfBand: true
+      })
+    ).resolves.toEqual({
+      state: Uint8Array.from([9]),
+      commit: Uint8Array.from([8]),
+      newEpoch: 4
+    });
+    expect(update).toHaveBeenCalledWith(state, {
+      name: 'Team',
+      external_senders: [{ user_id: 'bot', signing_public_key: [3] }],
+      ratchet_tree_out_of_band: true
+    });
+  });
+});
@@ -0,0 +1,56 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import { type CommitOutputResult, parseCommitOutput } from './mlsWasmCommits.js';
+import {
+  isRecordLike,
+  readBoolean,
+  readObjectArray,
+  readOptionalString,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type { GroupContextChange } from './types.js';
+
+export function readGroupContext(value: unknown): GroupContextChange {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM group context must be an object');
+  }
+
+  return {
+    name: readOptionalString(value, 'name') ?? null,
+    externalSenders: readObjectArray(value, 'external_senders').map(
+      (sender) => ({
+        userId: readString(sender, 'user_id'),
+        signingPublicKey: readUint8Array(sender, 'signing_public_key')
+      })
+    ),
+    ratchetTreeOutOfBand: readBoolean(value, 'ratchet_tree_out_of_band')
+  };
+}
+
+export async function wasmGroupContext(
+  stateBytes: Uint8Array
+): Promise<GroupContextChange> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_group_state_metadata(stateBytes);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM metadata response must be an object');
+  }
+  return readGroupContext(value['group_context']);
+}
+
+/** Commits `context` as the group's full replacement context. */
+export async function wasmUpdateGroupContext(
+  stateBytes: Uint8Array,
+  context: GroupContextChange
+): Promise<CommitOutputResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_update_group_context(stateBytes, {
+    name: context.name ?? undefined,
+    external_senders: context.externalSenders.map((sender) => ({
+      user_id: sender.userId,
+      signing_public_key: Array.from(sender.signingPublicKey)
+    })),
+    ratchet_tree_out_of_band: context.ratchetTreeOutOfBand
+  });
+  return parseCommitOutput(value, 'update-group-context');
+}
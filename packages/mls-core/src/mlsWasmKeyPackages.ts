@@ -0,0 +1,30 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import {
+  type GeneratedKeyPackage,
+  parseGeneratedKeyPackage
+} from './mlsWasmBridge.js';
+
+/** Generates a key package that may be used for more than one add. */
+export async function wasmGenerateLastResortKeyPackage(
+  credentialBundle: Uint8Array,
+  credentialPrivateKey: Uint8Array
+): Promise<GeneratedKeyPackage> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseGeneratedKeyPackage(
+    bindings.mls_generate_last_resort_key_package(
+      credentialBundle,
+      credentialPrivateKey
+    )
+  );
+}
+
+/** Returns which of `storedRefs` the welcome targets, or null for none. */
+export async function wasmMatchWelcomeKeyPackage(
+  welcomeBytes: Uint8Array,
+  storedRefs: string[]
+): Promise<string | null> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return (
+    bindings.mls_match_welcome_key_package(welcomeBytes, storedRefs) ?? null
+  );
+}
@@ -0,0 +1,69 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import { wasmDecryptMessageInto } from './mlsWasmMessages.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('mlsWasmMessages', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('passes the caller buffer through and reports the written length', async () => {
+    const decryptInto = vi.fn(
+      (_state: Uint8Array, _ciphertext: Uint8Array, out: Uint8Array) => {
+        out.set([104, 105]);
+        return {
+          status: 'decrypted',
+          epoch: 2,
+          sender_id: 'alice',
+          sender_signature_key: [7],
+          authenticated: true,
+          plaintext_len: 2,
+          authenticated_data: []
+        };
+      }
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_decrypt_message_into: decryptInto })
+    );
+
+    const out = new Uint8Array(16);
+    const result = await wasmDecryptMessageInto(
+      Uint8Array.from([1]),
+      Uint8Array.from([2]),
+      out
+    );
+
+    expect(decryptInto.mock.calls[0]?.[2]).toBe(out);
+    expect(result).toEqual({
+      status: 'decrypted',
+      epoch: 2,
+      senderId: 'alice',
+      senderSignatureKey: Uint8Array.from([7]),
+      authenticated: true,
+      plaintextLength: 2,
+      authenticatedData: Uint8Array.from([])
+    });
+    expect(Array.from(out.subarray(0, result.plaintextLength))).toEqual([
+      104, 105
+    ]);
+  });
+
+  it('surfaces the required size when the buffer is too small', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_decrypt_message_into: () => {
+          throw new Error('output buffer too small: need 5 bytes, got 1');
+        }
+      })
+    );
+
+    await expect(
+      wasmDecryptMessageInto(
+        Uint8Array.from([1]),
+        Uint8Array.from([2]),
+        new Uint8Array(1)
+      )
+    ).rejects.toThrow('need 5 bytes');
+  });
+});
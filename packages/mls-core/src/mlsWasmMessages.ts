@@ -0,0 +1,36 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import { readDecryptStatus } from './mlsWasmBridge.js';
+import {
+  isRecordLike,
+  readBoolean,
+  readNumber,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type { DecryptedIntoContent } from './types.js';
+
+/**
+ * Decrypts into `out` without allocating a plaintext array. Fails with the
+ * required size when `out` is too small.
+ */
+export async function wasmDecryptMessageInto(
+  stateBytes: Uint8Array,
+  ciphertext: Uint8Array,
+  out: Uint8Array
+): Promise<DecryptedIntoContent> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_decrypt_message_into(stateBytes, ciphertext, out);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM decrypt-into response must be an object');
+  }
+
+  return {
+    status: readDecryptStatus(value),
+    epoch: readNumber(value, 'epoch'),
+    senderId: readString(value, 'sender_id'),
+    senderSignatureKey: readUint8Array(value, 'sender_signature_key'),
+    authenticated: readBoolean(value, 'authenticated'),
+    plaintextLength: readNumber(value, 'plaintext_len'),
+    authenticatedData: readUint8Array(value, 'authenticated_data')
+  };
+}
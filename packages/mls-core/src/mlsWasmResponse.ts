@@ -0,0 +1,127 @@
+/** Field readers that validate untyped WASM responses. */
+
+export interface RecordLike {
+  [key: string]: unknown;
+}
+
+export function isRecordLike(value: unknown): value is RecordLike {
+  return typeof value === 'object' && value !== null;
+}
+
+export function readString(record: RecordLike, field: string): string {
+  const value = record[field];
+  if (typeof value !== 'string') {
+    throw new Error(`WASM response field '${field}' must be a string`);
+  }
+  return value;
+}
+
+export function readOptionalString(
+  record: RecordLike,
+  field: string
+): string | undefined {
+  const value = record[field];
+  if (value === undefined || value === null) {
+    return undefined;
+  }
+  return readString(record, field);
+}
+
+export function readStringArray(record: RecordLike, field: string): string[] {
+  const value = record[field];
+  if (
+    !Array.isArray(value) ||
+    !value.every((entry) => typeof entry === 'string')
+  ) {
+    throw new Error(`WASM response field '${field}' must be a string array`);
+  }
+  return value;
+}
+
+export function readNumber(record: RecordLike, field: string): number {
+  const value = record[field];
+  if (typeof value !== 'number' || !Number.isFinite(value)) {
+    throw new Error(`WASM response field '${field}' must be a finite number`);
+  }
+  return value;
+}
+
+export function readBoolean(record: RecordLike, field: string): boolean {
+  const value = record[field];
+  if (typeof value !== 'boolean') {
+    throw new Error(`WASM response field '${field}' must be a boolean`);
+  }
+  return value;
+}
+
+export function readUint8Array(record: RecordLike, field: string): Uint8Array {
+  return toUint8Array(record[field], field);
+}
+
+/** Reads a bare array of byte strings, such as a batch of ciphertexts. */
+export function readUint8ArrayList(
+  value: unknown,
+  context: string
+): Uint8Array[] {
+  if (!Array.isArray(value)) {
+    throw new Error(`WASM ${context} response must be an array`);
+  }
+  return value.map((entry: unknown, index) =>
+    toUint8Array(entry, `${context}[${index}]`)
+  );
+}
+
+function toUint8Array(value: unknown, field: string): Uint8Array {
+  if (value instanceof Uint8Array) {
+    return value;
+  }
+
+  if (Array.isArray(value)) {
+    for (const entry of value) {
+      const isByte =
+        typeof entry === 'number' &&
+        Number.isInteger(entry) &&
+        entry >= 0 &&
+        entry <= 255;
+
+      if (!isByte) {
+        throw new Error(
+          `WASM response field '${field}' array entries must be integers between 0 and 255`
+        );
+      }
+    }
+
+    return Uint8Array.from(value);
+  }
+
+  if (value instanceof ArrayBuffer) {
+    return new Uint8Array(value);
+  }
+
+  if (ArrayBuffer.isView(value)) {
+    return new Uint8Array(value.buffer, value.byteOffset, value.byteLength);
+  }
+
+  throw new Error(
+    `WASM response field '${field}' must be Uint8Array-compatible binary data`
+  );
+}
+
+export function readObjectArray(
+  record: RecordLike,
+  field: string
+): RecordLike[] {
+  const value = record[field];
+  if (!Array.isArray(value)) {
+    throw new Error(`WASM response field '${field}' must be an array`);
+  }
+
+  const output: RecordLike[] = [];
+  for (const item of value) {
+    if (!isRecordLike(item)) {
+      throw new Error(`WASM response field '${field}' entries must be objects`);
+    }
+    output.push(item);
+  }
+  return output;
+}
@@ -0,0 +1,107 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import { base64ToBytes } from './base64.js';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  wasmCompareSafetyNumber,
+  wasmDeriveAttachmentKey,
+  wasmDeriveBackupKey,
+  wasmListExternalPsks
+} from './mlsWasmSecrets.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('mlsWasmSecrets', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('passes the decoded authenticator and parses the safety number', async () => {
+    const compare = vi.fn(
+      (_state: Uint8Array, _otherAuthenticator: Uint8Array) => ({
+        matches: false,
+        safety_number: '01234 56789'
+      })
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_compare_safety_number: compare })
+    );
+
+    const state = Uint8Array.from([1]);
+    const other = base64ToBytes('AQID', 'Epoch authenticator');
+    await expect(wasmCompareSafetyNumber(state, other)).resolves.toEqual({
+      matches: false,
+      safetyNumber: '01234 56789'
+    });
+    expect(compare).toHaveBeenCalledWith(state, Uint8Array.from([1, 2, 3]));
+  });
+
+  it('rejects a safety-number response without a match flag', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_compare_safety_number: () => ({ safety_number: '01234' })
+      })
+    );
+
+    await expect(
+      wasmCompareSafetyNumber(Uint8Array.from([1]), Uint8Array.from([2]))
+    ).rejects.toThrow("WASM response field 'matches' must be a boolean");
+  });
+
+  it('derives the backup key from the current state', async () => {
+    const deriveBackupKey = vi.fn((_state: Uint8Array) =>
+      Uint8Array.from([4, 5, 6])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_derive_backup_key: deriveBackupKey })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(wasmDeriveBackupKey(state)).resolves.toEqual(
+      Uint8Array.from([4, 5, 6])
+    );
+    expect(deriveBackupKey).toHaveBeenCalledWith(state);
+  });
+
+  it('derives the attachment key from the shipped nonce', async () => {
+    const deriveAttachmentKey = vi.fn(
+      (_state: Uint8Array, _nonce: Uint8Array) => Uint8Array.from([7, 8, 9])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_derive_attachment_key: deriveAttachmentKey
+      })
+    );
+
+    const state = Uint8Array.from([1]);
+    const nonce = Uint8Array.from([2, 3]);
+    await expect(wasmDeriveAttachmentKey(state, nonce)).resolves.toEqual(
+      Uint8Array.from([7, 8, 9])
+    );
+    expect(deriveAttachmentKey).toHaveBeenCalledWith(state, nonce);
+  });
+
+  it('rejects an authenticator that is not base64', () => {
+    expect(() => base64ToBytes('not base64!', 'Epoch authenticator')).toThrow(
+      'Epoch authenticator must be valid base64'
+    );
+  });
+
+  it('lists external PSK ids and rejects non-string entries', async () => {
+    const spy = vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings');
+    spy.mockResolvedValue(
+      createPrimitiveBindings({
+        mls_list_external_psks: () => ['backup', 'pairing']
+      })
+    );
+    await expect(wasmListExternalPsks(Uint8Array.from([1]))).resolves.toEqual([
+      'backup',
+      'pairing'
+    ]);
+
+    spy.mockResolvedValue(
+      createPrimitiveBindings({ mls_list_external_psks: () => [1] })
+    );
+    await expect(wasmListExternalPsks(Uint8Array.from([1]))).rejects.toThrow(
+      'WASM list-external-psks response must be a string array'
+    );
+  });
+});
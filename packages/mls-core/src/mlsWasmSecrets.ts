@@ -0,0 +1,75 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import { isRecordLike, readBoolean, readString } from './mlsWasmResponse.js';
+import type { SafetyNumber } from './types.js';
+
+export async function wasmEpochAuthenticator(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_epoch_authenticator(stateBytes);
+}
+
+export async function wasmDeriveBackupKey(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_derive_backup_key(stateBytes);
+}
+
+export async function wasmDeriveAttachmentKey(
+  stateBytes: Uint8Array,
+  nonce: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_derive_attachment_key(stateBytes, nonce);
+}
+
+export async function wasmCompareSafetyNumber(
+  stateBytes: Uint8Array,
+  otherAuthenticator: Uint8Array
+): Promise<SafetyNumber> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_compare_safety_number(
+    stateBytes,
+    otherAuthenticator
+  );
+  if (!isRecordLike(value)) {
+    throw new Error('WASM safety-number response must be an object');
+  }
+
+  return {
+    matches: readBoolean(value, 'matches'),
+    safetyNumber: readString(value, 'safety_number')
+  };
+}
+
+export async function wasmAddExternalPsk(
+  stateBytes: Uint8Array,
+  pskId: string,
+  secret: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_add_external_psk(stateBytes, pskId, secret);
+}
+
+export async function wasmRemoveExternalPsk(
+  stateBytes: Uint8Array,
+  pskId: string
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_remove_external_psk(stateBytes, pskId);
+}
+
+export async function wasmListExternalPsks(
+  stateBytes: Uint8Array
+): Promise<string[]> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_list_external_psks(stateBytes);
+  if (
+    !Array.isArray(value) ||
+    !value.every((pskId) => typeof pskId === 'string')
+  ) {
+    throw new Error('WASM list-external-psks response must be a string array');
+  }
+  return value;
+}
@@ -0,0 +1,149 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+import * as mlsWasmBackend from './mlsWasmBackend.js';
+import {
+  wasmEpochsBehind,
+  wasmGroupMetadata,
+  wasmMarkReadOnly,
+  wasmSetGroupMetadata,
+  wasmSetGroupPolicy,
+  wasmSyncDigest,
+  wasmValidateGroupState
+} from './mlsWasmState.js';
+import { createPrimitiveBindings } from './test/wasmBindingsMock.js';
+
+describe('mlsWasmState', () => {
+  beforeEach(() => {
+    vi.restoreAllMocks();
+  });
+
+  it('reads local metadata entries into a map', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_group_metadata: () => [
+          { key: 'draft', value: 'hello' },
+          { key: 'topic', value: 'weekly' }
+        ]
+      })
+    );
+
+    const entries = await wasmGroupMetadata(Uint8Array.from([1]));
+
+    expect([...entries]).toEqual([
+      ['draft', 'hello'],
+      ['topic', 'weekly']
+    ]);
+  });
+
+  it('clears a metadata entry by omitting its value', async () => {
+    const setMetadata = vi.fn(
+      (_state: Uint8Array, _key: string, _value: string | undefined) =>
+        Uint8Array.from([2])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_set_group_metadata: setMetadata })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(wasmSetGroupMetadata(state, 'draft', null)).resolves.toEqual(
+      Uint8Array.from([2])
+    );
+    expect(setMetadata).toHaveBeenCalledWith(state, 'draft', undefined);
+  });
+
+  it('converts the signed epoch distance from a 64-bit value', async () => {
+    const epochsBehind = vi.fn(
+      (_state: Uint8Array, remoteEpoch: bigint) => remoteEpoch - 5n
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_epochs_behind: epochsBehind })
+    );
+
+    const state = Uint8Array.from([1]);
+    await expect(wasmEpochsBehind(state, 8)).resolves.toBe(3);
+    await expect(wasmEpochsBehind(state, 2)).resolves.toBe(-3);
+    expect(epochsBehind).toHaveBeenCalledWith(state, 8n);
+  });
+
+  it('returns the sync digest bytes', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_sync_digest: () => Uint8Array.from([9, 8, 7])
+      })
+    );
+
+    await expect(wasmSyncDigest(Uint8Array.from([1]))).resolves.toEqual(
+      Uint8Array.from([9, 8, 7])
+    );
+  });
+
+  it('replaces the policy while keeping the read-only flag', async () => {
+    const setPolicy = vi.fn((_state: Uint8Array, _policy: unknown) =>
+      Uint8Array.from([2])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_group_policy: () => ({
+          rekey_on_remove: false,
+          min_members: 0,
+          read_only: true,
+          allow_unknown_extensions: false
+        }),
+        mls_set_group_policy: setPolicy
+      })
+    );
+
+    const state = Uint8Array.from([1]);
+    await wasmSetGroupPolicy(state, {
+      rekeyOnRemove: true,
+      minMembers: 2,
+      allowUnknownExtensions: true
+    });
+    expect(setPolicy).toHaveBeenCalledWith(state, {
+      rekey_on_remove: true,
+      min_members: 2,
+      read_only: true,
+      allow_unknown_extensions: true
+    });
+  });
+
+  it('marks a state read-only without dropping other policy fields', async () => {
+    const setPolicy = vi.fn((_state: Uint8Array, _policy: unknown) =>
+      Uint8Array.from([2])
+    );
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({ mls_set_group_policy: setPolicy })
+    );
+
+    const state = Uint8Array.from([1]);
+    await wasmMarkReadOnly(state);
+    expect(setPolicy).toHaveBeenCalledWith(state, {
+      rekey_on_remove: false,
+      min_members: 0,
+      read_only: true,
+      allow_unknown_extensions: false
+    });
+  });
+
+  it('reports a pre-leaf-key state as valid but needing an upgrade', async () => {
+    vi.spyOn(mlsWasmBackend, 'loadMlsWasmPrimitiveBindings').mockResolvedValue(
+      createPrimitiveBindings({
+        mls_validate_group_state: () => ({
+          valid: true,
+          epoch: 4,
+          reason: null,
+          resync_required: false,
+          upgrade_required: true
+        })
+      })
+    );
+
+    await expect(
+      wasmValidateGroupState('group-1', Uint8Array.from([1]))
+    ).resolves.toEqual({
+      valid: true,
+      epoch: 4,
+      resyncRequired: false,
+      upgradeRequired: true
+    });
+  });
+});
@@ -0,0 +1,206 @@
+import { loadMlsWasmPrimitiveBindings } from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  readBoolean,
+  readNumber,
+  readObjectArray,
+  readOptionalString,
+  readString,
+  readUint8Array
+} from './mlsWasmResponse.js';
+import type {
+  GroupPolicy,
+  GroupStateReport,
+  PublicGroupState
+} from './types.js';
+
+interface ImportStateResult {
+  state: Uint8Array;
+  epoch: number;
+}
+
+function parseImportStateResult(value: unknown): ImportStateResult {
+  if (!isRecordLike(value)) {
+    throw new Error('WASM import-state response must be an object');
+  }
+
+  return {
+    state: readUint8Array(value, 'state'),
+    epoch: readNumber(value, 'epoch')
+  };
+}
+
+export async function wasmExportGroupState(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_export_group_state(stateBytes);
+}
+
+export async function wasmImportGroupState(
+  groupId: string,
+  stateBytes: Uint8Array
+): Promise<ImportStateResult> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return parseImportStateResult(
+    bindings.mls_import_group_state(groupId, stateBytes)
+  );
+}
+
+export async function wasmExportPublicGroupState(
+  stateBytes: Uint8Array
+): Promise<PublicGroupState> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_export_public_group_state(stateBytes);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM public-state response must be an object');
+  }
+
+  return {
+    groupId: readString(value, 'group_id'),
+    epoch: readNumber(value, 'epoch'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    memberCount: readNumber(value, 'member_count'),
+    members: readObjectArray(value, 'members').map((member) => ({
+      userId: readString(member, 'user_id'),
+      identity: readUint8Array(member, 'identity'),
+      leafIndex: readNumber(member, 'leaf_index')
+    }))
+  };
+}
+
+export async function wasmValidateGroupState(
+  groupId: string,
+  stateBytes: Uint8Array
+): Promise<GroupStateReport> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_validate_group_state(groupId, stateBytes);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM validate-state response must be an object');
+  }
+
+  const reason = readOptionalString(value, 'reason');
+  return {
+    valid: readBoolean(value, 'valid'),
+    epoch: readNumber(value, 'epoch'),
+    resyncRequired: readBoolean(value, 'resync_required'),
+    upgradeRequired: readBoolean(value, 'upgrade_required'),
+    ...(reason === undefined ? {} : { reason })
+  };
+}
+
+async function readGroupPolicyRecord(
+  stateBytes: Uint8Array
+): Promise<Record<string, unknown>> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const policy = bindings.mls_group_policy(stateBytes);
+  if (!isRecordLike(policy)) {
+    throw new Error('WASM group policy response must be an object');
+  }
+  return policy;
+}
+
+export async function wasmGroupPolicy(
+  stateBytes: Uint8Array
+): Promise<GroupPolicy> {
+  const policy = await readGroupPolicyRecord(stateBytes);
+  return {
+    rekeyOnRemove: readBoolean(policy, 'rekey_on_remove'),
+    minMembers: readNumber(policy, 'min_members'),
+    allowUnknownExtensions: readBoolean(policy, 'allow_unknown_extensions')
+  };
+}
+
+/** Replaces the state's policy, keeping its read-only flag. */
+export async function wasmSetGroupPolicy(
+  stateBytes: Uint8Array,
+  policy: GroupPolicy
+): Promise<Uint8Array> {
+  const current = await readGroupPolicyRecord(stateBytes);
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_set_group_policy(stateBytes, {
+    rekey_on_remove: policy.rekeyOnRemove,
+    min_members: policy.minMembers,
+    read_only: readBoolean(current, 'read_only'),
+    allow_unknown_extensions: policy.allowUnknownExtensions
+  });
+}
+
+/** Returns the state with its signing private key erased. */
+export async function wasmDeleteSigningKey(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_delete_signing_key(stateBytes);
+}
+
+/** Sets the state's read-only policy, keeping its other policy fields. */
+export async function wasmMarkReadOnly(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const policy = await readGroupPolicyRecord(stateBytes);
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_set_group_policy(stateBytes, {
+    ...policy,
+    read_only: true
+  });
+}
+
+export async function wasmPendingProposalCount(
+  stateBytes: Uint8Array
+): Promise<number> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_pending_proposal_count(stateBytes);
+}
+
+/** Returns `remoteEpoch` minus the local epoch; negative when ahead. */
+export async function wasmEpochsBehind(
+  stateBytes: Uint8Array,
+  remoteEpoch: number
+): Promise<number> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return Number(bindings.mls_epochs_behind(stateBytes, BigInt(remoteEpoch)));
+}
+
+export async function wasmSyncDigest(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_sync_digest(stateBytes);
+}
+
+export async function wasmMembersDigest(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_members_digest(stateBytes);
+}
+
+/** Sets a local-only metadata entry, or clears it when `value` is null. */
+export async function wasmSetGroupMetadata(
+  stateBytes: Uint8Array,
+  key: string,
+  value: string | null
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_set_group_metadata(stateBytes, key, value ?? undefined);
+}
+
+export async function wasmGroupMetadata(
+  stateBytes: Uint8Array
+): Promise<Map<string, string>> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_group_metadata(stateBytes);
+  if (!Array.isArray(value)) {
+    throw new Error('WASM group metadata response must be an array');
+  }
+
+  const entries = new Map<string, string>();
+  for (const entry of value) {
+    if (!isRecordLike(entry)) {
+      throw new Error('WASM group metadata entries must be objects');
+    }
+    entries.set(readString(entry, 'key'), readString(entry, 'value'));
+  }
+  return entries;
+}
@@ -0,0 +1,99 @@
+import { base64ToBytes } from './base64.js';
+import {
+  loadMlsWasmPrimitiveBindings,
+  resolveMlsBackendStatus
+} from './mlsWasmBackend.js';
+import {
+  isRecordLike,
+  readBoolean,
+  readNumber,
+  readString,
+  readStringArray
+} from './mlsWasmResponse.js';
+import type { WelcomeInfo, WelcomePublicInfo } from './types.js';
+
+export type WelcomeJoinStatus = 'join' | 'already_joined';
+
+export async function wasmInspectWelcome(
+  welcomeBytes: Uint8Array
+): Promise<WelcomeInfo> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_inspect_welcome(welcomeBytes);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM inspect-welcome response must be an object');
+  }
+
+  return {
+    groupId: readString(value, 'group_id'),
+    epoch: readNumber(value, 'epoch'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    keyPackageRefs: readStringArray(value, 'key_package_refs'),
+    hasRatchetTree: readBoolean(value, 'has_ratchet_tree')
+  };
+}
+
+/**
+ * Reads a base64 welcome's unencrypted framing so a delivery service can
+ * index it by group and epoch without being a member.
+ */
+export async function inspectWelcomePublic(
+  welcomeBase64: string
+): Promise<WelcomePublicInfo> {
+  const status = await resolveMlsBackendStatus();
+  if (!status.productionReady) {
+    throw new Error(`MLS backend not ready: ${status.reason}`);
+  }
+
+  const welcome = base64ToBytes(welcomeBase64, 'welcome');
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const value = bindings.mls_inspect_welcome(welcome);
+  if (!isRecordLike(value)) {
+    throw new Error('WASM inspect-welcome response must be an object');
+  }
+
+  return {
+    groupId: readString(value, 'group_id'),
+    epoch: readNumber(value, 'epoch'),
+    ciphersuite: readNumber(value, 'ciphersuite'),
+    encryptedGroupInfoPresent: readBoolean(
+      value,
+      'encrypted_group_info_present'
+    ),
+    joinerRefs: readStringArray(value, 'key_package_refs')
+  };
+}
+
+export async function wasmWelcomeJoinStatus(
+  stateBytes: Uint8Array,
+  welcomeBytes: Uint8Array
+): Promise<WelcomeJoinStatus> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  const status = bindings.mls_welcome_join_status(stateBytes, welcomeBytes);
+  if (status !== 'join' && status !== 'already_joined') {
+    throw new Error(`WASM welcome join status is unknown: '${String(status)}'`);
+  }
+  return status;
+}
+
+export async function wasmResendWelcome(
+  stateBytes: Uint8Array,
+  leafIndex: number
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_resend_welcome(stateBytes, leafIndex);
+}
+
+export async function wasmExportRatchetTree(
+  stateBytes: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_export_ratchet_tree(stateBytes);
+}
+
+export async function wasmAttachRatchetTree(
+  welcomeBytes: Uint8Array,
+  ratchetTree: Uint8Array
+): Promise<Uint8Array> {
+  const bindings = await loadMlsWasmPrimitiveBindings();
+  return bindings.mls_attach_ratchet_tree(welcomeBytes, ratchetTree);
+}
@@ -0,0 +1,25 @@
+import { describe, expect, it } from 'vitest';
+import { MlsStorage } from './storage.js';
+
+describe('MlsStorage', () => {
+  it('creates namespaced views over one storage', () => {
+    const storage = new MlsStorage();
+    const work = storage.withNamespace('work');
+
+    expect(work).toBeInstanceOf(MlsStorage);
+    expect(work).not.toBe(storage);
+    expect(work.withNamespace('inbox')).toBeInstanceOf(MlsStorage);
+  });
+
+  it('rejects empty or separator-bearing namespaces', () => {
+    const storage = new MlsStorage();
+
+    expect(() => storage.withNamespace('')).toThrow(/Invalid storage namespace/);
+    expect(() => storage.withNamespace('a\u001fb')).toThrow(
+      /Invalid storage namespace/
+    );
+    expect(() => storage.withNamespace('a/b')).toThrow(
+      /Invalid storage namespace/
+    );
+  });
+});
@@ -8,6 +8,10 @@ import type { LocalKeyPackage, LocalMlsState, MlsCredential } from './types.js';
 
 const DB_NAME = 'tearleads-mls';
 const DB_VERSION = 1;
+/** Joins a namespace to a record key; never produced by base64 ids or hex refs. */
+const NAMESPACE_SEPARATOR = '\u001f';
+/** Joins nested namespaces, so namespace names may not contain it. */
+const NESTED_NAMESPACE_SEPARATOR = '/';
 
 interface MlsDbSchema extends DBSchema {
   credentials: {
@@ -30,18 +34,54 @@ interface MlsDbSchema extends DBSchema {
   };
 }
 
+interface SharedConnection {
+  db: IDBPDatabase<MlsDbSchema> | null;
+  initPromise: Promise<IDBPDatabase<MlsDbSchema>> | null;
+}
+
 export class MlsStorage {
-  private db: IDBPDatabase<MlsDbSchema> | null = null;
-  private initPromise: Promise<IDBPDatabase<MlsDbSchema>> | null = null;
+  private connection: SharedConnection = { db: null, initPromise: null };
+  private namespace = '';
+  private ownsConnection = true;
+
+  /**
+   * Returns a view over the same database whose records are keyed under
+   * `namespace`, so several identities can share one persistence layer.
+   *
+   * Views never see records from other namespaces or from the root storage,
+   * and the root storage never sees namespaced records. Isolation is by key
+   * only: every view shares the database and can be read by same-origin code.
+   * Closing a view is a no-op; close the root storage to release the connection.
+   *
+   * Names may not contain `/`, which joins nested views: otherwise `a/b` and
+   * `a` then `b` would share records.
+   */
+  withNamespace(namespace: string): MlsStorage {
+    if (
+      !namespace ||
+      namespace.includes(NAMESPACE_SEPARATOR) ||
+      namespace.includes(NESTED_NAMESPACE_SEPARATOR)
+    ) {
+      throw new Error(`Invalid storage namespace: ${JSON.stringify(namespace)}`);
+    }
+
+    const view = new MlsStorage();
+    view.connection = this.connection;
+    view.namespace = this.namespace
+      ? `${this.namespace}${NESTED_NAMESPACE_SEPARATOR}${namespace}`
+      : namespace;
+    view.ownsConnection = false;
+    return view;
+  }
 
   async init(): Promise<void> {
-    if (this.db) return;
-    if (this.initPromise) {
-      await this.initPromise;
+    if (this.connection.db) return;
+    if (this.connection.initPromise) {
+      await this.connection.initPromise;
       return;
     }
 
-    this.initPromise = openDB<MlsDbSchema>(DB_NAME, DB_VERSION, {
+    this.connection.initPromise = openDB<MlsDbSchema>(DB_NAME, DB_VERSION, {
       upgrade(db) {
         // Credentials store - one per user
         if (!db.objectStoreNames.contains('credentials')) {
@@ -66,72 +106,119 @@ export class MlsStorage {
       }
     });
 
-    this.db = await this.initPromise;
+    this.connection.db = await this.connection.initPromise;
   }
 
   private async getDb(): Promise<IDBPDatabase<MlsDbSchema>> {
-    if (!this.db) {
+    if (!this.connection.db) {
       await this.init();
     }
-    if (!this.db) {
+    if (!this.connection.db) {
       throw new Error('Failed to initialize MLS storage');
     }
-    return this.db;
+    return this.connection.db;
+  }
+
+  private scoped(key: string): string {
+    return this.namespace
+      ? `${this.namespace}${NAMESPACE_SEPARATOR}${key}`
+      : key;
+  }
+
+  /** Returns the unscoped key when `key` belongs to this view, else null. */
+  private unscoped(key: string): string | null {
+    if (!this.namespace) {
+      return key.includes(NAMESPACE_SEPARATOR) ? null : key;
+    }
+
+    const prefix = `${this.namespace}${NAMESPACE_SEPARATOR}`;
+    return key.startsWith(prefix) ? key.slice(prefix.length) : null;
   }
 
   // Credential operations
 
   async getCredential(userId: string): Promise<MlsCredential | undefined> {
     const db = await this.getDb();
-    return db.get('credentials', userId);
+    const stored = await db.get('credentials', this.scoped(userId));
+    return stored && { ...stored, userId };
   }
 
   async saveCredential(credential: MlsCredential): Promise<void> {
     const db = await this.getDb();
-    await db.put('credentials', credential);
+    await db.put('credentials', {
+      ...credential,
+      userId: this.scoped(credential.userId)
+    });
+  }
+
+  async deleteCredential(userId: string): Promise<void> {
+    const db = await this.getDb();
+    await db.delete('credentials', this.scoped(userId));
   }
 
   // Key package operations
 
   async getKeyPackage(ref: string): Promise<LocalKeyPackage | undefined> {
     const db = await this.getDb();
-    return db.get('keyPackages', ref);
+    const stored = await db.get('keyPackages', this.scoped(ref));
+    return stored && { ...stored, ref };
   }
 
   async saveKeyPackage(keyPackage: LocalKeyPackage): Promise<void> {
     const db = await this.getDb();
-    await db.put('keyPackages', keyPackage);
+    await db.put('keyPackages', {
+      ...keyPackage,
+      ref: this.scoped(keyPackage.ref)
+    });
+  }
+
+  /** Lists refs of every key package whose private key is still held. */
+  async listKeyPackageRefs(): Promise<string[]> {
+    const db = await this.getDb();
+    const keys = await db.getAllKeys('keyPackages');
+    return keys.flatMap((key) => {
+      const ref = this.unscoped(key);
+      return ref === null ? [] : [ref];
+    });
   }
 
   async deleteKeyPackage(ref: string): Promise<void> {
     const db = await this.getDb();
-    await db.delete('keyPackages', ref);
+    await db.delete('keyPackages', this.scoped(ref));
   }
 
   // Group state operations
 
   async getAllGroupStates(): Promise<LocalMlsState[]> {
     const db = await this.getDb();
-    return db.getAllFromIndex('groupStates', 'byUpdatedAt');
+    const states = await db.getAllFromIndex('groupStates', 'byUpdatedAt');
+    return states.flatMap((state) => {
+      const groupId = this.unscoped(state.groupId);
+      return groupId === null ? [] : [{ ...state, groupId }];
+    });
   }
 
   async saveGroupState(state: LocalMlsState): Promise<void> {
     const db = await this.getDb();
-    await db.put('groupStates', state);
+    await db.put('groupStates', {
+      ...state,
+      groupId: this.scoped(state.groupId)
+    });
   }
 
   async deleteGroupState(groupId: string): Promise<void> {
     const db = await this.getDb();
-    await db.delete('groupStates', groupId);
+    await db.delete('groupStates', this.scoped(groupId));
   }
 
   // Close connection
 
   close(): void {
-    if (this.db) {
-      this.db.close();
-      this.db = null;
-      this.initPromise = null;
+    if (!this.ownsConnection) return;
+    if (this.connection.db) {
+      this.connection.db.close();
+      this.connection.db = null;
+      this.connection.initPromise = null;
     }
   }
 }
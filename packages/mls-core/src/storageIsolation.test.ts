@@ -0,0 +1,78 @@
+import 'fake-indexeddb/auto';
+import { afterEach, describe, expect, it } from 'vitest';
+import { MlsStorage } from './storage.js';
+import type { LocalKeyPackage, LocalMlsState } from './types.js';
+
+function keyPackage(ref: string): LocalKeyPackage {
+  return {
+    ref,
+    keyPackage: Uint8Array.from([1]),
+    privateKey: Uint8Array.from([2]),
+    createdAt: 1
+  };
+}
+
+function groupState(groupId: string, epoch: number): LocalMlsState {
+  return {
+    groupId,
+    serializedState: Uint8Array.from([epoch]),
+    epoch,
+    updatedAt: epoch
+  };
+}
+
+describe('MlsStorage namespaces over IndexedDB', () => {
+  const root = new MlsStorage();
+
+  afterEach(() => {
+    root.close();
+  });
+
+  it('keeps credentials, key packages and group states apart', async () => {
+    const work = root.withNamespace('work');
+    const home = root.withNamespace('home');
+    await root.init();
+
+    await work.saveCredential({
+      credentialBundle: Uint8Array.from([1]),
+      privateKey: Uint8Array.from([2]),
+      userId: 'alice',
+      createdAt: 1
+    });
+    await work.saveKeyPackage(keyPackage('kp-work'));
+    await home.saveKeyPackage(keyPackage('kp-home'));
+    await work.saveGroupState(groupState('group-1', 1));
+    await home.saveGroupState(groupState('group-1', 2));
+
+    expect((await work.getCredential('alice'))?.userId).toBe('alice');
+    expect(await home.getCredential('alice')).toBeUndefined();
+    expect(await root.getCredential('alice')).toBeUndefined();
+
+    expect(await work.listKeyPackageRefs()).toEqual(['kp-work']);
+    expect(await home.listKeyPackageRefs()).toEqual(['kp-home']);
+    expect(await root.listKeyPackageRefs()).toEqual([]);
+
+    expect(
+      (await work.getAllGroupStates()).map((state) => state.epoch)
+    ).toEqual([1]);
+    expect(
+      (await home.getAllGroupStates()).map((state) => state.epoch)
+    ).toEqual([2]);
+    expect(await root.getAllGroupStates()).toEqual([]);
+
+    await home.deleteGroupState('group-1');
+    expect(await work.getAllGroupStates()).toHaveLength(1);
+  });
+
+  it('hides nested view records from the parent view', async () => {
+    const team = root.withNamespace('team');
+    const inbox = team.withNamespace('inbox');
+    await root.init();
+
+    await inbox.saveKeyPackage(keyPackage('kp-inbox'));
+
+    expect(await inbox.listKeyPackageRefs()).toEqual(['kp-inbox']);
+    expect(await team.listKeyPackageRefs()).toEqual([]);
+    expect(await team.getKeyPackage('kp-inbox')).toBeUndefined();
+  });
+});
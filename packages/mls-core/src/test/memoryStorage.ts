@@ -0,0 +1,89 @@
+import { MlsClient } from '../mls.js';
+import type { MlsBackendStatus } from '../mlsWasmBackend.js';
+import { MlsStorage } from '../storage.js';
+import type {
+  LocalKeyPackage,
+  LocalMlsState,
+  MlsCredential
+} from '../types.js';
+
+/** Backend status reported by a loaded production WASM module. */
+export const READY_BACKEND_STATUS: MlsBackendStatus = {
+  backend: 'wasm',
+  wasmModuleLoaded: true,
+  backendName: 'mock-mls',
+  backendVersion: '0.0.0',
+  productionReady: true,
+  reason: 'Rust/WASM MLS backend is active.'
+};
+
+/** MlsStorage kept in plain maps, for client tests without IndexedDB. */
+export class MemoryMlsStorage extends MlsStorage {
+  readonly credentials = new Map<string, MlsCredential>();
+  readonly keyPackages = new Map<string, LocalKeyPackage>();
+  readonly groupStates = new Map<string, LocalMlsState>();
+
+  override async init(): Promise<void> {}
+
+  override async getCredential(
+    userId: string
+  ): Promise<MlsCredential | undefined> {
+    return this.credentials.get(userId);
+  }
+
+  override async saveCredential(credential: MlsCredential): Promise<void> {
+    this.credentials.set(credential.userId, credential);
+  }
+
+  override async deleteCredential(userId: string): Promise<void> {
+    this.credentials.delete(userId);
+  }
+
+  override async getKeyPackage(
+    ref: string
+  ): Promise<LocalKeyPackage | undefined> {
+    return this.keyPackages.get(ref);
+  }
+
+  override async saveKeyPackage(keyPackage: LocalKeyPackage): Promise<void> {
+    this.keyPackages.set(keyPackage.ref, keyPackage);
+  }
+
+  override async listKeyPackageRefs(): Promise<string[]> {
+    return Array.from(this.keyPackages.keys());
+  }
+
+  override async deleteKeyPackage(ref: string): Promise<void> {
+    this.keyPackages.delete(ref);
+  }
+
+  override async getAllGroupStates(): Promise<LocalMlsState[]> {
+    return Array.from(this.groupStates.values());
+  }
+
+  override async saveGroupState(state: LocalMlsState): Promise<void> {
+    this.groupStates.set(state.groupId, state);
+  }
+
+  override async deleteGroupState(groupId: string): Promise<void> {
+    this.groupStates.delete(groupId);
+  }
+
+  override close(): void {}
+}
+
+/** Initializes a client over `storage` holding a credential for `userId`. */
+export async function createClientWithCredential(
+  storage: MemoryMlsStorage,
+  userId = 'alice'
+): Promise<MlsClient> {
+  await storage.saveCredential({
+    credentialBundle: Uint8Array.from([1]),
+    privateKey: Uint8Array.from([2]),
+    userId,
+    createdAt: 1
+  });
+  const client = new MlsClient(userId, storage);
+  await client.init();
+  return client;
+}
@@ -0,0 +1,235 @@
+import type { MlsWasmPrimitiveBindings } from '../mlsWasmBackend.js';
+
+/** Builds ready-to-use WASM bindings that return minimal valid responses. */
+export function createPrimitiveBindings(
+  overrides: Partial<MlsWasmPrimitiveBindings> = {}
+): MlsWasmPrimitiveBindings {
+  return {
+    mls_backend_name: () => 'mock-mls',
+    mls_backend_version: () => '0.0.0-test',
+    mls_backend_ready: () => true,
+    mls_backend_notice: () => 'ready',
+    mls_init_with_options: () => undefined,
+    mls_generate_credential: () => ({
+      user_id: 'mock-user',
+      credential_bundle: [1],
+      private_key: [2],
+      created_at_ms: 1
+    }),
+    mls_generate_key_package: () => ({
+      key_package: [1],
+      key_package_ref: 'mock-ref',
+      private_key: [2],
+      created_at_ms: 1
+    }),
+    mls_generate_last_resort_key_package: () => ({
+      key_package: [3],
+      key_package_ref: 'mock-last-resort-ref',
+      private_key: [4],
+      created_at_ms: 1
+    }),
+    mls_match_welcome_key_package: () => undefined,
+    mls_create_group: () => Uint8Array.from([]),
+    mls_create_solo_group: () => Uint8Array.from([]),
+    mls_join_group: () => ({
+      state: Uint8Array.from([]),
+      group_id: 'group-1',
+      epoch: 1,
+      ciphersuite: 3,
+      members: [{ user_id: 'alice', leaf_index: 0 }]
+    }),
+    mls_add_member: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      welcome: Uint8Array.from([]),
+      group_info: Uint8Array.from([]),
+      new_epoch: 1
+    }),
+    mls_add_members: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      welcomes: [],
+      group_info: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_add_members_bundle: () => ({
+      state: Uint8Array.from([]),
+      bundle: Uint8Array.from([]),
+      new_epoch: 1
+    }),
+    mls_unpack_commit_bundle: () => ({
+      commit: Uint8Array.from([]),
+      welcomes: []
+    }),
+    mls_remove_member: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      new_epoch: 1
+    }),
+    mls_replace_member: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      welcome: Uint8Array.from([]),
+      group_info: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_process_commit: () => ({
+      state: Uint8Array.from([]),
+      new_epoch: 1,
+      group_context_changed: null
+    }),
+    mls_preview_commit: () => ({
+      new_epoch: 2,
+      added: [],
+      removed: [{ user_id: 'mock-user', leaf_index: 1 }],
+      removes_self: true
+    }),
+    mls_update_group_context: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_self_update: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_should_rekey: () => false,
+    mls_encrypt_message: () => Uint8Array.from([]),
+    mls_reencrypt_history: () => [],
+    mls_decrypt_message: () => ({
+      status: 'decrypted',
+      epoch: 1,
+      sender_id: 'mock-user',
+      sender_signature_key: Uint8Array.from([]),
+      authenticated: true,
+      plaintext: Uint8Array.from([]),
+      authenticated_data: Uint8Array.from([]),
+      framing: Uint8Array.from([])
+    }),
+    mls_decrypt_message_into: () => ({
+      status: 'decrypted',
+      epoch: 1,
+      sender_id: 'mock-user',
+      sender_signature_key: Uint8Array.from([]),
+      authenticated: true,
+      plaintext_len: 0,
+      authenticated_data: Uint8Array.from([])
+    }),
+    mls_catch_up: () => ({
+      state: Uint8Array.from([]),
+      epoch: 1,
+      messages: [],
+      pending: []
+    }),
+    mls_group_state_metadata: () => ({
+      group_id: 'mock-group',
+      epoch: 1,
+      self_user_id: 'mock-user',
+      members: [],
+      group_context: {
+        name: null,
+        external_senders: [],
+        ratchet_tree_out_of_band: false
+      }
+    }),
+    mls_members_digest: () => Uint8Array.from([]),
+    mls_pending_proposal_count: () => 0,
+    mls_epochs_behind: () => 0n,
+    mls_sync_digest: () => Uint8Array.from([]),
+    mls_group_policy: () => ({
+      rekey_on_remove: false,
+      min_members: 0,
+      read_only: false,
+      allow_unknown_extensions: false
+    }),
+    mls_set_group_policy: () => Uint8Array.from([]),
+    mls_delete_signing_key: () => Uint8Array.from([]),
+    mls_set_group_metadata: () => Uint8Array.from([]),
+    mls_group_metadata: () => [],
+    mls_key_package_expired: () => false,
+    mls_validate_key_package: () => ({
+      valid: true,
+      key_package_ref: 'mock-ref',
+      identity: Uint8Array.from([]),
+      ciphersuite: 3
+    }),
+    mls_inspect_key_package: () => ({
+      key_package_ref: 'mock-ref',
+      ciphersuite: 3,
+      user_id: 'mock-user',
+      identity: '',
+      signing_public_key: '',
+      init_key: '',
+      leaf_node_encryption_key: '',
+      lifetime: { not_before: 0, not_after: 0 },
+      last_resort: false
+    }),
+    mls_member_credentials: () => [],
+    mls_sign_blob: () => Uint8Array.from([]),
+    mls_verify_blob: () => false,
+    mls_add_external_psk: () => Uint8Array.from([]),
+    mls_remove_external_psk: () => Uint8Array.from([]),
+    mls_list_external_psks: () => [],
+    mls_commit_psk: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_store_proposal: () => ({
+      state: Uint8Array.from([]),
+      proposal_ref: 'mock-proposal-ref'
+    }),
+    mls_commit_received_proposals: () => ({
+      state: Uint8Array.from([]),
+      commit: Uint8Array.from([]),
+      welcomes: [],
+      group_info: Uint8Array.from([]),
+      new_epoch: 2
+    }),
+    mls_epoch_authenticator: () => Uint8Array.from([1, 2, 3]),
+    mls_derive_backup_key: () => Uint8Array.from([4, 5, 6]),
+    mls_derive_attachment_key: () => Uint8Array.from([7, 8, 9]),
+    mls_compare_safety_number: () => ({
+      matches: true,
+      safety_number: '12345 67890'
+    }),
+    mls_export_group_state: () => Uint8Array.from([]),
+    mls_import_group_state: () => ({
+      state: Uint8Array.from([]),
+      epoch: 1
+    }),
+    mls_export_public_group_state: () => ({
+      group_id: 'mock-group',
+      epoch: 1,
+      ciphersuite: 3,
+      member_count: 0,
+      members: []
+    }),
+    mls_validate_group_state: () => ({
+      valid: true,
+      epoch: 1,
+      reason: null,
+      resync_required: false,
+      upgrade_required: false
+    }),
+    mls_force_leave: () => ({
+      group_id: 'mock-group',
+      epoch: 1,
+      proposal: Uint8Array.from([])
+    }),
+    mls_inspect_welcome: () => ({
+      group_id: 'mock-group',
+      epoch: 1,
+      ciphersuite: 3,
+      key_package_refs: ['mock-ref'],
+      has_ratchet_tree: true,
+      encrypted_group_info_present: true
+    }),
+    mls_welcome_join_status: () => 'already_joined',
+    mls_resend_welcome: () => Uint8Array.from([]),
+    mls_export_ratchet_tree: () => Uint8Array.from([]),
+    mls_attach_ratchet_tree: () => Uint8Array.from([]),
+    ...overrides
+  };
+}
@@ -1,9 +1,25 @@
+import type { DecryptStatus } from './mlsWasmBridge.js';
+
+/**
+ * Application messages counted since the group entered `currentEpoch`.
+ * `received` counts only `decrypted` results: `sender_not_in_group` messages
+ * carry no verifiable signature or plaintext, so they are left out.
+ */
+export interface MessageCounters {
+  sent: number;
+  received: number;
+  currentEpoch: number;
+}
+
 /** Local MLS state stored in IndexedDB. */
 export interface LocalMlsState {
   groupId: string;
   serializedState: Uint8Array;
   epoch: number;
   updatedAt: number;
+  messageCounters?: MessageCounters;
+  /** Set aside as corrupted; kept for inspection but never loaded. */
+  quarantined?: boolean;
 }
 
 /** MLS credential stored locally. */
@@ -20,4 +36,230 @@ export interface LocalKeyPackage {
   keyPackage: Uint8Array;
   privateKey: Uint8Array;
   createdAt: number;
+  /** Kept after joining so the package can serve more than one add. */
+  lastResort?: boolean;
+}
+
+/** Upload-time verdict from `validateKeyPackage`. */
+export interface KeyPackageValidation {
+  valid: boolean;
+  ref: string;
+  /** Credential identity bytes; empty when the package could not be parsed. */
+  identity: Uint8Array;
+  ciphersuite: number;
+  reason?: string;
+}
+
+/** Public key package contents from `inspectKeyPackage`; keys are base64. */
+export interface KeyPackageInfo {
+  ref: string;
+  ciphersuite: number;
+  userId: string;
+  /** Credential identity bytes behind `userId`. */
+  identity: string;
+  signatureKey: string;
+  initKey: string;
+  leafNodeEncryptionKey: string;
+  /** Validity window in Unix seconds. */
+  lifetime: { notBefore: number; notAfter: number };
+  lastResort: boolean;
+}
+
+/** Commit and per-member welcomes split out of a bundle by `unpackBundle`. */
+export interface CommitBundle {
+  commit: Uint8Array;
+  welcomes: { keyPackageRef: string; welcome: Uint8Array }[];
+}
+
+/** Secret-free group snapshot from `exportPublicGroupState`. */
+export interface PublicGroupState {
+  groupId: string;
+  epoch: number;
+  ciphersuite: number;
+  memberCount: number;
+  members: { userId: string; identity: Uint8Array; leafIndex: number }[];
+}
+
+/** Integrity verdict from `validateGroupState`. */
+export interface GroupStateReport {
+  valid: boolean;
+  /** Epoch recorded in the state; zero when it could not be decoded. */
+  epoch: number;
+  /** The state is unusable; quarantine the group and rejoin via a welcome. */
+  resyncRequired: boolean;
+  /**
+   * The state was saved before leaf keys existed. It still works for
+   * messaging, but must `selfUpdate` and send the commit before it can
+   * process other members' update commits.
+   */
+  upgradeRequired: boolean;
+  reason?: string;
+}
+
+/** Key package bytes published for others to add us. */
+export interface KeyPackageWithRef {
+  ref: string;
+  keyPackageBytes: Uint8Array;
+}
+
+export interface AddMembersOptions {
+  /**
+   * Attach an update path that rotates our leaf key in the same commit,
+   * giving the add post-compromise security at the cost of a larger commit
+   * and more work for every member. Adds skip it by default; removes and
+   * self-updates always carry one regardless.
+   */
+  forcePathUpdate?: boolean;
+}
+
+/** Commit produced locally, with the welcome for any added members. */
+export interface CommitResult {
+  commit: Uint8Array;
+  welcome?: Uint8Array;
+  groupInfo?: Uint8Array;
+  newEpoch?: number;
+}
+
+/** Welcome routed to one added key package. */
+export interface RecipientWelcome {
+  keyPackageRef: string;
+  epoch: number;
+  ciphersuite: number;
+  welcome: Uint8Array;
+}
+
+/**
+ * Commit that may add several members. `welcomes` has one entry per added
+ * key package, in input order, so the server can route each; they all carry
+ * the combined welcome that `welcome` also holds.
+ */
+export interface AddMembersResult extends CommitResult {
+  welcomes: RecipientWelcome[];
+}
+
+/** Application message opened by `decryptMessage`. */
+export interface DecryptedContent {
+  /**
+   * `own_message` marks a relay echo of our own message with empty plaintext.
+   * `sender_not_in_group` marks a message whose sender was removed, or whose
+   * leaf went to a new member, before it arrived. Its signature cannot be
+   * checked, so plaintext and sender fields are empty.
+   * `future_epoch` marks a message from an epoch within the client's tolerance
+   * that we have not reached; buffer it and retry after the pending commit.
+   */
+  status: DecryptStatus;
+  /** Epoch the message was sent in. */
+  epoch: number;
+  senderId: string;
+  /** Sender's leaf signature key; pin it to detect changes without an update. */
+  senderSignatureKey: Uint8Array;
+  /** False when no member vouches for the plaintext; never attribute it. */
+  authenticated: boolean;
+  plaintext: Uint8Array;
+  authenticatedData: Uint8Array;
+  /**
+   * Canonical message bytes for relays to forward, set only when requested
+   * and the signature verified; empty otherwise.
+   */
+  framing: Uint8Array;
+}
+
+/** Outcome of `decryptInto`; the plaintext is in the caller's buffer. */
+export interface DecryptedIntoContent
+  extends Omit<DecryptedContent, 'plaintext' | 'framing'> {
+  /** Plaintext bytes written to the start of the buffer. */
+  plaintextLength: number;
+}
+
+/** Outcome of replaying missed commits and messages with `catchUp`. */
+export interface CatchUpResult {
+  /** Epoch the group reached after merging every commit. */
+  epoch: number;
+  /** Processed application messages in the order they became readable. */
+  messages: DecryptedContent[];
+  /** Messages still ahead of `epoch`; keep them buffered for a later call. */
+  pending: Uint8Array[];
+}
+
+/** One step of a cursor-based export started with `exportStateBegin`. */
+export type ExportStateChunk =
+  | { done: false; groupId: string; state: Uint8Array }
+  | { done: true; credential: MlsCredential | null };
+
+/** Whether `joinGroup` installed the welcome or ignored a replay. */
+export type JoinGroupStatus = 'joined' | 'already_joined';
+
+/** Outcome of `joinGroup` with enough group info to render the conversation. */
+export interface JoinGroupResult {
+  status: JoinGroupStatus;
+  groupId: string;
+  epoch: number;
+  ciphersuite: number;
+  members: Map<string, number>; // userId -> leafIndex
+}
+
+/** Shared group context agreed through commits, e.g. the group's name. */
+export interface GroupContextChange {
+  name: string | null;
+  /** Non-members allowed to submit proposals to the group. */
+  externalSenders: { userId: string; signingPublicKey: Uint8Array }[];
+  /** Welcomes omit the member tree; see `exportRatchetTree`. */
+  ratchetTreeOutOfBand: boolean;
+}
+
+/**
+ * Local commit policy stored with a group's state. Observers are read-only
+ * through their client options, so read-only mode is not set here.
+ */
+export interface GroupPolicy {
+  /** Attach an update path to every removal. */
+  rekeyOnRemove: boolean;
+  /** Refuse removals leaving fewer members than this; 0 disables the check. */
+  minMembers: number;
+  /** Accept key packages advertising extensions this build does not know. */
+  allowUnknownExtensions: boolean;
+}
+
+/**
+ * Pre-shared key injected by `commitPsk`: an external one stored with
+ * `addExternalPsk`, or the secret of an epoch this group retained.
+ */
+export type PskReference =
+  | { type: 'external'; pskId: string }
+  | { type: 'resumption'; epoch: number };
+
+/** Outcome of `compareSafetyNumber`. */
+export interface SafetyNumber {
+  /** Both members hold the same epoch authenticator. */
+  matches: boolean;
+  /** Our authenticator as space-separated five-digit groups for display. */
+  safetyNumber: string;
+}
+
+/** Effect of a staged commit, reported by `previewCommit` before merging. */
+export interface CommitPreview {
+  newEpoch: number;
+  addedUserIds: string[];
+  removedUserIds: string[];
+  removesSelf: boolean;
+}
+
+/** Public header of a welcome, readable before joining. */
+export interface WelcomeInfo {
+  groupId: string;
+  epoch: number;
+  ciphersuite: number;
+  keyPackageRefs: string[];
+  /** False when the group sends its member tree out of band. */
+  hasRatchetTree: boolean;
+}
+
+/** Routing view of a welcome, read without any key package secrets. */
+export interface WelcomePublicInfo {
+  groupId: string;
+  epoch: number;
+  ciphersuite: number;
+  encryptedGroupInfoPresent: boolean;
+  /** Key package refs of the joiners the welcome is addressed to. */
+  joinerRefs: string[];
 }